@@ -66,21 +66,71 @@ fn test<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
     };
 
     tests::queue::test_nop(&mut ring, &test)?;
+    tests::queue::test_nop_inject_result(&mut ring, &test)?;
     tests::queue::test_queue_split(&mut ring, &test)?;
     tests::queue::test_debug_print(&mut ring, &test)?;
+    tests::queue::test_ring_raw_fd(&mut ring, &test)?;
+    tests::queue::test_completion_ready(&mut ring, &test)?;
     tests::queue::test_msg_ring_data(&mut ring, &test)?;
+    tests::queue::test_msg_ring_data_user_flags(&mut ring, &test)?;
     tests::queue::test_msg_ring_send_fd(&mut ring, &test)?;
+    tests::queue::test_msg_ring_send_fd_cqe_skip(&mut ring, &test)?;
 
     tests::queue::test_batch(&mut ring, &test)?;
+    tests::queue::test_batch_partial(&mut ring, &test)?;
+    tests::queue::test_submit_batch(&mut ring, &test)?;
+    tests::queue::test_submit_batch_sqpoll(&mut ring, &test)?;
+    tests::queue::test_flush_overflow(&mut ring, &test)?;
+    tests::queue::test_into_split(&mut ring, &test)?;
+    tests::queue::test_into_split_registered_ring_fd(&mut ring, &test)?;
+    tests::queue::test_sq_remaining(&mut ring, &test)?;
+    tests::queue::test_sq_in_place(&mut ring, &test)?;
+    tests::queue::test_skip_success(&mut ring, &test)?;
+
+    // setup
+    tests::setup::test_setup_entry_sizes(&mut ring, &test)?;
+    tests::setup::test_setup_single_issuer(&mut ring, &test)?;
+    tests::setup::test_setup_defer_taskrun(&mut ring, &test)?;
+    tests::setup::test_setup_coop_taskrun(&mut ring, &test)?;
+    tests::setup::test_setup_taskrun_flag(&mut ring, &test)?;
+    tests::setup::test_setup_r_disabled(&mut ring, &test)?;
+    tests::setup::test_setup_attach_wq(&mut ring, &test)?;
+    tests::setup::test_setup_submit_all(&mut ring, &test)?;
+    tests::setup::test_setup_no_mmap(&mut ring, &test)?;
+    tests::setup::test_setup_no_sqarray(&mut ring, &test)?;
+    tests::setup::test_setup_cqsize(&mut ring, &test)?;
+    tests::setup::test_setup_clamp(&mut ring, &test)?;
+    tests::setup::test_params_entries(&mut ring, &test)?;
+    tests::setup::test_params_features(&mut ring, &test)?;
+    tests::setup::test_setup_hybrid_iopoll(&mut ring, &test)?;
+    tests::setup::test_builder_entry_helpers(&mut ring, &test)?;
+    tests::setup::test_setup_dontfork(&mut ring, &test)?;
+    tests::setup::test_setup_sqpoll(&mut ring, &test)?;
+    tests::setup::test_sqpoll_enter_raw(&mut ring, &test)?;
+    tests::setup::test_sqpoll_need_wakeup(&mut ring, &test)?;
 
     // register
     tests::register::test_register_files_sparse(&mut ring, &test)?;
+    tests::register::test_register_files_sparse_update(&mut ring, &test)?;
+    tests::register::test_register_files_update_tag(&mut ring, &test)?;
+    tests::register::test_register_eventfd(&mut ring, &test)?;
+    tests::register::test_register_ring_fd(&mut ring, &test)?;
+    tests::register::test_register_iowq_max_workers(&mut ring, &test)?;
+    tests::register::test_register_iowq_aff(&mut ring, &test)?;
+    tests::register::test_register_restrictions(&mut ring, &test)?;
+    tests::register::test_register_napi(&mut ring, &test)?;
+    tests::register::test_register_probe(&mut ring, &test)?;
+    tests::register::test_capabilities(&mut ring, &test)?;
     tests::register_buffers::test_register_buffers(&mut ring, &test)?;
     tests::register_buffers::test_register_buffers_update(&mut ring, &test)?;
+    tests::register_buffers::test_register_buffer_slots(&mut ring, &test)?;
+    tests::register_buffers::test_fixed_from_buf(&mut ring, &test)?;
     tests::register_buf_ring::test_register_buf_ring(&mut ring, &test)?;
+    tests::register_buf_ring::test_register_pbuf_status(&mut ring, &test)?;
     tests::register_sync_cancel::test_register_sync_cancel(&mut ring, &test)?;
     tests::register_sync_cancel::test_register_sync_cancel_unsubmitted(&mut ring, &test)?;
     tests::register_sync_cancel::test_register_sync_cancel_any(&mut ring, &test)?;
+    tests::register_sync_cancel::test_register_sync_cancel_timeout(&mut ring, &test)?;
 
     // async cancellation
     tests::cancel::test_async_cancel_user_data(&mut ring, &test)?;
@@ -88,6 +138,8 @@ fn test<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
     tests::cancel::test_async_cancel_any(&mut ring, &test)?;
     tests::cancel::test_async_cancel_fd(&mut ring, &test)?;
     tests::cancel::test_async_cancel_fd_all(&mut ring, &test)?;
+    tests::cancel::test_async_cancel_fd_all_reads(&mut ring, &test)?;
+    tests::cancel::test_async_cancel_all_reads(&mut ring, &test)?;
 
     // fs
     tests::fs::test_file_write_read(&mut ring, &test)?;
@@ -96,16 +148,27 @@ fn test<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
     tests::fs::test_file_fsync(&mut ring, &test)?;
     tests::fs::test_file_fsync_file_range(&mut ring, &test)?;
     tests::fs::test_file_fallocate(&mut ring, &test)?;
+    tests::fs::test_file_fallocate_punch_hole(&mut ring, &test)?;
     tests::fs::test_file_openat2(&mut ring, &test)?;
+    tests::fs::test_file_openat2_resolve_beneath(&mut ring, &test)?;
     tests::fs::test_file_openat2_close_file_index(&mut ring, &test)?;
     tests::fs::test_file_openat_close_file_index(&mut ring, &test)?;
     tests::fs::test_file_close(&mut ring, &test)?;
     tests::fs::test_file_direct_write_read(&mut ring, &test)?;
     #[cfg(not(feature = "ci"))]
     tests::fs::test_statx(&mut ring, &test)?;
+    tests::fs::test_statx_buf(&mut ring, &test)?;
     tests::fs::test_file_splice(&mut ring, &test)?;
+    tests::fs::test_pipe_splice_move(&mut ring, &test)?;
+    tests::fs::test_pipe_tee(&mut ring, &test)?;
+    tests::fs::test_file_fadvise(&mut ring, &test)?;
     tests::fs::test_ftruncate(&mut ring, &test)?;
     tests::fs::test_fixed_fd_install(&mut ring, &test)?;
+    tests::fs::test_fixed_fd_install_pipe(&mut ring, &test)?;
+    tests::fs::test_xattr(&mut ring, &test)?;
+    tests::fs::test_path_ops(&mut ring, &test)?;
+    tests::fs::test_iopoll_read(&mut ring, &test)?;
+    tests::fs::test_hybrid_iopoll_read(&mut ring, &test)?;
 
     // timeout
     tests::timeout::test_timeout(&mut ring, &test)?;
@@ -114,7 +177,12 @@ fn test<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
     tests::timeout::test_timeout_update(&mut ring, &test)?;
     tests::timeout::test_timeout_cancel(&mut ring, &test)?;
     tests::timeout::test_timeout_abs(&mut ring, &test)?;
+    tests::timeout::test_timeout_abs_realtime(&mut ring, &test)?;
+    tests::timeout::test_link_timeout_abs(&mut ring, &test)?;
+    tests::timeout::test_timeout_multishot(&mut ring, &test)?;
     tests::timeout::test_timeout_submit_args(&mut ring, &test)?;
+    tests::timeout::test_timeout_submit_and_wait_timeout(&mut ring, &test)?;
+    tests::timeout::test_timeout_submit_and_wait_min(&mut ring, &test)?;
 
     // net
     tests::net::test_tcp_write_read(&mut ring, &test)?;
@@ -123,24 +191,36 @@ fn test<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
     tests::net::test_tcp_send_bundle(&mut ring, &test)?;
     tests::net::test_tcp_zero_copy_send_recv(&mut ring, &test)?;
     tests::net::test_tcp_zero_copy_send_fixed(&mut ring, &test)?;
+    tests::net::test_tcp_send_recv_poll_first(&mut ring, &test)?;
     tests::net::test_tcp_sendmsg_recvmsg(&mut ring, &test)?;
     tests::net::test_tcp_zero_copy_sendmsg_recvmsg(&mut ring, &test)?;
     tests::net::test_tcp_accept(&mut ring, &test)?;
     tests::net::test_tcp_accept_file_index(&mut ring, &test)?;
     tests::net::test_tcp_accept_multi(&mut ring, &test)?;
     tests::net::test_tcp_accept_multi_file_index(&mut ring, &test)?;
+    tests::net::test_tcp_accept_multi_file_alloc_range(&mut ring, &test)?;
     tests::net::test_tcp_connect(&mut ring, &test)?;
+    tests::net::test_tcp_connect_sockaddr(&mut ring, &test)?;
     tests::net::test_tcp_buffer_select(&mut ring, &test)?;
     tests::net::test_tcp_buffer_select_recvmsg(&mut ring, &test)?;
     tests::net::test_tcp_buffer_select_readv(&mut ring, &test)?;
     tests::net::test_tcp_recv_multi(&mut ring, &test)?;
+    tests::net::test_tcp_recv_multi_buf_ring(&mut ring, &test)?;
     tests::net::test_tcp_recv_bundle(&mut ring, &test)?;
     tests::net::test_tcp_recv_multi_bundle(&mut ring, &test)?;
 
     tests::net::test_tcp_shutdown(&mut ring, &test)?;
+    tests::net::test_tcp_shutdown_eof(&mut ring, &test)?;
+    tests::net::test_tcp_sockopt(&mut ring, &test)?;
     tests::net::test_socket(&mut ring, &test)?;
+    tests::net::test_socket_file_alloc_connect(&mut ring, &test)?;
+    tests::net::test_socket_bind_listen(&mut ring, &test)?;
+    tests::net::test_udp_recv_multi(&mut ring, &test)?;
+    tests::net::test_udp_recv_multi_with_buf_ring(&mut ring, &test)?;
     tests::net::test_udp_recvmsg_multishot(&mut ring, &test)?;
     tests::net::test_udp_recvmsg_multishot_trunc(&mut ring, &test)?;
+    tests::net::test_udp_sendto_recvfrom(&mut ring, &test)?;
+    tests::net::test_tcp_msghdr(&mut ring, &test)?;
     tests::net::test_udp_send_with_dest(&mut ring, &test)?;
     tests::net::test_udp_sendzc_with_dest(&mut ring, &test)?;
 
@@ -149,12 +229,17 @@ fn test<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
     tests::poll::test_eventfd_poll_remove(&mut ring, &test)?;
     tests::poll::test_eventfd_poll_remove_failed(&mut ring, &test)?;
     tests::poll::test_eventfd_poll_multi(&mut ring, &test)?;
+    tests::poll::test_pipe_poll_multi(&mut ring, &test)?;
+    tests::poll::test_pipe_epoll_ctl(&mut ring, &test)?;
 
     // futex
     tests::futex::test_futex_wait(&mut ring, &test)?;
     tests::futex::test_futex_wake(&mut ring, &test)?;
     tests::futex::test_futex_waitv(&mut ring, &test)?;
 
+    // process
+    tests::process::test_waitid(&mut ring, &test)?;
+
     // regression test
     tests::regression::test_issue154(&mut ring, &test)?;
 
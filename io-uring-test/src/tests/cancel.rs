@@ -255,6 +255,142 @@ pub fn test_async_cancel_fd_all<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
     Ok(())
 }
 
+// Cancels two reads pending on the same pipe with a single request.
+pub fn test_async_cancel_fd_all_reads<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    require!(
+        test;
+        test.probe.is_supported(opcode::Read::CODE);
+        test.probe.is_supported(opcode::AsyncCancel2::CODE);
+        test.probe.is_supported(opcode::Socket::CODE); // Check if Kernel >= 5.19
+    );
+
+    println!("test async_cancel_fd_all_reads");
+
+    let mut fds = [0; 2];
+    if unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC) } != 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    let (rx, _tx) = unsafe { (File::from_raw_fd(fds[0]), File::from_raw_fd(fds[1])) };
+    let fd = types::Fd(rx.as_raw_fd());
+
+    let mut bufs = [[0u8; 16]; 2];
+    let read_1 = opcode::Read::new(fd, bufs[0].as_mut_ptr(), 16).build();
+    let read_2 = opcode::Read::new(fd, bufs[1].as_mut_ptr(), 16).build();
+
+    let builder = CancelBuilder::fd(fd).all();
+    let cancel_e = opcode::AsyncCancel2::new(builder).build();
+
+    for sqe in [read_1.user_data(2006).into(), read_2.user_data(2007).into()] {
+        unsafe {
+            ring.submission().push(sqe).expect("queue is full");
+        }
+    }
+    ring.submit()?;
+
+    unsafe {
+        ring.submission()
+            .push(cancel_e.user_data(2008).into())
+            .expect("queue is full");
+    }
+
+    // Wait for both reads and the cancel request.
+    ring.submit_and_wait(3)?;
+
+    let mut cqes: Vec<cqueue::Entry> = ring.completion().map(Into::into).collect();
+    cqes.sort_unstable_by_key(cqueue::Entry::user_data);
+
+    assert_eq!(cqes.len(), 3);
+
+    assert_eq!(cqes[0].user_data(), 2006);
+    assert_eq!(cqes[1].user_data(), 2007);
+    assert_eq!(cqes[2].user_data(), 2008);
+
+    assert_eq!(cqes[0].result(), -libc::ECANCELED);
+    assert_eq!(cqes[1].result(), -libc::ECANCELED);
+    assert_eq!(cqes[2].result(), 2); // the number of requests cancelled
+
+    Ok(())
+}
+
+// Cancels three reads sharing the same user data, first one at a time and then all at once.
+pub fn test_async_cancel_all_reads<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    require!(
+        test;
+        test.probe.is_supported(opcode::Read::CODE);
+        test.probe.is_supported(opcode::Socket::CODE); // Check if Kernel >= 5.19
+    );
+
+    println!("test async_cancel_all_reads");
+
+    let mut fds = [0; 2];
+    if unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC) } != 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    let (rx, _tx) = unsafe { (File::from_raw_fd(fds[0]), File::from_raw_fd(fds[1])) };
+    let fd = types::Fd(rx.as_raw_fd());
+
+    let mut bufs = [[0u8; 16]; 3];
+    for buf in bufs.iter_mut() {
+        let read_e = opcode::Read::new(fd, buf.as_mut_ptr(), 16)
+            .build()
+            .user_data(2009);
+        unsafe {
+            ring.submission()
+                .push(read_e.into())
+                .expect("queue is full");
+        }
+    }
+    ring.submit()?;
+
+    // Without `all`, only the first match is canceled.
+    let cancel_e = opcode::AsyncCancel::new(2009).build().user_data(2010);
+    unsafe {
+        ring.submission()
+            .push(cancel_e.into())
+            .expect("queue is full");
+    }
+    ring.submit_and_wait(2)?;
+
+    let mut cqes: Vec<cqueue::Entry> = ring.completion().map(Into::into).collect();
+    cqes.sort_unstable_by_key(cqueue::Entry::user_data);
+
+    assert_eq!(cqes.len(), 2);
+    assert_eq!(cqes[0].user_data(), 2009);
+    assert_eq!(cqes[0].result(), -libc::ECANCELED);
+    assert_eq!(cqes[1].user_data(), 2010);
+    assert_eq!(cqes[1].result(), 0);
+
+    let cancel_e = opcode::AsyncCancel::new(2009)
+        .all(true)
+        .build()
+        .user_data(2011);
+    unsafe {
+        ring.submission()
+            .push(cancel_e.into())
+            .expect("queue is full");
+    }
+    ring.submit_and_wait(3)?;
+
+    let mut cqes: Vec<cqueue::Entry> = ring.completion().map(Into::into).collect();
+    cqes.sort_unstable_by_key(cqueue::Entry::user_data);
+
+    assert_eq!(cqes.len(), 3);
+    assert_eq!(cqes[0].user_data(), 2009);
+    assert_eq!(cqes[0].result(), -libc::ECANCELED);
+    assert_eq!(cqes[1].user_data(), 2009);
+    assert_eq!(cqes[1].result(), -libc::ECANCELED);
+    assert_eq!(cqes[2].user_data(), 2011);
+    assert_eq!(cqes[2].result(), 2); // the number of requests cancelled
+
+    Ok(())
+}
+
 fn create_dummy_fd() -> anyhow::Result<File> {
     unsafe {
         let fd = libc::eventfd(0, libc::EFD_CLOEXEC);
@@ -155,6 +155,66 @@ pub fn test_file_fallocate<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
     Ok(())
 }
 
+pub fn test_file_fallocate_punch_hole<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    use std::os::unix::fs::MetadataExt;
+
+    require!(
+        test;
+        test.probe.is_supported(opcode::Fallocate::CODE);
+    );
+
+    println!("test file_fallocate_punch_hole");
+
+    let mut file = tempfile::tempfile()?;
+    file.write_all(&[0xaa; 3 * 4096])?;
+    file.sync_all()?;
+    let blocks = file.metadata()?.blocks();
+
+    let falloc_e = opcode::Fallocate::new(types::Fd(file.as_raw_fd()), 4096)
+        .offset(4096)
+        .fallocate_flags(types::FallocateFlags::PUNCH_HOLE | types::FallocateFlags::KEEP_SIZE);
+
+    unsafe {
+        ring.submission()
+            .push(falloc_e.build().user_data(0x11).into())
+            .expect("queue is full");
+    }
+
+    ring.submit_and_wait(1)?;
+
+    let cqes: Vec<cqueue::Entry> = ring.completion().map(Into::into).collect();
+
+    assert_eq!(cqes.len(), 1);
+    assert_eq!(cqes[0].user_data(), 0x11);
+    if cqes[0].result() == -libc::EOPNOTSUPP {
+        println!("skipping: the file system does not support punching holes");
+        return Ok(());
+    }
+    assert_eq!(cqes[0].result(), 0);
+
+    // The size is kept, the middle page reads as zeros, and it no longer takes up space.
+    let meta = file.metadata()?;
+    assert_eq!(meta.len(), 3 * 4096);
+    assert!(meta.blocks() < blocks);
+
+    let hole = unsafe { libc::lseek(file.as_raw_fd(), 0, libc::SEEK_HOLE) };
+    assert_eq!(hole, 4096);
+    let data = unsafe { libc::lseek(file.as_raw_fd(), 4096, libc::SEEK_DATA) };
+    assert_eq!(data, 2 * 4096);
+
+    let mut output = Vec::new();
+    std::io::Seek::rewind(&mut file)?;
+    file.read_to_end(&mut output)?;
+    assert_eq!(output[..4096], [0xaa; 4096]);
+    assert_eq!(output[4096..2 * 4096], [0; 4096]);
+    assert_eq!(output[2 * 4096..], [0xaa; 4096]);
+
+    Ok(())
+}
+
 pub fn test_file_openat2<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
     ring: &mut IoUring<S, C>,
     test: &Test,
@@ -198,6 +258,62 @@ pub fn test_file_openat2<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
     Ok(())
 }
 
+pub fn test_file_openat2_resolve_beneath<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    require!(
+        test;
+        test.probe.is_supported(opcode::OpenAt2::CODE);
+    );
+
+    println!("test file_openat2_resolve_beneath");
+
+    let dir = tempfile::tempdir()?;
+    let dirfd = fs::File::open(dir.path())?;
+    let dirfd = types::Fd(dirfd.as_raw_fd());
+
+    let openhow = types::OpenHow::new()
+        .flags((libc::O_CREAT | libc::O_RDWR | libc::O_CLOEXEC) as _)
+        .mode(0o600)
+        .resolve(types::RESOLVE_BENEATH);
+
+    // Only the path below the directory may be opened.
+    let inner = CString::new("inner")?;
+    let escape = CString::new("../escape")?;
+    let absolute = CString::new(dir.path().join("absolute").as_os_str().as_bytes())?;
+
+    unsafe {
+        let mut queue = ring.submission();
+        for (path, user_data) in [(&inner, 0x12), (&escape, 0x13), (&absolute, 0x14)] {
+            let open_e = opcode::OpenAt2::new(dirfd, path.as_ptr(), &openhow);
+            queue
+                .push(open_e.build().user_data(user_data).into())
+                .expect("queue is full");
+        }
+    }
+
+    ring.submit_and_wait(3)?;
+
+    let mut cqes: Vec<cqueue::Entry> = ring.completion().map(Into::into).collect();
+    cqes.sort_unstable_by_key(cqueue::Entry::user_data);
+
+    assert_eq!(cqes.len(), 3);
+    assert_eq!(cqes[0].user_data(), 0x12);
+    assert!(cqes[0].result() >= 0);
+    assert_eq!(cqes[1].user_data(), 0x13);
+    assert_eq!(cqes[1].result(), -libc::EXDEV);
+    assert_eq!(cqes[2].user_data(), 0x14);
+    assert_eq!(cqes[2].result(), -libc::EXDEV);
+
+    let fd = unsafe { fs::File::from_raw_fd(cqes[0].result()) };
+    assert!(fd.metadata()?.is_file());
+    assert!(dir.path().join("inner").exists());
+    assert!(!dir.path().join("absolute").exists());
+
+    Ok(())
+}
+
 pub fn test_file_openat2_close_file_index<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
     ring: &mut IoUring<S, C>,
     test: &Test,
@@ -262,7 +378,7 @@ pub fn test_file_openat2_close_file_index<S: squeue::EntryMarker, C: cqueue::Ent
 
         unsafe {
             ring.submission()
-                .push(ild().user_data(0x12).into())
+                .push(op.build().user_data(0x12).into())
                 .expect("queue is full");
         }
 
@@ -812,6 +928,171 @@ pub fn test_file_splice<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
     Ok(())
 }
 
+pub fn test_pipe_splice_move<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    use std::io::Write;
+
+    require!(
+        test;
+        test.probe.is_supported(opcode::Splice::CODE);
+    );
+
+    println!("test pipe_splice_move");
+
+    let (mut pipe_in, pipe_out) = {
+        let mut pipes = [0, 0];
+        let ret = unsafe { libc::pipe(pipes.as_mut_ptr()) };
+        assert_eq!(ret, 0);
+        let pipe_out = unsafe { fs::File::from_raw_fd(pipes[0]) };
+        let pipe_in = unsafe { fs::File::from_raw_fd(pipes[1]) };
+        (pipe_in, pipe_out)
+    };
+
+    let input = &[0x5a; 1024];
+    pipe_in.write_all(input)?;
+
+    // Splice the pipe into the file at offset 512, leaving the file position alone.
+    let file = tempfile::tempfile()?;
+
+    let splice_e = opcode::Splice::from_offsets(
+        types::Fd(pipe_out.as_raw_fd()),
+        None,
+        types::Fd(file.as_raw_fd()),
+        Some(512),
+        1024,
+    )
+    .splice_flags(types::SpliceFlags::MOVE);
+
+    unsafe {
+        ring.submission()
+            .push(splice_e.build().user_data(0x34).into())
+            .expect("queue is full");
+    }
+
+    ring.submit_and_wait(1)?;
+
+    let cqes: Vec<cqueue::Entry> = ring.completion().map(Into::into).collect();
+
+    assert_eq!(cqes.len(), 1);
+    assert_eq!(cqes[0].user_data(), 0x34);
+    assert_eq!(cqes[0].result(), 1024);
+
+    let mut output = [0xff; 1536];
+    let n = unsafe { libc::pread(file.as_raw_fd(), output.as_mut_ptr().cast(), 1536, 0) };
+    assert_eq!(n, 1536);
+    assert_eq!(&output[..512], &[0; 512][..]);
+    assert_eq!(&output[512..], &input[..]);
+
+    Ok(())
+}
+
+pub fn test_pipe_tee<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    use std::io::{Read, Write};
+
+    require!(
+        test;
+        test.probe.is_supported(opcode::Tee::CODE);
+    );
+
+    println!("test pipe_tee");
+
+    let pipe = || -> anyhow::Result<(fs::File, fs::File)> {
+        let mut pipes = [0, 0];
+        let ret = unsafe { libc::pipe(pipes.as_mut_ptr()) };
+        assert_eq!(ret, 0);
+        let pipe_out = unsafe { fs::File::from_raw_fd(pipes[0]) };
+        let pipe_in = unsafe { fs::File::from_raw_fd(pipes[1]) };
+        Ok((pipe_in, pipe_out))
+    };
+    let (mut src_in, mut src_out) = pipe()?;
+    let (dst1_in, mut dst1_out) = pipe()?;
+    let (dst2_in, mut dst2_out) = pipe()?;
+
+    let input = b"the same data for two readers";
+    src_in.write_all(input)?;
+
+    // Tee the source pipe into both destinations, the data stays in the source.
+    unsafe {
+        let mut queue = ring.submission();
+        for (dst_in, user_data) in [(&dst1_in, 0x35), (&dst2_in, 0x36)] {
+            let tee_e = opcode::Tee::new(
+                types::Fd(src_out.as_raw_fd()),
+                types::Fd(dst_in.as_raw_fd()),
+                input.len() as _,
+            );
+            queue
+                .push(
+                    tee_e
+                        .build()
+                        .user_data(user_data)
+                        .flags(squeue::Flags::IO_LINK)
+                        .into(),
+                )
+                .expect("queue is full");
+        }
+    }
+
+    ring.submit_and_wait(2)?;
+
+    let cqes: Vec<cqueue::Entry> = ring.completion().map(Into::into).collect();
+
+    assert_eq!(cqes.len(), 2);
+    assert_eq!(cqes[0].user_data(), 0x35);
+    assert_eq!(cqes[0].result(), input.len() as i32);
+    assert_eq!(cqes[1].user_data(), 0x36);
+    assert_eq!(cqes[1].result(), input.len() as i32);
+
+    for reader in [&mut dst1_out, &mut dst2_out, &mut src_out] {
+        let mut output = vec![0; input.len()];
+        reader.read_exact(&mut output)?;
+        assert_eq!(&output[..], &input[..]);
+    }
+
+    Ok(())
+}
+
+pub fn test_file_fadvise<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    use std::io::Write;
+
+    require!(
+        test;
+        test.probe.is_supported(opcode::Fadvise::CODE);
+    );
+
+    println!("test file_fadvise");
+
+    let mut file = tempfile::tempfile()?;
+    file.write_all(&[0xa5; 8192])?;
+    file.sync_all()?;
+
+    let fadvise_e =
+        opcode::Fadvise::from_advice(types::Fd(file.as_raw_fd()), 8192, types::Advice::DontNeed);
+
+    unsafe {
+        ring.submission()
+            .push(fadvise_e.build().user_data(0x37).into())
+            .expect("queue is full");
+    }
+
+    ring.submit_and_wait(1)?;
+
+    let cqes: Vec<cqueue::Entry> = ring.completion().map(Into::into).collect();
+
+    assert_eq!(cqes.len(), 1);
+    assert_eq!(cqes[0].user_data(), 0x37);
+    assert_eq!(cqes[0].result(), 0);
+
+    Ok(())
+}
+
 pub fn test_ftruncate<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
     ring: &mut IoUring<S, C>,
     test: &Test,
@@ -936,5 +1217,403 @@ pub fn test_fixed_fd_install<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
     file.read_exact(&mut output)?;
     assert_eq!(output, input);
 
+    ring.submitter().unregister_files()?;
+
+    Ok(())
+}
+
+pub fn test_fixed_fd_install_pipe<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    require!(
+        test;
+        test.probe.is_supported(opcode::FixedFdInstall::CODE);
+    );
+
+    println!("test fixed_fd_install_pipe");
+
+    let (rx, mut tx) = {
+        let mut fds = [0; 2];
+        assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+        unsafe { (fs::File::from_raw_fd(fds[0]), fs::File::from_raw_fd(fds[1])) }
+    };
+    ring.submitter().register_files(&[rx.as_raw_fd()])?;
+    drop(rx);
+
+    let fixed_fd_install_e =
+        opcode::FixedFdInstall::new(types::Fixed(0), types::FIXED_FD_NO_CLOEXEC);
+
+    unsafe {
+        ring.submission()
+            .push(fixed_fd_install_e.build().user_data(0x03).into())
+            .expect("queue is full");
+    }
+
+    ring.submit_and_wait(1)?;
+
+    let cqes: Vec<cqueue::Entry> = ring.completion().map(Into::into).collect();
+
+    assert_eq!(cqes.len(), 1);
+    assert_eq!(cqes[0].user_data(), 0x03);
+    let fd = cqes[0].result();
+    assert!(fd > 0);
+    let mut rx = unsafe { fs::File::from_raw_fd(fd) };
+    assert_eq!(
+        unsafe { libc::fcntl(fd, libc::F_GETFD) } & libc::FD_CLOEXEC,
+        0
+    );
+
+    tx.write_all(b"pipe")?;
+    let mut output = [0; 4];
+    rx.read_exact(&mut output)?;
+    assert_eq!(&output, b"pipe");
+
+    ring.submitter().unregister_files()?;
+
+    Ok(())
+}
+
+pub fn test_statx_buf<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    require!(
+        test;
+        test.probe.is_supported(opcode::Statx::CODE);
+    );
+
+    use std::os::unix::fs::MetadataExt;
+
+    println!("test statx_buf");
+
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("test-io-uring-statx-buf");
+    let pathbuf = CString::new(path.as_os_str().as_bytes())?;
+    fs::write(&path, [0xa5; 1234])?;
+
+    let mut statxbuf = types::StatxBuf::new();
+
+    let statx_e = opcode::Statx::new(
+        types::Fd(libc::AT_FDCWD),
+        pathbuf.as_ptr(),
+        statxbuf.as_mut_ptr(),
+    )
+    .mask(libc::STATX_BASIC_STATS)
+    .build()
+    .user_data(0x9b)
+    .into();
+
+    unsafe {
+        ring.submission().push(statx_e).expect("queue is full");
+    }
+
+    ring.submit_and_wait(1)?;
+
+    let cqes: Vec<cqueue::Entry> = ring.completion().map(Into::into).collect();
+
+    assert_eq!(cqes.len(), 1);
+    assert_eq!(cqes[0].user_data(), 0x9b);
+    assert_eq!(cqes[0].result(), 0);
+
+    let meta = fs::metadata(&path)?;
+    assert_eq!(statxbuf.size(), Some(meta.len()));
+    assert_eq!(statxbuf.size(), Some(1234));
+    assert_eq!(statxbuf.mode().map(u32::from), Some(meta.mode()));
+    assert_eq!(statxbuf.ino(), Some(meta.ino()));
+    assert_eq!(statxbuf.mtime(), Some(meta.modified()?));
+
+    Ok(())
+}
+
+pub fn test_xattr<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    require!(
+        test;
+        test.probe.is_supported(opcode::SetXattr::CODE);
+        test.probe.is_supported(opcode::FGetXattr::CODE);
+    );
+
+    println!("test xattr");
+
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("xattr");
+    let file = fs::File::create(&path)?;
+    let path = CString::new(path.as_os_str().as_bytes())?;
+    let name = CString::new("user.io_uring")?;
+    let value = b"ring value";
+
+    let set_e = opcode::SetXattr::new(
+        path.as_ptr(),
+        name.as_ptr(),
+        value.as_ptr().cast(),
+        value.len() as _,
+    )
+    .flags(libc::XATTR_CREATE)
+    .build()
+    .user_data(0x01)
+    .into();
+
+    unsafe {
+        ring.submission().push(set_e).expect("queue is full");
+    }
+    ring.submit_and_wait(1)?;
+
+    let cqe: cqueue::Entry = ring.completion().next().expect("cqueue is empty").into();
+    assert_eq!(cqe.user_data(), 0x01);
+    if cqe.result() == -libc::EOPNOTSUPP {
+        println!("skipping: the temporary directory does not support user xattrs");
+        return Ok(());
+    }
+    assert_eq!(cqe.result(), 0);
+
+    // XATTR_CREATE fails now that the attribute exists.
+    let fset_e = opcode::FSetXattr::new(
+        types::Fd(file.as_raw_fd()),
+        name.as_ptr(),
+        value.as_ptr().cast(),
+        value.len() as _,
+    )
+    .flags(libc::XATTR_CREATE)
+    .build()
+    .user_data(0x02)
+    .into();
+
+    let mut buf = [0u8; 32];
+    let get_e = opcode::GetXattr::new(
+        path.as_ptr(),
+        name.as_ptr(),
+        buf.as_mut_ptr().cast(),
+        buf.len() as _,
+    )
+    .build()
+    .user_data(0x03)
+    .into();
+
+    let mut fbuf = [0u8; 32];
+    let fget_e = opcode::FGetXattr::new(
+        types::Fd(file.as_raw_fd()),
+        name.as_ptr(),
+        fbuf.as_mut_ptr().cast(),
+        fbuf.len() as _,
+    )
+    .build()
+    .user_data(0x04)
+    .into();
+
+    unsafe {
+        let mut queue = ring.submission();
+        queue.push(fset_e).expect("queue is full");
+        queue.push(get_e).expect("queue is full");
+        queue.push(fget_e).expect("queue is full");
+    }
+    ring.submit_and_wait(3)?;
+
+    let mut cqes: Vec<cqueue::Entry> = ring.completion().map(Into::into).collect();
+    cqes.sort_by_key(|cqe| cqe.user_data());
+
+    assert_eq!(cqes.len(), 3);
+    assert_eq!(cqes[0].user_data(), 0x02);
+    assert_eq!(cqes[0].result(), -libc::EEXIST);
+    assert_eq!(cqes[1].user_data(), 0x03);
+    assert_eq!(cqes[1].result(), value.len() as i32);
+    assert_eq!(&buf[..value.len()], value);
+    assert_eq!(cqes[2].user_data(), 0x04);
+    assert_eq!(cqes[2].result(), value.len() as i32);
+    assert_eq!(&fbuf[..value.len()], value);
+
+    Ok(())
+}
+
+pub fn test_path_ops<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    require!(
+        test;
+        test.probe.is_supported(opcode::RenameAt::CODE);
+        test.probe.is_supported(opcode::UnlinkAt::CODE);
+        test.probe.is_supported(opcode::MkDirAt::CODE);
+        test.probe.is_supported(opcode::SymlinkAt::CODE);
+        test.probe.is_supported(opcode::LinkAt::CODE);
+    );
+
+    println!("test path_ops");
+
+    let dir = tempfile::tempdir()?;
+    let dirfd = fs::File::open(dir.path())?;
+    let dirfd = types::Fd(dirfd.as_raw_fd());
+    fs::write(dir.path().join("file"), b"contents")?;
+
+    let file = CString::new("file")?;
+    let subdir = CString::new("subdir")?;
+    let renamed = CString::new("subdir/renamed")?;
+    let symlink = CString::new("symlink")?;
+    let hardlink = CString::new("hardlink")?;
+
+    // Run each operation on its own, in order, checking it succeeded.
+    let mut run = |entry: squeue::Entry| -> anyhow::Result<i32> {
+        unsafe {
+            ring.submission()
+                .push(entry.user_data(0x33).into())
+                .expect("queue is full");
+        }
+        ring.submit_and_wait(1)?;
+        let cqe: cqueue::Entry = ring.completion().next().expect("cqueue is empty").into();
+        assert_eq!(cqe.user_data(), 0x33);
+        Ok(cqe.result())
+    };
+
+    let mkdir_e = opcode::MkDirAt::new(dirfd, subdir.as_ptr()).mode(0o755);
+    assert_eq!(run(mkdir_e.build())?, 0);
+    assert!(dir.path().join("subdir").is_dir());
+
+    let rename_e = opcode::RenameAt::new(dirfd, file.as_ptr(), dirfd, renamed.as_ptr())
+        .flags(libc::RENAME_NOREPLACE)
+        .build();
+    assert_eq!(run(rename_e.clone())?, 0);
+    assert!(!dir.path().join("file").exists());
+    assert_eq!(fs::read(dir.path().join("subdir/renamed"))?, b"contents");
+
+    // The same rename fails now that the source is gone.
+    assert_eq!(run(rename_e)?, -libc::ENOENT);
+
+    let symlink_e = opcode::SymlinkAt::new(dirfd, renamed.as_ptr(), symlink.as_ptr());
+    assert_eq!(run(symlink_e.build())?, 0);
+    assert_eq!(
+        fs::read_link(dir.path().join("symlink"))?,
+        std::path::Path::new("subdir/renamed")
+    );
+
+    let link_e = opcode::LinkAt::new(dirfd, renamed.as_ptr(), dirfd, hardlink.as_ptr());
+    assert_eq!(run(link_e.build())?, 0);
+    assert_eq!(fs::read(dir.path().join("hardlink"))?, b"contents");
+
+    for path in [&symlink, &hardlink, &renamed] {
+        let unlink_e = opcode::UnlinkAt::new(dirfd, path.as_ptr());
+        assert_eq!(run(unlink_e.build())?, 0);
+    }
+    let rmdir_e = opcode::UnlinkAt::new(dirfd, subdir.as_ptr()).flags(libc::AT_REMOVEDIR);
+    assert_eq!(run(rmdir_e.build())?, 0);
+    assert_eq!(fs::read_dir(dir.path())?.count(), 0);
+
+    Ok(())
+}
+
+pub fn test_iopoll_read<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    require!(
+        test;
+        test.probe.is_supported(opcode::Read::CODE);
+    );
+
+    println!("test iopoll_read");
+
+    let err = ring
+        .submitter()
+        .iopoll_wait(1)
+        .expect_err("iopoll_wait should require setup_iopoll");
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+
+    let mut iopoll_ring: IoUring<S, C> = IoUring::builder().setup_iopoll().build(4)?;
+    assert!(iopoll_ring.params().is_setup_iopoll());
+
+    polled_read(&mut iopoll_ring)
+}
+
+pub fn test_hybrid_iopoll_read<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    _ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    require!(
+        test;
+        test.probe.is_supported(opcode::Read::CODE);
+    );
+
+    println!("test hybrid_iopoll_read");
+
+    let mut iopoll_ring: IoUring<S, C> = match IoUring::builder()
+        .setup_iopoll()
+        .setup_hybrid_iopoll()
+        .build(4)
+    {
+        Ok(ring) => ring,
+        Err(err) if err.raw_os_error() == Some(libc::EINVAL) => {
+            println!("skipping: hybrid IOPOLL is not supported by this kernel");
+            return Ok(());
+        }
+        Err(err) => return Err(err.into()),
+    };
+    assert!(iopoll_ring.params().is_setup_iopoll());
+    assert!(iopoll_ring.params().is_setup_hybrid_iopoll());
+
+    polled_read(&mut iopoll_ring)
+}
+
+/// Read a page from an `O_DIRECT` file through an IOPOLL ring, skipping if polled I/O isn't
+/// available for it.
+fn polled_read<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    iopoll_ring: &mut IoUring<S, C>,
+) -> anyhow::Result<()> {
+    use std::os::unix::fs::OpenOptionsExt;
+    use tempfile::TempDir;
+
+    #[repr(align(4096))]
+    struct AlignedBuffer([u8; 4096]);
+
+    let dir = TempDir::new_in(".")?;
+    let path = dir.path().join("io-uring-test-file");
+    fs::write(&path, [0xa5; 4096])?;
+
+    let file = match fs::OpenOptions::new()
+        .read(true)
+        .custom_flags(libc::O_DIRECT)
+        .open(&path)
+    {
+        Ok(file) => file,
+        Err(err) if err.raw_os_error() == Some(libc::EINVAL) => {
+            println!("skipping: O_DIRECT is not supported here");
+            return Ok(());
+        }
+        Err(err) => return Err(err.into()),
+    };
+
+    let mut output = Box::new(AlignedBuffer([0; 4096]));
+    let read_e = opcode::Read::new(
+        types::Fd(file.as_raw_fd()),
+        output.0.as_mut_ptr(),
+        output.0.len() as _,
+    );
+
+    unsafe {
+        iopoll_ring
+            .submission()
+            .push(read_e.build().user_data(0x42).into())
+            .expect("queue is full");
+    }
+    iopoll_ring.submit()?;
+
+    assert_eq!(iopoll_ring.submitter().iopoll_wait(1)?, 1);
+
+    let cqes: Vec<cqueue::Entry> = iopoll_ring.completion().map(Into::into).collect();
+    assert_eq!(cqes.len(), 1);
+    assert_eq!(cqes[0].user_data(), 0x42);
+
+    // Files on devices without polled queues (e.g. anything but NVMe) can't be used for IOPOLL.
+    if cqes[0].result() == -libc::EOPNOTSUPP {
+        println!("skipping: the file system does not support polled I/O");
+        return Ok(());
+    }
+
+    assert_eq!(cqes[0].result(), 4096);
+    assert_eq!(output.0, [0xa5; 4096]);
+
+    // Nothing is in flight, so there is nothing to wait for.
+    assert_eq!(iopoll_ring.submitter().iopoll_wait(0)?, 0);
+
     Ok(())
 }
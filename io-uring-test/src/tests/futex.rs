@@ -1,17 +1,12 @@
 use crate::Test;
 use io_uring::cqueue::EntryMarker;
-use io_uring::types::FutexWaitV;
+use io_uring::types::{FutexWaitV, FUTEX2_SIZE_U32};
 use io_uring::{cqueue, opcode, squeue, IoUring};
 use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use std::{io, ptr, thread};
 
-// Not defined by libc.
-//
-// From: https://github.com/torvalds/linux/blob/v6.7/include/uapi/linux/futex.h#L63
-const FUTEX2_SIZE_U32: u32 = 2;
-
 const INIT_VAL: u32 = 0xDEAD_BEEF;
 
 fn syscall_futex(futex: *const u32, op: libc::c_int, val: u32) -> io::Result<i64> {
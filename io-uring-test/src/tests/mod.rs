@@ -3,10 +3,12 @@ pub mod fs;
 pub mod futex;
 pub mod net;
 pub mod poll;
+pub mod process;
 pub mod queue;
 pub mod register;
 pub mod register_buf_ring;
 pub mod register_buffers;
 pub mod register_sync_cancel;
 pub mod regression;
+pub mod setup;
 pub mod timeout;
@@ -333,6 +333,80 @@ pub fn test_tcp_zero_copy_send_fixed<S: squeue::EntryMarker, C: cqueue::EntryMar
     Ok(())
 }
 
+pub fn test_tcp_send_recv_poll_first<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    require!(
+        test;
+        test.probe.is_supported(opcode::SendZc::CODE);
+        test.probe.is_supported(opcode::Recv::CODE);
+    );
+
+    println!("test tcp_send_recv_poll_first");
+
+    let (send_stream, recv_stream) = tcp_pair()?;
+
+    let send_fd = types::Fd(send_stream.as_raw_fd());
+    let recv_fd = types::Fd(recv_stream.as_raw_fd());
+
+    let text = b"poll first, then send from a fixed buffer";
+    let mut output = vec![0; text.len()];
+
+    let _ = ring.submitter().unregister_buffers();
+
+    let mut buf0 = vec![0; 256];
+    let iovecs = [libc::iovec {
+        iov_base: buf0.as_mut_ptr() as _,
+        iov_len: buf0.len() as _,
+    }];
+    unsafe { ring.submitter().register_buffers(&iovecs)? };
+
+    // Send from the middle of the registered buffer.
+    buf0[7..(text.len() + 7)].copy_from_slice(text);
+
+    // The receive goes straight to waiting, since nothing has been sent yet.
+    let recv_e = opcode::Recv::new(recv_fd, output.as_mut_ptr(), output.len() as _)
+        .poll_first(true)
+        .build()
+        .user_data(0x02)
+        .into();
+
+    unsafe {
+        ring.submission().push(recv_e).expect("queue is full");
+    }
+    ring.submit()?;
+
+    let send_e = opcode::SendZc::new(send_fd, buf0[7..].as_ptr(), text.len() as _)
+        .buf_index(Some(0))
+        .poll_first(true)
+        .build()
+        .user_data(0x01)
+        .into();
+
+    unsafe {
+        ring.submission().push(send_e).expect("queue is full");
+    }
+    ring.submit_and_wait(3)?;
+
+    let mut cqes: Vec<cqueue::Entry> = ring.completion().map(Into::into).collect();
+    cqes.sort_by_key(|cqe| (cqe.user_data(), !io_uring::cqueue::more(cqe.flags())));
+
+    assert_eq!(cqes.len(), 3);
+    assert_eq!(cqes[0].user_data(), 0x01);
+    assert!(io_uring::cqueue::more(cqes[0].flags()));
+    assert_eq!(cqes[0].result(), text.len() as i32);
+    assert_eq!(cqes[1].user_data(), 0x01);
+    assert!(io_uring::cqueue::notif(cqes[1].flags()));
+    assert_eq!(cqes[2].user_data(), 0x02);
+    assert_eq!(cqes[2].result(), text.len() as i32);
+    assert_eq!(&output, text);
+
+    ring.submitter().unregister_buffers()?;
+
+    Ok(())
+}
+
 pub fn test_tcp_sendmsg_recvmsg<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
     ring: &mut IoUring<S, C>,
     test: &Test,
@@ -633,10 +707,11 @@ pub fn test_tcp_accept_multi<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
     let addr = listener.local_addr()?;
     let fd = types::Fd(listener.as_raw_fd());
 
-    // 2 streams
+    // 3 streams, accepted by a single submission
 
     let _stream1 = TcpStream::connect(addr)?;
     let _stream2 = TcpStream::connect(addr)?;
+    let _stream3 = TcpStream::connect(addr)?;
 
     let accept_e = opcode::AcceptMulti::new(fd);
 
@@ -646,15 +721,16 @@ pub fn test_tcp_accept_multi<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
             .expect("queue is full");
     }
 
-    ring.submit_and_wait(2)?;
+    ring.submit_and_wait(3)?;
 
     let cqes: Vec<cqueue::Entry> = ring.completion().map(Into::into).collect();
 
-    assert_eq!(cqes.len(), 2);
+    assert_eq!(cqes.len(), 3);
 
     for cqe in cqes {
         assert_eq!(cqe.user_data(), 2002);
         assert!(cqe.result() >= 0);
+        assert!(cqueue::more(cqe.flags()));
 
         let fd = cqe.result();
 
@@ -710,17 +786,18 @@ pub fn test_tcp_accept_multi_file_index<S: squeue::EntryMarker, C: cqueue::Entry
     let addr = listener.local_addr()?;
     let fd = types::Fd(listener.as_raw_fd());
 
-    // 2 streams
+    // 3 streams, accepted by a single submission
 
     let _stream1 = TcpStream::connect(addr)?;
     let _stream2 = TcpStream::connect(addr)?;
+    let _stream3 = TcpStream::connect(addr)?;
 
-    // 2 fixed table index spots
+    // 3 fixed table index spots
 
-    // Cleanup all fixed files (if any), then reserve slot 0.
+    // Cleanup all fixed files (if any), then reserve slots 0 to 2.
     let _ = ring.submitter().unregister_files();
 
-    ring.submitter().register_files_sparse(2).unwrap();
+    ring.submitter().register_files_sparse(3).unwrap();
     let accept_e = opcode::AcceptMulti::new(fd).allocate_file_index(true);
 
     unsafe {
@@ -729,15 +806,20 @@ pub fn test_tcp_accept_multi_file_index<S: squeue::EntryMarker, C: cqueue::Entry
             .expect("queue is full");
     }
 
-    ring.submit_and_wait(2)?;
+    ring.submit_and_wait(3)?;
 
     let cqes: Vec<cqueue::Entry> = ring.completion().map(Into::into).collect();
 
-    assert_eq!(cqes.len(), 2);
-    #[allow(clippy::needless_range_loop)]
-    for round in 0..=1 {
-        assert_eq!(cqes[round].user_data(), 2002);
-        assert!(cqes[round].result() >= 0);
+    assert_eq!(cqes.len(), 3);
+
+    // Each connection is allocated its own slot of the table.
+    let mut slots: Vec<i32> = cqes.iter().map(cqueue::Entry::result).collect();
+    slots.sort_unstable();
+    assert_eq!(slots, [0, 1, 2]);
+
+    for cqe in &cqes {
+        assert_eq!(cqe.user_data(), 2002);
+        assert!(cqueue::more(cqe.flags()));
 
         // The fixed descriptor will be closed when the
         // table is unregistered below.
@@ -778,6 +860,90 @@ pub fn test_tcp_accept_multi_file_index<S: squeue::EntryMarker, C: cqueue::Entry
     Ok(())
 }
 
+pub fn test_tcp_accept_multi_file_alloc_range<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    // register_file_alloc_range was introduced in kernel 6.0, as was the opcode for SendZc.
+    require!(
+        test;
+        test.probe.is_supported(opcode::Accept::CODE);
+        test.probe.is_supported(opcode::SendZc::CODE);
+    );
+
+    println!("test tcp_accept_multi_file_alloc_range");
+
+    let listener = TCP_LISTENER.get_or_try_init(|| TcpListener::bind("127.0.0.1:0"))?;
+    let addr = listener.local_addr()?;
+    let fd = types::Fd(listener.as_raw_fd());
+
+    let _stream1 = TcpStream::connect(addr)?;
+    let _stream2 = TcpStream::connect(addr)?;
+    let _stream3 = TcpStream::connect(addr)?;
+
+    // Cleanup all fixed files (if any), then reserve slots 0 to 7, of which
+    // only 4 to 6 may be allocated.
+    let _ = ring.submitter().unregister_files();
+
+    ring.submitter().register_files_sparse(8).unwrap();
+    ring.submitter().register_file_alloc_range(4, 3).unwrap();
+
+    // The range must fit into the table.
+    let err = ring
+        .submitter()
+        .register_file_alloc_range(6, 3)
+        .unwrap_err();
+    assert_eq!(err.raw_os_error(), Some(libc::EINVAL));
+
+    let accept_e = opcode::AcceptMulti::new(fd).allocate_file_index(true);
+
+    unsafe {
+        ring.submission()
+            .push(accept_e.build().user_data(2004).into())
+            .expect("queue is full");
+    }
+
+    ring.submit_and_wait(3)?;
+
+    let cqes: Vec<cqueue::Entry> = ring.completion().map(Into::into).collect();
+
+    assert_eq!(cqes.len(), 3);
+
+    let mut slots: Vec<i32> = cqes.iter().map(cqueue::Entry::result).collect();
+    slots.sort_unstable();
+    assert_eq!(slots, [4, 5, 6]);
+
+    for cqe in &cqes {
+        assert_eq!(cqe.user_data(), 2004);
+        assert!(cqueue::more(cqe.flags()));
+    }
+
+    // Cancel the multishot accept
+
+    let cancel_e = opcode::AsyncCancel::new(2004);
+
+    unsafe {
+        ring.submission()
+            .push(cancel_e.build().user_data(2005).into())
+            .expect("queue is full");
+    }
+
+    ring.submit_and_wait(2)?;
+
+    let mut cqes: Vec<cqueue::Entry> = ring.completion().map(Into::into).collect();
+    cqes.sort_unstable_by_key(cqueue::Entry::user_data);
+
+    assert_eq!(cqes.len(), 2);
+    assert_eq!(cqes[0].user_data(), 2004);
+    assert_eq!(cqes[0].result(), -libc::ECANCELED);
+    assert_eq!(cqes[1].user_data(), 2005);
+    assert_eq!(cqes[1].result(), 0);
+
+    ring.submitter().unregister_files().unwrap();
+
+    Ok(())
+}
+
 pub fn test_tcp_connect<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
     ring: &mut IoUring<S, C>,
     test: &Test,
@@ -822,6 +988,50 @@ pub fn test_tcp_connect<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
     Ok(())
 }
 
+pub fn test_tcp_connect_sockaddr<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    use socket2::{Domain, Protocol, Socket, Type};
+
+    require!(
+        test;
+        test.probe.is_supported(opcode::Connect::CODE);
+    );
+
+    println!("test tcp_connect_sockaddr");
+
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let addr = types::SockAddr::from(listener.local_addr()?);
+    assert_eq!(addr.as_socket_addr(), listener.local_addr()?);
+
+    let stream = Socket::new(Domain::IPV4, Type::STREAM, Some(Protocol::TCP))?;
+    let connect_e = opcode::Connect::from_addr(types::Fd(stream.as_raw_fd()), &addr);
+
+    unsafe {
+        ring.submission()
+            .push(connect_e.build().user_data(0x10).into())
+            .expect("queue is full");
+    }
+
+    ring.submit_and_wait(1)?;
+
+    let cqes: Vec<cqueue::Entry> = ring.completion().map(Into::into).collect();
+
+    assert_eq!(cqes.len(), 1);
+    assert_eq!(cqes[0].user_data(), 0x10);
+    assert_eq!(cqes[0].result(), 0);
+
+    let (_, peer) = listener.accept()?;
+    let local = stream
+        .local_addr()?
+        .as_socket()
+        .expect("not an inet socket");
+    assert_eq!(peer, local);
+
+    Ok(())
+}
+
 pub fn test_tcp_buffer_select<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
     ring: &mut IoUring<S, C>,
     test: &Test,
@@ -1223,6 +1433,95 @@ pub fn test_tcp_recv_multi<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
     Ok(())
 }
 
+pub fn test_tcp_recv_multi_buf_ring<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    require!(
+        test;
+        test.probe.is_supported(opcode::SendZc::CODE); // also available 6.0, like the multishot for recv
+    );
+
+    println!("test tcp_recv_multi_buf_ring");
+
+    let (mut send_stream, recv_stream) = tcp_pair()?;
+
+    let recv_fd = types::Fd(recv_stream.as_raw_fd());
+
+    // Provide two buffers of 256 bytes through a buf ring, then recycle the first one
+    // once it has been consumed, so that 768 bytes can be received in total.
+    let mut bufs = vec![[0u8; 256]; 2];
+    let mut buf_ring = types::BufRing::new(4)?;
+    for (bid, buf) in bufs.iter_mut().enumerate() {
+        unsafe { buf_ring.add(buf.as_mut_ptr(), buf.len() as _, bid as _) };
+    }
+    buf_ring.advance();
+
+    unsafe {
+        ring.submitter().register_buf_ring(
+            buf_ring.ring_addr(),
+            buf_ring.ring_entries(),
+            0xbeef,
+        )?;
+    }
+
+    let input: Vec<u8> = (0..768).map(|i| (i / 256) as u8 + 1).collect();
+    send_stream.write_all(&input[..512])?;
+
+    let recv_e = opcode::RecvMulti::new(recv_fd, 0xbeef)
+        .build()
+        .user_data(0x23)
+        .into();
+
+    unsafe {
+        ring.submission().push(recv_e).expect("queue is full");
+    }
+
+    let mut received = Vec::new();
+    while received.len() < 512 {
+        ring.submit_and_wait(1)?;
+
+        for cqe in ring.completion().map(Into::<cqueue::Entry>::into) {
+            assert_eq!(cqe.user_data(), 0x23);
+            assert!(cqe.result() > 0, "unexpected result {}", cqe.result());
+            assert!(cqueue::more(cqe.flags()));
+            let bid = buf_ring.buffer_id(cqe.flags()).expect("no buffer selected");
+            received.extend_from_slice(&bufs[bid as usize][..cqe.result() as usize]);
+        }
+    }
+    assert_eq!(&received, &input[..512]);
+
+    // Recycle the first buffer, and receive the remaining data in it.
+    let bid = 0;
+    unsafe { buf_ring.add(bufs[bid].as_mut_ptr(), bufs[bid].len() as _, bid as _) };
+    buf_ring.advance();
+
+    send_stream.write_all(&input[512..])?;
+    send_stream.shutdown(Shutdown::Write)?;
+
+    let mut done = false;
+    while !done {
+        ring.submit_and_wait(1)?;
+
+        for cqe in ring.completion().map(Into::<cqueue::Entry>::into) {
+            assert_eq!(cqe.user_data(), 0x23);
+            if cqe.result() > 0 {
+                assert_eq!(buf_ring.buffer_id(cqe.flags()), Some(0));
+                received.extend_from_slice(&bufs[0][..cqe.result() as usize]);
+            } else {
+                // Either the peer shut down or the ring ran out of buffers.
+                assert!(!cqueue::more(cqe.flags()));
+                done = true;
+            }
+        }
+    }
+    assert_eq!(received, input);
+
+    ring.submitter().unregister_buf_ring(0xbeef)?;
+
+    Ok(())
+}
+
 pub fn test_tcp_recv_bundle<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
     ring: &mut IoUring<S, C>,
     test: &Test,
@@ -1461,77 +1760,209 @@ pub fn test_tcp_shutdown<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
     Ok(())
 }
 
-pub fn test_socket<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+pub fn test_tcp_shutdown_eof<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
     ring: &mut IoUring<S, C>,
     test: &Test,
 ) -> anyhow::Result<()> {
-    use socket2::{Domain, Protocol, Socket, Type};
-
     require!(
         test;
-        test.probe.is_supported(opcode::Socket::CODE);
+        test.probe.is_supported(opcode::Write::CODE);
+        test.probe.is_supported(opcode::Shutdown::CODE);
     );
 
-    println!("test socket");
+    println!("test tcp_shutdown_eof");
 
-    // Open a UDP socket, through old-style `socket(2)` syscall.
-    // This is used both as a kernel sanity check, and for comparing the returned io-uring FD.
-    let plain_socket = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP)).unwrap();
-    let plain_fd = plain_socket.as_raw_fd();
+    let (send_stream, mut recv_stream) = tcp_pair()?;
+    let send_fd = types::Fd(send_stream.as_raw_fd());
+
+    // Send the data, then half-close the write side once it has been sent.
+    let text = b"goodbye";
+    let write_e = opcode::Write::new(send_fd, text.as_ptr(), text.len() as _)
+        .build()
+        .flags(Flags::IO_LINK)
+        .user_data(0x29);
+    let shutdown_e = opcode::Shutdown::new(send_fd, libc::SHUT_WR)
+        .build()
+        .user_data(0x2a);
 
-    let socket_fd_op = opcode::Socket::new(
-        Domain::IPV4.into(),
-        Type::DGRAM.into(),
-        Protocol::UDP.into(),
-    );
     unsafe {
         ring.submission()
-            .push(socket_fd_op.build().user_data(42).into())
+            .push_multiple([write_e.into(), shutdown_e.into()])
             .expect("queue is full");
     }
-    ring.submit_and_wait(1)?;
+
+    ring.submit_and_wait(2)?;
 
     let cqes: Vec<cqueue::Entry> = ring.completion().map(Into::into).collect();
-    assert_eq!(cqes.len(), 1);
-    assert_eq!(cqes[0].user_data(), 42);
-    assert!(cqes[0].result() >= 0);
-    let io_uring_socket = unsafe { Socket::from_raw_fd(cqes[0].result()) };
-    assert!(io_uring_socket.as_raw_fd() != plain_fd);
-    assert_eq!(cqes[0].flags(), 0);
 
-    // Try a setsockopt.
-    {
-        let mut optval: libc::c_int = 0;
-        let mut optval_size: libc::socklen_t = std::mem::size_of_val(optval) as libc::socklen_t;
-        // Get value before.
-        let ret = unsafe {
-            libc::getsockopt(
-                io_uring_socket.as_raw_fd(),
-                libc::SOL_SOCKET,
-                libc::SO_REUSEADDR,
-                &mut optval as *mut _ as *mut libc::c_void,
-                &mut optval_size as *mut _ as *mut libc::socklen_t,
-            )
-        };
-        assert_eq!(ret, 0);
-        assert_eq!(optval, 0);
+    assert_eq!(cqes.len(), 2);
+    assert_eq!(cqes[0].user_data(), 0x29);
+    assert_eq!(cqes[0].result(), text.len() as i32);
+    assert_eq!(cqes[1].user_data(), 0x2a);
+    assert_eq!(cqes[1].result(), 0);
 
-        // Set value.
-        optval = 1;
-        let op = io_uring::opcode::SetSockOpt::new(
-            io_uring::types::Fd(io_uring_socket.as_raw_fd()),
-            libc::SOL_SOCKET as u32,
-            libc::SO_REUSEADDR as u32,
-            optval as *const _ as *const libc::c_void,
-            std::mem::size_of_val(optval) as libc::socklen_t,
-        )
-        .build()
-        .user_data(1234);
-        unsafe {
-            ring.submission().push(op.into()).expect("queue is full");
-        }
-        ring.submit_and_wait(1)?;
-        let cqes: Vec<cqueue::Entry> = ring.completion().map(Into::into).collect();
+    // The peer reads the data followed by EOF.
+    let mut buf = Vec::new();
+    recv_stream.read_to_end(&mut buf)?;
+    assert_eq!(buf, text);
+
+    Ok(())
+}
+
+pub fn test_tcp_sockopt<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    // Socket commands over `IORING_OP_URING_CMD` appeared in kernel 6.7 and cannot be probed
+    // at runtime. Thus, we additionally check for the `IORING_OP_FUTEX_WAIT` opcode (>= 6.7)
+    // as a proxy instead.
+    require!(
+        test;
+        test.probe.is_supported(opcode::UringCmd16::CODE);
+        test.probe.is_supported(opcode::FutexWait::CODE);
+    );
+
+    println!("test tcp_sockopt");
+
+    let (stream, _) = tcp_pair()?;
+    let fd = types::Fd(stream.as_raw_fd());
+    assert!(!stream.nodelay()?);
+
+    let on: libc::c_int = 1;
+    let mut keepalive: libc::c_int = 0;
+    let optlen = mem::size_of::<libc::c_int>() as u32;
+
+    // Any level can be set, but only `SOL_SOCKET` options can be read back through the ring.
+    let nodelay_e = opcode::SetSockOpt::new(
+        fd,
+        libc::IPPROTO_TCP as _,
+        libc::TCP_NODELAY as _,
+        &on as *const _ as *const libc::c_void,
+        optlen,
+    );
+    let set_e = opcode::SetSockOpt::new(
+        fd,
+        libc::SOL_SOCKET as _,
+        libc::SO_KEEPALIVE as _,
+        &on as *const _ as *const libc::c_void,
+        optlen,
+    );
+    let get_e = opcode::GetSockOpt::new(
+        fd,
+        libc::SOL_SOCKET as _,
+        libc::SO_KEEPALIVE as _,
+        &mut keepalive as *mut _ as *mut libc::c_void,
+        optlen,
+    );
+
+    unsafe {
+        let mut queue = ring.submission();
+        queue
+            .push(
+                nodelay_e
+                    .build()
+                    .user_data(0x41)
+                    .flags(Flags::IO_LINK)
+                    .into(),
+            )
+            .expect("queue is full");
+        queue
+            .push(set_e.build().user_data(0x42).flags(Flags::IO_LINK).into())
+            .expect("queue is full");
+        queue
+            .push(get_e.build().user_data(0x43).into())
+            .expect("queue is full");
+    }
+
+    ring.submit_and_wait(3)?;
+
+    let cqes: Vec<cqueue::Entry> = ring.completion().map(Into::into).collect();
+
+    assert_eq!(cqes.len(), 3);
+    assert_eq!(cqes[0].user_data(), 0x41);
+    assert_eq!(cqes[0].result(), 0);
+    assert_eq!(cqes[1].user_data(), 0x42);
+    assert_eq!(cqes[1].result(), 0);
+    assert_eq!(cqes[2].user_data(), 0x43);
+    assert_eq!(cqes[2].result(), optlen as i32);
+
+    assert!(stream.nodelay()?);
+    assert_eq!(keepalive, 1);
+
+    Ok(())
+}
+
+pub fn test_socket<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    use socket2::{Domain, Protocol, Socket, Type};
+
+    require!(
+        test;
+        test.probe.is_supported(opcode::Socket::CODE);
+    );
+
+    println!("test socket");
+
+    // Open a UDP socket, through old-style `socket(2)` syscall.
+    // This is used both as a kernel sanity check, and for comparing the returned io-uring FD.
+    let plain_socket = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP)).unwrap();
+    let plain_fd = plain_socket.as_raw_fd();
+
+    let socket_fd_op = opcode::Socket::new(
+        Domain::IPV4.into(),
+        Type::DGRAM.into(),
+        Protocol::UDP.into(),
+    );
+    unsafe {
+        ring.submission()
+            .push(socket_fd_op.build().user_data(42).into())
+            .expect("queue is full");
+    }
+    ring.submit_and_wait(1)?;
+
+    let cqes: Vec<cqueue::Entry> = ring.completion().map(Into::into).collect();
+    assert_eq!(cqes.len(), 1);
+    assert_eq!(cqes[0].user_data(), 42);
+    assert!(cqes[0].result() >= 0);
+    let io_uring_socket = unsafe { Socket::from_raw_fd(cqes[0].result()) };
+    assert!(io_uring_socket.as_raw_fd() != plain_fd);
+    assert_eq!(cqes[0].flags(), 0);
+
+    // Try a setsockopt.
+    {
+        let mut optval: libc::c_int = 0;
+        let mut optval_size: libc::socklen_t = std::mem::size_of_val(&optval) as libc::socklen_t;
+        // Get value before.
+        let ret = unsafe {
+            libc::getsockopt(
+                io_uring_socket.as_raw_fd(),
+                libc::SOL_SOCKET,
+                libc::SO_REUSEADDR,
+                &mut optval as *mut _ as *mut libc::c_void,
+                &mut optval_size as *mut _ as *mut libc::socklen_t,
+            )
+        };
+        assert_eq!(ret, 0);
+        assert_eq!(optval, 0);
+
+        // Set value.
+        optval = 1;
+        let op = io_uring::opcode::SetSockOpt::new(
+            io_uring::types::Fd(io_uring_socket.as_raw_fd()),
+            libc::SOL_SOCKET as u32,
+            libc::SO_REUSEADDR as u32,
+            &optval as *const _ as *const libc::c_void,
+            std::mem::size_of_val(&optval) as libc::socklen_t,
+        )
+        .build()
+        .user_data(1234);
+        unsafe {
+            ring.submission().push(op.into()).expect("queue is full");
+        }
+        ring.submit_and_wait(1)?;
+        let cqes: Vec<cqueue::Entry> = ring.completion().map(Into::into).collect();
         assert_eq!(cqes.len(), 1);
         assert_eq!(cqes[0].user_data(), 1234);
         assert_eq!(cqes[0].result(), 0);
@@ -1591,6 +2022,352 @@ pub fn test_socket<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
     Ok(())
 }
 
+pub fn test_socket_file_alloc_connect<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    require!(
+        test;
+        test.probe.is_supported(opcode::Socket::CODE);
+        test.probe.is_supported(opcode::Connect::CODE);
+    );
+
+    println!("test socket_file_alloc_connect");
+
+    // Cleanup all fixed files (if any), then leave the kernel a few slots to choose from.
+    let _ = ring.submitter().unregister_files();
+    ring.submitter().register_files_sparse(4).unwrap();
+
+    let socket_e = opcode::Socket::new(libc::AF_INET, libc::SOCK_STREAM, libc::IPPROTO_TCP)
+        .file_index(Some(types::DestinationSlot::auto_target()));
+
+    unsafe {
+        ring.submission()
+            .push(socket_e.build().user_data(0x34).into())
+            .expect("queue is full");
+    }
+    ring.submit_and_wait(1)?;
+
+    let cqes: Vec<cqueue::Entry> = ring.completion().map(Into::into).collect();
+    assert_eq!(cqes.len(), 1);
+    assert_eq!(cqes[0].user_data(), 0x34);
+    assert!(
+        (0..4).contains(&cqes[0].result()),
+        "unexpected result {}",
+        cqes[0].result()
+    );
+    let slot = cqes[0].result() as u32;
+
+    // Connect through the allocated slot, without ever seeing a regular file descriptor.
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let addr = types::SockAddr::from(listener.local_addr()?);
+    let connect_e = opcode::Connect::from_addr(types::Fixed(slot), &addr);
+
+    unsafe {
+        ring.submission()
+            .push(connect_e.build().user_data(0x35).into())
+            .expect("queue is full");
+    }
+    ring.submit_and_wait(1)?;
+
+    let cqes: Vec<cqueue::Entry> = ring.completion().map(Into::into).collect();
+    assert_eq!(cqes.len(), 1);
+    assert_eq!(cqes[0].user_data(), 0x35);
+    assert_eq!(cqes[0].result(), 0);
+
+    let _ = listener.accept()?;
+
+    ring.submitter().unregister_files().unwrap();
+
+    Ok(())
+}
+
+pub fn test_socket_bind_listen<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    use socket2::{Domain, Protocol, SockAddr, Type};
+
+    require!(
+        test;
+        test.probe.is_supported(opcode::Socket::CODE);
+        test.probe.is_supported(opcode::Bind::CODE);
+        test.probe.is_supported(opcode::Listen::CODE);
+        test.probe.is_supported(opcode::FixedFdInstall::CODE);
+    );
+
+    println!("test socket_bind_listen");
+
+    // Cleanup all fixed files (if any), then reserve slot 0.
+    let _ = ring.submitter().unregister_files();
+    ring.submitter().register_files_sparse(1).unwrap();
+
+    // Create, bind and listen on a loopback socket in fixed slot 0 with one linked chain.
+    let sockaddr = SockAddr::from("127.0.0.1:0".parse::<std::net::SocketAddr>()?);
+    let dest_slot = types::DestinationSlot::try_from_slot_target(0).unwrap();
+    let socket_e = opcode::Socket::new(
+        Domain::IPV4.into(),
+        Type::STREAM.into(),
+        Protocol::TCP.into(),
+    )
+    .file_index(Some(dest_slot));
+    let bind_e = opcode::Bind::new(
+        types::Fixed(0),
+        sockaddr.as_ptr() as *const _,
+        sockaddr.len(),
+    );
+    let listen_e = opcode::Listen::new(types::Fixed(0), 8);
+
+    unsafe {
+        let mut queue = ring.submission();
+        queue
+            .push(
+                socket_e
+                    .build()
+                    .user_data(0x31)
+                    .flags(Flags::IO_LINK)
+                    .into(),
+            )
+            .expect("queue is full");
+        queue
+            .push(bind_e.build().user_data(0x32).flags(Flags::IO_LINK).into())
+            .expect("queue is full");
+        queue
+            .push(listen_e.build().user_data(0x33).into())
+            .expect("queue is full");
+    }
+
+    ring.submit_and_wait(3)?;
+
+    let cqes: Vec<cqueue::Entry> = ring.completion().map(Into::into).collect();
+
+    assert_eq!(cqes.len(), 3);
+    assert_eq!(cqes[0].user_data(), 0x31);
+    assert_eq!(cqes[0].result(), 0);
+    assert_eq!(cqes[1].user_data(), 0x32);
+    assert_eq!(cqes[1].result(), 0);
+    assert_eq!(cqes[2].user_data(), 0x33);
+    assert_eq!(cqes[2].result(), 0);
+
+    // Install the fixed socket as a regular one to check it accepts connections.
+    unsafe {
+        ring.submission()
+            .push(
+                opcode::FixedFdInstall::new(types::Fixed(0), 0)
+                    .build()
+                    .user_data(0x34)
+                    .into(),
+            )
+            .expect("queue is full");
+    }
+
+    ring.submit_and_wait(1)?;
+
+    let cqes: Vec<cqueue::Entry> = ring.completion().map(Into::into).collect();
+
+    assert_eq!(cqes.len(), 1);
+    assert_eq!(cqes[0].user_data(), 0x34);
+    assert!(cqes[0].result() >= 0);
+
+    let listener = unsafe { TcpListener::from_raw_fd(cqes[0].result()) };
+    let addr = listener.local_addr()?;
+    assert!(addr.ip().is_loopback());
+    assert_ne!(addr.port(), 0);
+
+    let _stream = TcpStream::connect(addr)?;
+    let _ = listener.accept()?;
+
+    ring.submitter().unregister_files().unwrap();
+
+    Ok(())
+}
+
+pub fn test_udp_recv_multi<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    // Multishot recv was introduced in 6.0, like `SendZc`.
+    require!(
+        test;
+        test.probe.is_supported(opcode::Recv::CODE);
+        test.probe.is_supported(opcode::SendZc::CODE);
+    );
+
+    println!("test udp_recv_multi");
+
+    let server_socket = std::net::UdpSocket::bind("127.0.0.1:0")?;
+    let client_socket = std::net::UdpSocket::bind("127.0.0.1:0")?;
+    client_socket.connect(server_socket.local_addr()?)?;
+
+    const BUF_GROUP: u16 = 34;
+    let mut bufs = vec![[0u8; 64]; 4];
+    let mut buf_ring = types::BufRing::new(4)?;
+    for (bid, buf) in bufs.iter_mut().enumerate() {
+        unsafe { buf_ring.add(buf.as_mut_ptr(), buf.len() as _, bid as _) };
+    }
+    buf_ring.advance();
+
+    unsafe {
+        ring.submitter().register_buf_ring(
+            buf_ring.ring_addr(),
+            buf_ring.ring_entries(),
+            BUF_GROUP,
+        )?;
+    }
+
+    let recv_e = opcode::RecvMulti::new(Fd(server_socket.as_raw_fd()), BUF_GROUP)
+        .build()
+        .user_data(0x24)
+        .into();
+
+    unsafe {
+        ring.submission().push(recv_e).expect("queue is full");
+    }
+    ring.submit()?;
+
+    // Each datagram is received into its own buffer, from the single submission.
+    let datagrams: [&[u8]; 3] = [b"one", b"two", b"three"];
+    for datagram in datagrams {
+        client_socket.send(datagram)?;
+    }
+
+    let mut cqes: Vec<cqueue::Entry> = Vec::new();
+    while cqes.len() < datagrams.len() {
+        ring.submit_and_wait(1)?;
+        cqes.extend(ring.completion().map(Into::<cqueue::Entry>::into));
+    }
+
+    assert_eq!(cqes.len(), datagrams.len());
+    for (cqe, datagram) in cqes.iter().zip(datagrams) {
+        assert_eq!(cqe.user_data(), 0x24);
+        assert!(cqueue::more(cqe.flags()));
+        assert_eq!(cqe.result(), datagram.len() as i32);
+
+        let bid = buf_ring.buffer_id(cqe.flags()).expect("no buffer selected");
+        assert_eq!(&bufs[bid as usize][..datagram.len()], datagram);
+    }
+
+    // Terminate the multishot request before the buffers go away.
+    let cancel_e = opcode::AsyncCancel::new(0x24);
+
+    unsafe {
+        ring.submission()
+            .push(cancel_e.build().user_data(0x25).into())
+            .expect("queue is full");
+    }
+    ring.submit_and_wait(2)?;
+
+    let mut cqes: Vec<cqueue::Entry> = ring.completion().map(Into::into).collect();
+    cqes.sort_unstable_by_key(cqueue::Entry::user_data);
+
+    assert_eq!(cqes.len(), 2);
+    assert_eq!(cqes[0].user_data(), 0x24);
+    assert!(!cqueue::more(cqes[0].flags()));
+    assert_eq!(cqes[0].result(), -libc::ECANCELED);
+    assert_eq!(cqes[1].user_data(), 0x25);
+    assert_eq!(cqes[1].result(), 0);
+
+    ring.submitter().unregister_buf_ring(BUF_GROUP)?;
+
+    Ok(())
+}
+
+pub fn test_udp_recv_multi_with_buf_ring<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    // Multishot recv was introduced in 6.0, like `SendZc`.
+    require!(
+        test;
+        test.probe.is_supported(opcode::Recv::CODE);
+        test.probe.is_supported(opcode::SendZc::CODE);
+    );
+
+    println!("test udp_recv_multi_with_buf_ring");
+
+    let server_socket = std::net::UdpSocket::bind("127.0.0.1:0")?;
+    let client_socket = std::net::UdpSocket::bind("127.0.0.1:0")?;
+    client_socket.connect(server_socket.local_addr()?)?;
+
+    // Fewer buffers than datagrams, so the request keeps going only if they are recycled. The
+    // kernel selects a buffer before each retry, so the ring must never run dry.
+    const BUF_GROUP: u16 = 35;
+    let mut bufs = [[0u8; 64]; 4];
+    let mut buf_ring = types::BufRing::new(4)?;
+    for (bid, buf) in bufs.iter_mut().enumerate() {
+        unsafe { buf_ring.add(buf.as_mut_ptr(), buf.len() as _, bid as _) };
+    }
+    buf_ring.advance();
+
+    unsafe {
+        ring.submitter().register_buf_ring(
+            buf_ring.ring_addr(),
+            buf_ring.ring_entries(),
+            BUF_GROUP,
+        )?;
+    }
+
+    let recv_e = opcode::RecvMulti::new(Fd(server_socket.as_raw_fd()), BUF_GROUP)
+        .build()
+        .user_data(0x26)
+        .into();
+
+    unsafe {
+        ring.submission().push(recv_e).expect("queue is full");
+    }
+    ring.submit()?;
+
+    let datagrams: [&[u8]; 6] = [b"one", b"two", b"three", b"four", b"five", b"six"];
+    let mut received: Vec<Vec<u8>> = Vec::new();
+
+    for pair in datagrams.chunks(2) {
+        for datagram in pair {
+            client_socket.send(datagram)?;
+        }
+
+        let want = received.len() + pair.len();
+        while received.len() < want {
+            ring.submit_and_wait(1)?;
+
+            let mut cq = ring.completion();
+            let mut completions = unsafe { cq.with_buf_ring(&mut buf_ring) };
+            while let Some(completion) = completions.next_completion() {
+                let cqe = completion.entry();
+                assert_eq!(cqe.user_data(), 0x26);
+                assert!(cqe.result() > 0, "unexpected result {}", cqe.result());
+                assert!(cqueue::more(cqe.flags()));
+                assert!(completion.buffer_id().is_some());
+                received.push(completion.data().to_vec());
+            }
+        }
+    }
+
+    assert_eq!(received, datagrams);
+
+    // Terminate the multishot request before the buffers go away.
+    let cancel_e = opcode::AsyncCancel::new(0x26);
+
+    unsafe {
+        ring.submission()
+            .push(cancel_e.build().user_data(0x27).into())
+            .expect("queue is full");
+    }
+    ring.submit_and_wait(2)?;
+
+    let mut cqes: Vec<cqueue::Entry> = ring.completion().map(Into::into).collect();
+    cqes.sort_unstable_by_key(cqueue::Entry::user_data);
+
+    assert_eq!(cqes.len(), 2);
+    assert_eq!(cqes[0].user_data(), 0x26);
+    assert_eq!(cqes[0].result(), -libc::ECANCELED);
+    assert_eq!(cqes[1].user_data(), 0x27);
+    assert_eq!(cqes[1].result(), 0);
+
+    ring.submitter().unregister_buf_ring(BUF_GROUP)?;
+
+    Ok(())
+}
+
 pub fn test_udp_recvmsg_multishot<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
     ring: &mut IoUring<S, C>,
     test: &Test,
@@ -1882,6 +2659,112 @@ pub fn test_udp_recvmsg_multishot_trunc<S: squeue::EntryMarker, C: cqueue::Entry
     Ok(())
 }
 
+pub fn test_udp_sendto_recvfrom<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    require!(
+        test;
+        test.probe.is_supported(opcode::SendMsg::CODE);
+        test.probe.is_supported(opcode::RecvMsg::CODE);
+    );
+
+    println!("test udp_sendto_recvfrom");
+
+    // Neither socket is connected, so the addresses come from the messages alone.
+    let server_socket = std::net::UdpSocket::bind("127.0.0.1:0")?;
+    let client_socket = std::net::UdpSocket::bind("127.0.0.1:0")?;
+
+    let mut buf = [0u8; 32];
+    let mut recv_msg = types::RecvFrom::new(&mut buf);
+    let send_msg = types::SendTo::new(b"datagram", server_socket.local_addr()?);
+
+    let recv_e = opcode::RecvMsg::new(Fd(server_socket.as_raw_fd()), recv_msg.as_mut_ptr());
+    let send_e = opcode::SendMsg::new(Fd(client_socket.as_raw_fd()), send_msg.as_ptr());
+
+    unsafe {
+        let mut queue = ring.submission();
+        queue
+            .push(recv_e.build().user_data(0x28).into())
+            .expect("queue is full");
+        queue
+            .push(send_e.build().user_data(0x29).into())
+            .expect("queue is full");
+    }
+    ring.submit_and_wait(2)?;
+
+    let mut cqes: Vec<cqueue::Entry> = ring.completion().map(Into::into).collect();
+    cqes.sort_unstable_by_key(cqueue::Entry::user_data);
+
+    assert_eq!(cqes.len(), 2);
+    assert_eq!(cqes[0].user_data(), 0x28);
+    assert_eq!(cqes[0].result(), 8);
+    assert_eq!(cqes[1].user_data(), 0x29);
+    assert_eq!(cqes[1].result(), 8);
+
+    assert_eq!(recv_msg.peer_addr(), Some(client_socket.local_addr()?));
+    assert_eq!(recv_msg.msg_flags() & libc::MSG_TRUNC, 0);
+    drop(recv_msg);
+    assert_eq!(&buf[..8], b"datagram");
+
+    Ok(())
+}
+
+pub fn test_tcp_msghdr<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    require!(
+        test;
+        test.probe.is_supported(opcode::SendMsg::CODE);
+        test.probe.is_supported(opcode::RecvMsg::CODE);
+    );
+
+    println!("test tcp_msghdr");
+
+    let (send_stream, recv_stream) = tcp_pair()?;
+
+    // Gather from two buffers, and scatter into two buffers split at a different point.
+    let iov = [io::IoSlice::new(b"hello "), io::IoSlice::new(b"msghdr")];
+    let send_msg = types::MsgHdr::new().iov(&iov);
+
+    let (mut head, mut tail) = ([0u8; 4], [0u8; 8]);
+    let mut recv_iov = [
+        io::IoSliceMut::new(&mut head),
+        io::IoSliceMut::new(&mut tail),
+    ];
+    let mut recv_msg = types::MsgHdr::new().iov_mut(&mut recv_iov);
+
+    let send_e = opcode::SendMsg::from_msghdr(Fd(send_stream.as_raw_fd()), &send_msg);
+    let recv_e = opcode::RecvMsg::from_msghdr(Fd(recv_stream.as_raw_fd()), &mut recv_msg)
+        .flags(libc::MSG_WAITALL as u32);
+
+    unsafe {
+        let mut queue = ring.submission();
+        queue
+            .push(send_e.build().user_data(0x2a).into())
+            .expect("queue is full");
+        queue
+            .push(recv_e.build().user_data(0x2b).into())
+            .expect("queue is full");
+    }
+    ring.submit_and_wait(2)?;
+
+    let mut cqes: Vec<cqueue::Entry> = ring.completion().map(Into::into).collect();
+    cqes.sort_unstable_by_key(cqueue::Entry::user_data);
+
+    assert_eq!(cqes.len(), 2);
+    assert_eq!(cqes[0].user_data(), 0x2a);
+    assert_eq!(cqes[0].result(), 12);
+    assert_eq!(cqes[1].user_data(), 0x2b);
+    assert_eq!(cqes[1].result(), 12);
+
+    assert_eq!(&head, b"hell");
+    assert_eq!(&tail, b"o msghdr");
+
+    Ok(())
+}
+
 pub fn test_udp_send_with_dest<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
     ring: &mut IoUring<S, C>,
     test: &Test,
@@ -234,3 +234,136 @@ pub fn test_eventfd_poll_multi<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
 
     Ok(())
 }
+
+pub fn test_pipe_poll_multi<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    require!(
+        test;
+        test.probe.is_supported(opcode::PollAdd::CODE);
+        test.probe.is_supported(opcode::MkDirAt::CODE); // Available since 5.15 when the multi poll was available 5.13.
+    );
+
+    println!("test pipe_poll_multi");
+
+    let (rx, mut tx) = unsafe {
+        let mut fds = [0; 2];
+        if libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC) != 0 {
+            return Err(io::Error::last_os_error().into());
+        }
+        (File::from_raw_fd(fds[0]), File::from_raw_fd(fds[1]))
+    };
+
+    let poll_e = opcode::PollAdd::new(types::Fd(rx.as_raw_fd()), libc::POLLIN as _).multi(true);
+
+    unsafe {
+        ring.submission()
+            .push(poll_e.build().user_data(0x05).into())
+            .expect("queue is full");
+    }
+    ring.submit()?;
+
+    // Every write makes the pipe readable again, and is reported by the same poll request.
+    for _ in 0..3 {
+        tx.write_all(b"x")?;
+        ring.submit_and_wait(1)?;
+
+        let cqes: Vec<cqueue::Entry> = ring.completion().map(Into::into).collect();
+        assert_eq!(cqes.len(), 1);
+        assert_eq!(cqes[0].user_data(), 0x05);
+        assert!(io_uring::cqueue::more(cqes[0].flags()));
+        assert_eq!(cqes[0].result(), libc::POLLIN as i32);
+    }
+
+    // The poll stays armed until it is cancelled.
+    let cancel_e = opcode::AsyncCancel::new(0x05);
+
+    unsafe {
+        ring.submission()
+            .push(cancel_e.build().user_data(0x06).into())
+            .expect("queue is full");
+    }
+    ring.submit_and_wait(2)?;
+
+    let mut cqes: Vec<cqueue::Entry> = ring.completion().map(Into::into).collect();
+    cqes.sort_unstable_by_key(cqueue::Entry::user_data);
+
+    assert_eq!(cqes.len(), 2);
+    assert_eq!(cqes[0].user_data(), 0x05);
+    assert!(!io_uring::cqueue::more(cqes[0].flags()));
+    assert_eq!(cqes[0].result(), -libc::ECANCELED);
+    assert_eq!(cqes[1].user_data(), 0x06);
+    assert_eq!(cqes[1].result(), 0);
+
+    Ok(())
+}
+
+pub fn test_pipe_epoll_ctl<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    require!(
+        test;
+        test.probe.is_supported(opcode::EpollCtl::CODE);
+    );
+
+    println!("test pipe_epoll_ctl");
+
+    let epfd = unsafe {
+        let fd = libc::epoll_create1(libc::EPOLL_CLOEXEC);
+        if fd == -1 {
+            return Err(io::Error::last_os_error().into());
+        }
+        File::from_raw_fd(fd)
+    };
+
+    let (rx, mut tx) = unsafe {
+        let mut fds = [0; 2];
+        if libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC) != 0 {
+            return Err(io::Error::last_os_error().into());
+        }
+        (File::from_raw_fd(fds[0]), File::from_raw_fd(fds[1]))
+    };
+
+    let ev = libc::epoll_event {
+        events: libc::EPOLLIN as _,
+        u64: 0x77,
+    };
+
+    // Adding the same fd twice fails the second time.
+    for user_data in [0x07, 0x08] {
+        let epoll_e = opcode::EpollCtl::new(
+            types::Fd(epfd.as_raw_fd()),
+            types::Fd(rx.as_raw_fd()),
+            libc::EPOLL_CTL_ADD,
+            &ev as *const libc::epoll_event as *const types::epoll_event,
+        );
+
+        unsafe {
+            ring.submission()
+                .push(epoll_e.build().user_data(user_data).into())
+                .expect("queue is full");
+        }
+        ring.submit_and_wait(1)?;
+    }
+
+    let cqes: Vec<cqueue::Entry> = ring.completion().map(Into::into).collect();
+
+    assert_eq!(cqes.len(), 2);
+    assert_eq!(cqes[0].user_data(), 0x07);
+    assert_eq!(cqes[0].result(), 0);
+    assert_eq!(cqes[1].user_data(), 0x08);
+    assert_eq!(cqes[1].result(), -libc::EEXIST);
+
+    // The pipe is now watched by the epoll instance.
+    tx.write_all(b"x")?;
+
+    let mut events = [libc::epoll_event { events: 0, u64: 0 }; 2];
+    let n = unsafe { libc::epoll_wait(epfd.as_raw_fd(), events.as_mut_ptr(), 2, 1000) };
+    assert_eq!(n, 1);
+    assert_eq!({ events[0].u64 }, 0x77);
+    assert_eq!({ events[0].events }, libc::EPOLLIN as u32);
+
+    Ok(())
+}
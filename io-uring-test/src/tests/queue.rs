@@ -1,5 +1,8 @@
 use crate::Test;
-use io_uring::{cqueue::{self, EntryMarker}, opcode, squeue, types, IoUring};
+use io_uring::{
+    cqueue::{self, EntryMarker},
+    opcode, squeue, types, IoUring,
+};
 
 pub fn test_nop<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
     ring: &mut IoUring<S, C>,
@@ -29,6 +32,40 @@ pub fn test_nop<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
     Ok(())
 }
 
+pub fn test_nop_inject_result<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    // Older kernels silently ignore IORING_NOP_INJECT_RESULT, so only run this where it is known
+    // to exist, which includes every kernel with the opcode for Bind (6.11).
+    require! {
+        test;
+        test.probe.is_supported(opcode::Bind::CODE);
+    }
+
+    println!("test nop_inject_result");
+
+    let nop_e = opcode::Nop::new()
+        .result(-libc::EINVAL)
+        .build()
+        .user_data(0x43)
+        .into();
+
+    unsafe {
+        ring.submission().push(nop_e).expect("queue is full");
+    }
+
+    ring.submit_and_wait(1)?;
+
+    let cqes: Vec<cqueue::Entry> = ring.completion().map(Into::into).collect();
+
+    assert_eq!(cqes.len(), 1);
+    assert_eq!(cqes[0].user_data(), 0x43);
+    assert_eq!(cqes[0].result(), -libc::EINVAL);
+
+    Ok(())
+}
+
 pub fn test_batch<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
     ring: &mut IoUring<S, C>,
     test: &Test,
@@ -77,6 +114,328 @@ pub fn test_batch<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
     Ok(())
 }
 
+pub fn test_batch_partial<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    _ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    require! {
+        test;
+    }
+
+    println!("test batch_partial");
+
+    let mut ring: IoUring<S, C> = IoUring::builder().build(64)?;
+    let sqes: Vec<S> = vec![opcode::Nop::new().build().user_data(0x0a).into(); 100];
+
+    let pushed = unsafe { ring.submission().push_partial(&sqes) };
+    assert_eq!(pushed, 64);
+    assert!(ring.submission().is_full());
+    assert_eq!(
+        unsafe { ring.submission().push_partial(&sqes[pushed..]) },
+        0
+    );
+
+    ring.submit_and_wait(64)?;
+    assert_eq!(ring.completion().count(), 64);
+
+    let rest = unsafe { ring.submission().push_partial(&sqes[pushed..]) };
+    assert_eq!(rest, 36);
+
+    ring.submit_and_wait(36)?;
+    let cqes: Vec<cqueue::Entry> = ring.completion().map(Into::into).collect();
+    assert_eq!(cqes.len(), 36);
+    assert!(cqes.iter().all(|cqe| cqe.user_data() == 0x0a));
+
+    Ok(())
+}
+
+pub fn test_submit_batch<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    _ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    require! {
+        test;
+    }
+
+    println!("test submit_batch");
+
+    let mut ring: IoUring<S, C> = IoUring::builder().setup_cqsize(1024).build(64)?;
+    check_submit_batch(&mut ring)
+}
+
+pub fn test_submit_batch_sqpoll<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    _ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    // Unprivileged SQPOLL needs 5.11, as does the opcode for Shutdown.
+    require! {
+        test;
+        test.probe.is_supported(opcode::Shutdown::CODE);
+    }
+
+    println!("test submit_batch_sqpoll");
+
+    let mut ring: IoUring<S, C> = IoUring::builder()
+        .setup_sqpoll(10)
+        .setup_cqsize(1024)
+        .build(8)?;
+    check_submit_batch(&mut ring)
+}
+
+fn check_submit_batch<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    ring: &mut IoUring<S, C>,
+) -> anyhow::Result<()> {
+    let sqes: Vec<S> = (0..1000)
+        .map(|i| opcode::Nop::new().build().user_data(i).into())
+        .collect();
+
+    let submitted = unsafe { ring.submit_batch(&sqes, 1000)? };
+    assert_eq!(submitted, 1000);
+    assert!(ring.submission().is_empty());
+
+    let mut user_data: Vec<u64> = ring.completion().map(|cqe| cqe.user_data()).collect();
+    user_data.sort_unstable();
+    assert_eq!(user_data, (0..1000).collect::<Vec<u64>>());
+
+    Ok(())
+}
+
+pub fn test_flush_overflow<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    _ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    require! {
+        test;
+        _ring.params().is_feature_nodrop();
+    }
+
+    println!("test flush_overflow");
+
+    let mut ring: IoUring<S, C> = IoUring::builder().build(4)?;
+    let cq_entries = ring.params().cq_entries() as usize;
+
+    // Post three completions more than the completion queue can hold, without reaping any.
+    let mut posted = 0;
+    while posted < cq_entries + 3 {
+        let nop_e: S = opcode::Nop::new().build().user_data(posted as _).into();
+        unsafe { ring.submission().push(nop_e).expect("queue is full") };
+        ring.submit()?;
+        posted += 1;
+    }
+
+    assert!(ring.submission().cq_overflow());
+    assert_eq!(ring.completion().len(), cq_entries);
+
+    let mut user_data: Vec<u64> = ring.completion().map(|cqe| cqe.user_data()).collect();
+    assert_eq!(ring.submitter().flush_overflow()?, 3);
+    assert!(!ring.submission().cq_overflow());
+    user_data.extend(ring.completion().map(|cqe| cqe.user_data()));
+
+    assert_eq!(user_data, (0..posted as u64).collect::<Vec<_>>());
+    assert_eq!(ring.submitter().flush_overflow()?, 0);
+
+    Ok(())
+}
+
+pub fn test_into_split<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    _ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    require! {
+        test;
+    }
+
+    println!("test into_split");
+
+    let ring: IoUring<S, C> = IoUring::builder().setup_cqsize(256).build(16)?;
+    let (mut submission, mut completion) = ring.into_split();
+
+    let mut user_data = std::thread::scope(|s| -> anyhow::Result<Vec<u64>> {
+        let reaper = s.spawn(move || -> std::io::Result<Vec<u64>> {
+            let mut user_data = Vec::new();
+            while user_data.len() < 100 {
+                completion.wait(1)?;
+                user_data.extend(completion.completion().map(|cqe| cqe.user_data()));
+            }
+            Ok(user_data)
+        });
+
+        for i in 0..100 {
+            let nop_e: S = opcode::Nop::new().build().user_data(i).into();
+            while unsafe { submission.submission().push(nop_e.clone()) }.is_err() {
+                submission.submit()?;
+            }
+        }
+        submission.submit()?;
+
+        Ok(reaper.join().unwrap()?)
+    })?;
+    user_data.sort_unstable();
+    assert_eq!(user_data, (0..100).collect::<Vec<u64>>());
+
+    Ok(())
+}
+
+pub fn test_into_split_registered_ring_fd<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    _ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    // register_ring_fd was introduced in kernel 5.18, as was the opcode for MsgRingData.
+    require! {
+        test;
+        test.probe.is_supported(opcode::MsgRingData::CODE);
+    }
+
+    println!("test into_split_registered_ring_fd");
+
+    let ring: IoUring<S, C> = IoUring::builder().build(8)?;
+    ring.submitter().register_ring_fd()?;
+    let (mut submission, completion) = ring.into_split();
+
+    // The halves never use the registration, and can't make one.
+    let err = submission.submitter().register_ring_fd().unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::Unsupported);
+    let err = submission.submitter().unregister_ring_fd().unwrap_err();
+    assert_eq!(err.raw_os_error(), Some(libc::EINVAL));
+
+    let nop_e = opcode::Nop::new().build().user_data(0x10).into();
+    unsafe {
+        submission.submission().push(nop_e).expect("queue is full");
+    }
+    submission.submit()?;
+
+    // The reaper registers a ring of its own, likely at the same index, which must not be waited
+    // on in place of the split one.
+    let user_data = std::thread::scope(|s| {
+        s.spawn(move || -> anyhow::Result<Vec<u64>> {
+            let other: IoUring<S, C> = IoUring::builder().build(2)?;
+            other.submitter().register_ring_fd()?;
+
+            let mut completion = completion;
+            completion.wait(1)?;
+            Ok(completion.completion().map(|cqe| cqe.user_data()).collect())
+        })
+        .join()
+        .unwrap()
+    })?;
+    assert_eq!(user_data, [0x10]);
+
+    Ok(())
+}
+
+pub fn test_sq_remaining<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    require! {
+        test;
+    }
+
+    println!("test sq_remaining");
+
+    let mut sq = ring.submission();
+    let capacity = sq.capacity();
+    assert_eq!(sq.remaining(), capacity);
+
+    for i in 1..capacity {
+        unsafe {
+            sq.push(opcode::Nop::new().build().user_data(0x0b).into())
+                .expect("queue is full");
+        }
+        assert_eq!(sq.len(), i);
+        assert_eq!(sq.remaining() + sq.len(), capacity);
+        assert!(!sq.is_full());
+    }
+    drop(sq);
+
+    ring.submit_and_wait(capacity - 1)?;
+    assert_eq!(ring.completion().count(), capacity - 1);
+    assert_eq!(ring.submission().remaining(), capacity);
+
+    Ok(())
+}
+
+pub fn test_sq_in_place<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    require! {
+        test;
+    }
+
+    println!("test sq_in_place");
+
+    let mut sq = ring.submission();
+    let (first, second) = sq.as_mut_slices();
+    assert!(first.len() + second.len() >= 3);
+
+    let slots = first.iter_mut().chain(second.iter_mut());
+    for (user_data, slot) in (0x0c..0x0f).zip(slots) {
+        slot.write(opcode::Nop::new().build().user_data(user_data).into());
+    }
+    unsafe {
+        sq.commit(3);
+    }
+    assert_eq!(sq.len(), 3);
+    drop(sq);
+
+    ring.submit_and_wait(3)?;
+
+    let mut user_data: Vec<u64> = ring
+        .completion()
+        .map(Into::<cqueue::Entry>::into)
+        .map(|cqe| {
+            assert_eq!(cqe.result(), 0);
+            cqe.user_data()
+        })
+        .collect();
+    user_data.sort_unstable();
+    assert_eq!(user_data, [0x0c, 0x0d, 0x0e]);
+
+    Ok(())
+}
+
+pub fn test_skip_success<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    require! {
+        test;
+        ring.params().is_feature_skip_cqe_on_success();
+    }
+
+    println!("test skip_success");
+
+    // Only the last link of the chain posts a completion.
+    const LINKS: u64 = 4;
+    let chain: Vec<S> = (1..=LINKS)
+        .map(|user_data| {
+            let sqe = opcode::Nop::new().build().user_data(user_data);
+            if user_data == LINKS {
+                sqe.into()
+            } else {
+                sqe.flags(squeue::Flags::SKIP_SUCCESS).into()
+            }
+        })
+        .collect();
+
+    unsafe {
+        ring.submission()
+            .push_linked(&chain)
+            .expect("queue is full");
+    }
+
+    ring.submit_and_wait(1)?;
+
+    let cqes: Vec<cqueue::Entry> = ring.completion().map(Into::into).collect();
+
+    assert_eq!(cqes.len(), 1);
+    assert_eq!(cqes[0].user_data(), LINKS);
+    assert_eq!(cqes[0].result(), 0);
+
+    Ok(())
+}
+
 pub fn test_queue_split<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
     ring: &mut IoUring<S, C>,
     test: &Test,
@@ -152,6 +511,93 @@ pub fn test_debug_print<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
     Ok(())
 }
 
+pub fn test_ring_raw_fd<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    use std::os::unix::io::AsRawFd;
+
+    require! {
+        test;
+    }
+
+    println!("test ring_raw_fd");
+
+    let fd = ring.as_raw_fd();
+    assert!(fd >= 0);
+    assert_eq!(ring.as_raw_fd(), fd);
+
+    let flags = unsafe { libc::fcntl(fd, libc::F_GETFD) };
+    assert!(flags != -1, "{}", std::io::Error::last_os_error());
+
+    // The fd stays the same while the ring is in use.
+    let nop_e: S = opcode::Nop::new().build().user_data(0x42).into();
+    unsafe { ring.submission().push(nop_e).expect("queue is full") };
+    ring.submit_and_wait(1)?;
+    assert!(ring.completion().next().is_some());
+    assert_eq!(ring.as_raw_fd(), fd);
+
+    Ok(())
+}
+
+pub fn test_completion_ready<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    _ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    use std::os::unix::io::AsRawFd;
+
+    require! {
+        test;
+    }
+
+    println!("test completion_ready");
+
+    let mut ring: IoUring<S, C> = IoUring::builder().build(8)?;
+
+    let efd = unsafe { libc::eventfd(0, libc::EFD_CLOEXEC | libc::EFD_NONBLOCK) };
+    if efd == -1 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    ring.submitter().register_eventfd(efd)?;
+
+    let readable = |fd, timeout| {
+        let mut pfd = libc::pollfd {
+            fd,
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        let ret = unsafe { libc::poll(&mut pfd, 1, timeout) };
+        ret == 1 && pfd.revents & libc::POLLIN != 0
+    };
+
+    assert!(!ring.completion_ready());
+    assert!(!readable(ring.as_raw_fd(), 0));
+    assert!(!readable(efd, 0));
+
+    let nop_e: S = opcode::Nop::new().build().user_data(0x42).into();
+    unsafe { ring.submission().push(nop_e).expect("queue is full") };
+    ring.submit()?;
+
+    assert!(readable(ring.as_raw_fd(), 1000));
+    assert!(readable(efd, 1000));
+    assert!(ring.completion_ready());
+
+    let mut count = 0u64;
+    let ret = unsafe { libc::read(efd, &mut count as *mut u64 as *mut _, 8) };
+    assert_eq!(ret, 8);
+    assert!(count >= 1);
+
+    let cqe: cqueue::Entry = ring.completion().next().expect("cqueue is empty").into();
+    assert_eq!(cqe.user_data(), 0x42);
+    assert!(!ring.completion_ready());
+    assert!(!readable(ring.as_raw_fd(), 0));
+
+    ring.submitter().unregister_eventfd()?;
+    unsafe { libc::close(efd) };
+
+    Ok(())
+}
+
 pub fn test_msg_ring_data<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
     ring: &mut IoUring<S, C>,
     test: &Test,
@@ -189,7 +635,7 @@ pub fn test_msg_ring_data<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
     assert_eq!(source_cqes[0].result(), 0);
     assert_eq!(source_cqes[0].flags(), 0);
 
-    let dest_cqes: Vec<cqueue::Entry> = dest_ring.completion().map(Into::into).collect();
+    let dest_cqes: Vec<cqueue::Entry> = dest_ring.completion().collect();
     assert_eq!(dest_cqes.len(), 1);
     assert_eq!(dest_cqes[0].user_data(), user_data);
     assert_eq!(dest_cqes[0].result(), result);
@@ -198,6 +644,109 @@ pub fn test_msg_ring_data<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
     Ok(())
 }
 
+pub fn test_msg_ring_data_user_flags<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    use std::os::unix::io::AsRawFd;
+
+    // Passing user flags requires 6.3 and cannot be probed at runtime.
+    // Thus, we additionally check for the `IORING_OP_FUTEX_WAIT` opcode (>= 6.7)
+    // as a proxy instead.
+    require!(
+        test;
+        test.probe.is_supported(opcode::MsgRingData::CODE);
+        test.probe.is_supported(opcode::FutexWait::CODE);
+    );
+
+    println!("test msg_ring_data_user_flags");
+
+    // The destination ring gets a completion carrying the passed flags.
+    let mut dest_ring = IoUring::new(1)?;
+    let fd = types::Fd(dest_ring.as_raw_fd());
+    let result = 83; // b'S'
+    let user_data = 75; // b'K'
+    let user_flags = 0x8000;
+    unsafe {
+        ring.submission()
+            .push(
+                opcode::MsgRingData::new(fd, result, user_data, Some(user_flags))
+                    .build()
+                    .into(),
+            )
+            .expect("queue is full");
+    }
+    ring.submit_and_wait(1)?;
+
+    let source_cqes: Vec<cqueue::Entry> = ring.completion().map(Into::into).collect();
+    assert_eq!(source_cqes.len(), 1);
+    assert_eq!(source_cqes[0].user_data(), 0);
+    assert_eq!(source_cqes[0].result(), 0);
+
+    let dest_cqes: Vec<cqueue::Entry> = dest_ring.completion().collect();
+    assert_eq!(dest_cqes.len(), 1);
+    assert_eq!(dest_cqes[0].user_data(), user_data);
+    assert_eq!(dest_cqes[0].result(), result);
+    assert_eq!(dest_cqes[0].flags(), user_flags);
+
+    Ok(())
+}
+
+pub fn test_msg_ring_send_fd_cqe_skip<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    use std::os::unix::io::AsRawFd;
+
+    // `IORING_MSG_RING_CQE_SKIP` appeared in kernel 6.1 and cannot be probed at runtime.
+    // Thus, we additionally check for the `IORING_OP_SENDMSG_ZC` opcode (>= 6.1)
+    // as a proxy instead.
+    require!(
+        test;
+        test.probe.is_supported(opcode::MsgRingData::CODE);
+        test.probe.is_supported(opcode::SendMsgZc::CODE);
+    );
+
+    println!("test msg_ring_send_fd_cqe_skip");
+
+    let _ = ring.submitter().unregister_files();
+    let tmp = tempfile::tempfile()?;
+    ring.submitter().register_files(&[tmp.as_raw_fd()])?;
+
+    let mut dest_ring = IoUring::new(8)?;
+    dest_ring.submitter().register_files_sparse(1)?;
+
+    // Only the source ring gets a completion, the file still lands in slot 0.
+    unsafe {
+        let fd = types::Fd(dest_ring.as_raw_fd());
+        let dest_slot = types::DestinationSlot::try_from_slot_target(0).unwrap();
+        ring.submission()
+            .push(
+                opcode::MsgRingSendFd::new(fd, types::Fixed(0), dest_slot, 33)
+                    .opcode_flags(types::MSG_RING_CQE_SKIP)
+                    .build()
+                    .user_data(0x42)
+                    .into(),
+            )
+            .expect("queue is full");
+    }
+    ring.submit_and_wait(1)?;
+
+    let source_cqes: Vec<cqueue::Entry> = ring.completion().map(Into::into).collect();
+    assert_eq!(source_cqes.len(), 1);
+    assert_eq!(source_cqes[0].user_data(), 0x42);
+    assert_eq!(source_cqes[0].result(), 0);
+
+    assert!(dest_ring.completion().is_empty());
+
+    // The slot is occupied, so unregistering it succeeds and a second time fails.
+    ring.submitter().unregister_files()?;
+    dest_ring.submitter().unregister_files()?;
+    dest_ring.submitter().unregister_files().unwrap_err();
+
+    Ok(())
+}
+
 pub fn test_msg_ring_send_fd<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
     ring: &mut IoUring<S, C>,
     test: &Test,
@@ -256,7 +805,7 @@ pub fn test_msg_ring_send_fd<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
         assert_eq!(source_cqes[0].result(), 0);
         assert_eq!(source_cqes[0].flags(), 0);
 
-        let dest_cqes: Vec<cqueue::Entry> = temp_ring.completion().map(Into::into).collect();
+        let dest_cqes: Vec<cqueue::Entry> = temp_ring.completion().collect();
         assert_eq!(dest_cqes.len(), 1);
         assert_eq!(dest_cqes[0].user_data(), 22);
         assert_eq!(dest_cqes[0].result(), 0);
@@ -281,7 +830,7 @@ pub fn test_msg_ring_send_fd<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
     }
     temp_ring.submit_and_wait(1)?;
     {
-        let source_cqes: Vec<cqueue::Entry> = temp_ring.completion().map(Into::into).collect();
+        let source_cqes: Vec<cqueue::Entry> = temp_ring.completion().collect();
         assert_eq!(source_cqes.len(), 1);
         assert_eq!(source_cqes[0].user_data(), 0);
         assert_eq!(source_cqes[0].result(), 0);
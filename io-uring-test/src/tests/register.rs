@@ -1,5 +1,8 @@
 use crate::Test;
-use io_uring::{cqueue, opcode, squeue, IoUring};
+use io_uring::cqueue::EntryMarker;
+use io_uring::{cqueue, opcode, squeue, types, IoUring};
+use std::io::Write;
+use std::os::unix::io::AsRawFd;
 
 pub fn test_register_files_sparse<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
     ring: &mut IoUring<S, C>,
@@ -68,3 +71,487 @@ pub fn test_register_files_sparse<S: squeue::EntryMarker, C: cqueue::EntryMarker
 
     Ok(())
 }
+
+pub fn test_register_files_sparse_update<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    require!(
+        test;
+        test.probe.is_supported(opcode::UringCmd16::CODE);
+        test.probe.is_supported(opcode::Read::CODE);
+    );
+
+    println!("test register_files_sparse_update");
+
+    let mut fd = tempfile::tempfile()?;
+    fd.write_all(b"sparse slot")?;
+
+    ring.submitter().register_files_sparse(4)?;
+
+    // Fill slot 2 of the empty table, leaving the others sparse.
+    let updated = ring
+        .submitter()
+        .register_files_update(2, &[fd.as_raw_fd()])?;
+    assert_eq!(updated, 1);
+
+    let mut buf = [0u8; 11];
+    let read_e = opcode::Read::new(types::Fixed(2), buf.as_mut_ptr(), buf.len() as _)
+        .offset(0)
+        .build()
+        .user_data(0x42)
+        .into();
+
+    // Slot 1 was never filled, so reading through it fails.
+    let read_sparse_e = opcode::Read::new(types::Fixed(1), buf.as_mut_ptr(), buf.len() as _)
+        .offset(0)
+        .build()
+        .user_data(0x43)
+        .into();
+
+    unsafe {
+        ring.submission().push(read_e).expect("queue is full");
+    }
+    ring.submit_and_wait(1)?;
+
+    let cqe: cqueue::Entry = ring.completion().next().expect("cqueue is empty").into();
+    assert_eq!(cqe.user_data(), 0x42);
+    assert_eq!(cqe.result(), 11);
+    assert_eq!(&buf, b"sparse slot");
+
+    unsafe {
+        ring.submission()
+            .push(read_sparse_e)
+            .expect("queue is full");
+    }
+    ring.submit_and_wait(1)?;
+
+    let cqe: cqueue::Entry = ring.completion().next().expect("cqueue is empty").into();
+    assert_eq!(cqe.user_data(), 0x43);
+    assert_eq!(cqe.result(), -libc::EBADF);
+
+    ring.submitter().unregister_files()?;
+
+    Ok(())
+}
+
+pub fn test_register_files_update_tag<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    require!(
+        test;
+        test.probe.is_supported(opcode::UringCmd16::CODE);
+    );
+
+    println!("test register_files_update_tag");
+
+    let fd = tempfile::tempfile()?;
+
+    ring.submitter().register_files_sparse(4)?;
+
+    // Every file needs a tag, and nothing is updated otherwise.
+    let fds = [fd.as_raw_fd(), fd.as_raw_fd()];
+    for (fds, tags) in [
+        (&fds[..], &[0x1234][..]),
+        (&fds[..1], &[0x1234, 0x5678][..]),
+    ] {
+        let err = ring
+            .submitter()
+            .register_files_update_tag(1, fds, tags)
+            .unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    }
+    ring.submit()?;
+    assert!(ring.completion().is_empty());
+
+    let updated = ring
+        .submitter()
+        .register_files_update_tag(1, &[fd.as_raw_fd()], &[0x1234])?;
+    assert_eq!(updated, 1);
+
+    // Clearing the slot releases the file, which posts a CQE carrying its tag.
+    let updated = ring.submitter().register_files_update(1, &[-1])?;
+    assert_eq!(updated, 1);
+
+    ring.submit_and_wait(1)?;
+
+    let cqe: cqueue::Entry = ring.completion().next().expect("cqueue is empty").into();
+    assert_eq!(cqe.user_data(), 0x1234);
+    assert_eq!(cqe.result(), 0);
+
+    ring.submitter().unregister_files()?;
+
+    Ok(())
+}
+
+pub fn test_register_eventfd<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    require!(
+        test;
+    );
+
+    println!("test register_eventfd");
+
+    let efd = unsafe { libc::eventfd(0, libc::EFD_CLOEXEC | libc::EFD_NONBLOCK) };
+    if efd == -1 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+
+    // Returns the eventfd counter, resetting it, or 0 if it was not signalled.
+    let read_counter = || {
+        let mut count = 0u64;
+        match unsafe { libc::read(efd, &mut count as *mut u64 as *mut _, 8) } {
+            8 => count,
+            _ => 0,
+        }
+    };
+
+    let nop = |ring: &mut IoUring<S, C>, flags| -> anyhow::Result<()> {
+        let nop_e: S = opcode::Nop::new()
+            .build()
+            .flags(flags)
+            .user_data(0x42)
+            .into();
+        unsafe { ring.submission().push(nop_e).expect("queue is full") };
+        ring.submit_and_wait(1)?;
+        let cqe: cqueue::Entry = ring.completion().next().expect("cqueue is empty").into();
+        assert_eq!(cqe.user_data(), 0x42);
+        Ok(())
+    };
+
+    ring.submitter().register_eventfd(efd)?;
+    let err = ring.submitter().register_eventfd(efd).unwrap_err();
+    assert_eq!(err.raw_os_error(), Some(libc::EBUSY));
+
+    nop(ring, squeue::Flags::empty())?;
+    assert!(read_counter() >= 1);
+
+    ring.submitter().unregister_eventfd()?;
+    nop(ring, squeue::Flags::empty())?;
+    assert_eq!(read_counter(), 0);
+
+    // The async variant skips completions posted inline, but not those from io-wq workers.
+    ring.submitter().register_eventfd_async(efd)?;
+    nop(ring, squeue::Flags::empty())?;
+    assert_eq!(read_counter(), 0);
+    nop(ring, squeue::Flags::ASYNC)?;
+    assert!(read_counter() >= 1);
+
+    ring.submitter().unregister_eventfd()?;
+    unsafe { libc::close(efd) };
+
+    Ok(())
+}
+
+pub fn test_register_ring_fd<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    // register_ring_fd was introduced in kernel 5.18, as was the opcode for MsgRingData.
+    require!(
+        test;
+        test.probe.is_supported(opcode::MsgRingData::CODE);
+        test.probe.is_supported(opcode::Nop::CODE);
+    );
+
+    println!("test register_ring_fd");
+
+    let index = ring.submitter().register_ring_fd()?;
+
+    // Registering twice is rejected.
+    let err = ring.submitter().register_ring_fd().unwrap_err();
+    assert_eq!(err.raw_os_error(), Some(libc::EEXIST));
+
+    // Submit and wait through the registered index.
+    let nop_e = opcode::Nop::new().build().user_data(0x44).into();
+    unsafe {
+        ring.submission().push(nop_e).expect("queue is full");
+    }
+    ring.submit_and_wait(1)?;
+
+    let cqe: cqueue::Entry = ring.completion().next().expect("cqueue is empty").into();
+    assert_eq!(cqe.user_data(), 0x44);
+    assert_eq!(cqe.result(), 0);
+
+    ring.submitter().unregister_ring_fd()?;
+
+    let err = ring.submitter().unregister_ring_fd().unwrap_err();
+    assert_eq!(err.raw_os_error(), Some(libc::EINVAL));
+
+    // The ring still works through its fd, and the freed index can be registered again.
+    ring.submit()?;
+    assert_eq!(ring.submitter().register_ring_fd()?, index);
+    ring.submitter().unregister_ring_fd()?;
+
+    // A ring dropped while its fd is registered releases the registration.
+    {
+        let temp_ring: IoUring<S, C> = IoUring::builder().build(2)?;
+        temp_ring.submitter().register_ring_fd()?;
+    }
+
+    // The registration is per thread: another thread enters the ring through its fd, even when
+    // it registered a ring of its own at the same index.
+    ring.submitter().register_ring_fd()?;
+    let nop_e = opcode::Nop::new().build().user_data(0x45).into();
+    unsafe {
+        ring.submission().push(nop_e).expect("queue is full");
+    }
+    let shared = &*ring;
+    std::thread::scope(|scope| {
+        scope
+            .spawn(|| -> anyhow::Result<()> {
+                let mut other: IoUring<S, C> = IoUring::builder().build(2)?;
+                other.submitter().register_ring_fd()?;
+
+                let err = shared.submitter().unregister_ring_fd().unwrap_err();
+                assert_eq!(err.raw_os_error(), Some(libc::EINVAL));
+
+                shared.submit_and_wait(1)?;
+                assert!(other.completion().is_empty());
+                Ok(())
+            })
+            .join()
+            .unwrap()
+    })?;
+
+    let cqe: cqueue::Entry = ring.completion().next().expect("cqueue is empty").into();
+    assert_eq!(cqe.user_data(), 0x45);
+    ring.submitter().unregister_ring_fd()?;
+
+    Ok(())
+}
+
+pub fn test_register_iowq_max_workers<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    // register_iowq_max_workers was introduced in kernel 5.15, as was the opcode for MkDirAt.
+    require!(
+        test;
+        test.probe.is_supported(opcode::MkDirAt::CODE);
+    );
+
+    println!("test register_iowq_max_workers");
+
+    // Passing zeroes only reads the current limits.
+    let mut original = [0; 2];
+    ring.submitter().register_iowq_max_workers(&mut original)?;
+
+    // Cap the bounded workers, leaving the unbounded workers unchanged.
+    let mut max = [4, 0];
+    ring.submitter().register_iowq_max_workers(&mut max)?;
+    assert_eq!(max, original);
+
+    let mut max = [0; 2];
+    ring.submitter().register_iowq_max_workers(&mut max)?;
+    assert_eq!(max, [4, original[1]]);
+
+    // Restore the original limits.
+    let mut max = original;
+    ring.submitter().register_iowq_max_workers(&mut max)?;
+    assert_eq!(max, [4, original[1]]);
+
+    Ok(())
+}
+
+pub fn test_register_iowq_aff<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    // register_iowq_aff was introduced in kernel 5.14, before the opcode for MkDirAt in 5.15.
+    require!(
+        test;
+        test.probe.is_supported(opcode::MkDirAt::CODE);
+    );
+
+    println!("test register_iowq_aff");
+
+    // Pin the workers to a single CPU that this process is allowed to run on.
+    let cpu_set = unsafe {
+        let mut allowed: libc::cpu_set_t = std::mem::zeroed();
+        if libc::sched_getaffinity(0, std::mem::size_of_val(&allowed), &mut allowed) != 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+        let cpu = (0..libc::CPU_SETSIZE as usize)
+            .find(|&cpu| libc::CPU_ISSET(cpu, &allowed))
+            .expect("no allowed cpu");
+
+        let mut cpu_set: libc::cpu_set_t = std::mem::zeroed();
+        libc::CPU_SET(cpu, &mut cpu_set);
+        cpu_set
+    };
+
+    ring.submitter().register_iowq_aff(&cpu_set)?;
+    ring.submitter().unregister_iowq_aff()?;
+
+    Ok(())
+}
+
+pub fn test_register_restrictions<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    _ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    use io_uring::register::Restriction;
+
+    // register_restrictions was introduced in kernel 5.10, before the opcode for Shutdown in 5.11.
+    require!(
+        test;
+        test.probe.is_supported(opcode::Shutdown::CODE);
+    );
+
+    println!("test register_restrictions");
+
+    // Restrictions can only be installed while the rings are disabled.
+    let mut ring: IoUring<S, C> = IoUring::builder().setup_r_disabled().build(4)?;
+
+    // The kernel rejects an unknown opcode, leaving the ring unrestricted.
+    let err = ring
+        .submitter()
+        .register_restrictions(&mut [
+            Restriction::sqe_op(opcode::Nop::CODE),
+            Restriction::sqe_op(u8::MAX),
+        ])
+        .unwrap_err();
+    assert_eq!(err.raw_os_error(), Some(libc::EINVAL));
+
+    ring.submitter()
+        .register_restrictions(&mut [Restriction::sqe_op(opcode::Nop::CODE)])?;
+
+    // They cannot be replaced afterwards.
+    let err = ring
+        .submitter()
+        .register_restrictions(&mut [Restriction::sqe_op(opcode::AsyncCancel::CODE)])
+        .unwrap_err();
+    assert_eq!(err.raw_os_error(), Some(libc::EBUSY));
+
+    ring.submitter().register_enable_rings()?;
+
+    // No register opcode was allowed.
+    let mut probe = io_uring::Probe::new();
+    let err = ring.submitter().register_probe(&mut probe).unwrap_err();
+    assert_eq!(err.raw_os_error(), Some(libc::EACCES));
+
+    unsafe {
+        let mut queue = ring.submission();
+        queue
+            .push(opcode::Nop::new().build().user_data(0x61).into())
+            .expect("queue is full");
+        queue
+            .push(
+                opcode::AsyncCancel::new(0x61)
+                    .build()
+                    .user_data(0x62)
+                    .into(),
+            )
+            .expect("queue is full");
+    }
+
+    ring.submit_and_wait(2)?;
+
+    let mut cqes: Vec<cqueue::Entry> = ring.completion().map(Into::into).collect();
+    cqes.sort_unstable_by_key(cqueue::Entry::user_data);
+
+    assert_eq!(cqes.len(), 2);
+    assert_eq!(cqes[0].user_data(), 0x61);
+    assert_eq!(cqes[0].result(), 0);
+    assert_eq!(cqes[1].user_data(), 0x62);
+    assert_eq!(cqes[1].result(), -libc::EACCES);
+
+    Ok(())
+}
+
+pub fn test_register_napi<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    // register_napi was introduced in kernel 6.9, as was the opcode for Ftruncate.
+    require!(
+        test;
+        test.probe.is_supported(opcode::Ftruncate::CODE);
+    );
+
+    println!("test register_napi");
+
+    // Each registration returns the settings it replaced.
+    let mut napi = types::NapiConfig::new()
+        .busy_poll_to(50)
+        .prefer_busy_poll(true);
+    ring.submitter().register_napi(&mut napi)?;
+    assert_eq!(napi.get_busy_poll_to(), 0);
+    assert!(!napi.is_prefer_busy_poll());
+
+    let mut napi = types::NapiConfig::new().busy_poll_to(100);
+    ring.submitter().register_napi(&mut napi)?;
+    assert_eq!(napi.get_busy_poll_to(), 50);
+    assert!(napi.is_prefer_busy_poll());
+
+    let napi = ring.submitter().unregister_napi()?;
+    assert_eq!(napi.get_busy_poll_to(), 100);
+    assert!(!napi.is_prefer_busy_poll());
+
+    Ok(())
+}
+
+pub fn test_register_probe<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    use io_uring::Probe;
+
+    // The shared probe only reports Nop if IORING_REGISTER_PROBE itself works.
+    require!(
+        test;
+        test.probe.is_supported(opcode::Nop::CODE);
+    );
+
+    println!("test register_probe");
+
+    assert_eq!(Probe::new().supported_ops().count(), 0);
+
+    let mut probe = Probe::new();
+    ring.submitter().register_probe(&mut probe)?;
+
+    let ops: Vec<u8> = probe.supported_ops().collect();
+    assert_eq!(ops.first(), Some(&opcode::Nop::CODE));
+    assert!(ops.windows(2).all(|w| w[0] < w[1]));
+    for op in 0..=u8::MAX {
+        assert_eq!(probe.is_supported(op), ops.contains(&op));
+    }
+
+    Ok(())
+}
+
+pub fn test_capabilities<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    require!(
+        test;
+        test.probe.is_supported(opcode::Nop::CODE);
+    );
+
+    println!("test capabilities");
+
+    let caps = ring.capabilities()?;
+    assert!(caps.has_nop());
+    assert_eq!(
+        caps.has_send_zc(),
+        test.probe.is_supported(opcode::SendZc::CODE)
+    );
+    assert_eq!(
+        caps.has_futex(),
+        test.probe.is_supported(opcode::FutexWait::CODE)
+    );
+    assert_eq!(caps.has_ext_arg(), ring.params().is_feature_ext_arg());
+
+    // Later calls return the cached summary.
+    assert_eq!(ring.capabilities()?, caps);
+    let copied = caps;
+    assert_eq!(copied, caps);
+    assert!(std::mem::size_of::<io_uring::Capabilities>() <= 16);
+
+    Ok(())
+}
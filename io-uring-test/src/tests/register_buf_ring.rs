@@ -201,29 +201,26 @@ impl InnerBufRing {
             match e.raw_os_error() {
                 Some(libc::EINVAL) => {
                     // using buf_ring requires kernel 5.19 or greater.
-                    return Err(io::Error::new(
-                            io::ErrorKind::Other,
-                            format!("buf_ring.register returned {}, most likely indicating this kernel is not 5.19+", e),
-                            ));
+                    return Err(io::Error::other(format!(
+                        "buf_ring.register returned {}, most likely indicating this kernel is not 5.19+",
+                        e
+                    )));
                 }
                 Some(libc::EEXIST) => {
                     // Registering a duplicate bgid is not allowed. There is an `unregister`
                     // operations that can remove the first, but care must be taken that there
                     // are no outstanding operations that will still return a buffer from that
                     // one.
-                    return Err(io::Error::new(
-                            io::ErrorKind::Other,
-                            format!(
-                                "buf_ring.register returned `{}`, indicating the attempted buffer group id {} was already registered",
-                            e,
-                            bgid),
-                        ));
+                    return Err(io::Error::other(format!(
+                        "buf_ring.register returned `{}`, indicating the attempted buffer group id {} was already registered",
+                        e, bgid
+                    )));
                 }
                 _ => {
-                    return Err(io::Error::new(
-                        io::ErrorKind::Other,
-                        format!("buf_ring.register returned `{}` for group id {}", e, bgid),
-                    ));
+                    return Err(io::Error::other(format!(
+                        "buf_ring.register returned `{}` for group id {}",
+                        e, bgid
+                    )));
                 }
             }
         };
@@ -424,10 +421,7 @@ impl Builder {
         // larger than 2^15 anyway, so this is a good place to catch it. Here we return a unique
         // error that is more descriptive than the InvalidArg that would come from the interface.
         if b.ring_entries > (1 << 15) {
-            return Err(io::Error::new(
-                io::ErrorKind::Other,
-                "ring_entries exceeded 32768",
-            ));
+            return Err(io::Error::other("ring_entries exceeded 32768"));
         }
 
         // Requirement of the interface is the ring entries is a power of two, making its and our
@@ -721,3 +715,50 @@ pub fn test_register_buf_ring<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
 
     Ok(())
 }
+
+pub fn test_register_pbuf_status<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> io::Result<()> {
+    // register_pbuf_status was introduced in kernel 6.8, as was the opcode for FixedFdInstall.
+    require!(
+        test;
+        test.probe.is_supported(opcode::FixedFdInstall::CODE);
+    );
+
+    println!("test register_pbuf_status");
+
+    let buf_ring = Builder::new(889)
+        .ring_entries(4)
+        .buf_cnt(4)
+        .buf_len(128)
+        .build()?;
+
+    // Unknown buffer groups have no status.
+    let err = ring.submitter().register_pbuf_status(889).unwrap_err();
+    assert_eq!(err.raw_os_error(), Some(libc::ENOENT));
+
+    buf_ring.rc.register(ring)?;
+    assert_eq!(ring.submitter().register_pbuf_status(889)?, 0);
+
+    let text = b"The quick brown fox jumps over the lazy dog.";
+    let fd = tempfile::tempfile()?;
+    let fd = types::Fd(fd.as_raw_fd());
+    write_text_to_file(ring, fd, text)?;
+
+    // Every buffer picked by the kernel advances the head.
+    let buf0 = buf_ring_read(ring, &buf_ring, fd, text.len() as _)?;
+    assert_eq!(ring.submitter().register_pbuf_status(889)?, 1);
+
+    let buf1 = buf_ring_read(ring, &buf_ring, fd, text.len() as _)?;
+    assert_eq!(ring.submitter().register_pbuf_status(889)?, 2);
+
+    // Returning buffers moves the tail, not the head.
+    std::mem::drop(buf0);
+    std::mem::drop(buf1);
+    assert_eq!(ring.submitter().register_pbuf_status(889)?, 2);
+
+    buf_ring.rc.unregister(ring)?;
+
+    Ok(())
+}
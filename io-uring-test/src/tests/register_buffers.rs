@@ -28,7 +28,7 @@ pub fn test_register_buffers<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
         test,
         "register_buffers",
         None,
-        |ring, iovecs, _| unsafe { ring.submitter().register_buffers(&iovecs) },
+        |ring, iovecs, _| unsafe { ring.submitter().register_buffers(iovecs) },
     )?;
     _test_register_buffers(
         ring,
@@ -49,7 +49,7 @@ pub fn test_register_buffers<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
         },
     )?;
 
-    return Ok(());
+    Ok(())
 }
 
 fn _test_register_buffers<
@@ -188,6 +188,138 @@ fn _test_register_buffers<
     Ok(())
 }
 
+pub fn test_register_buffer_slots<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    require!(
+        test;
+        test.probe.is_supported(WriteFixed::CODE);
+        ring.params().is_feature_resource_tagging();
+    );
+
+    println!("test register_buffer_slots");
+
+    const SLOT_SIZE: usize = 1 << 12;
+
+    let mut file = tempfile::tempfile()?;
+    let mut region = vec![0u8; 2 * SLOT_SIZE];
+    region[..SLOT_SIZE].fill(b'a');
+    region[SLOT_SIZE..].fill(b'b');
+
+    // Safety: `region` outlives the registration, which is removed below.
+    unsafe {
+        ring.submitter()
+            .register_buffer_slots(region.as_mut_ptr(), SLOT_SIZE, &[0x10, 0x20])?;
+    }
+
+    let write_e = WriteFixed::new(
+        Fd(file.as_raw_fd()),
+        region[SLOT_SIZE..].as_ptr(),
+        SLOT_SIZE as _,
+        1,
+    )
+    .offset(0)
+    .build()
+    .user_data(0x42)
+    .into();
+
+    unsafe {
+        ring.submission().push(write_e).expect("queue is full");
+    }
+    ring.submit_and_wait(1)?;
+
+    let cqe: cqueue::Entry = ring.completion().next().expect("cqueue is empty").into();
+    assert_eq!(cqe.user_data(), 0x42);
+    assert_eq!(cqe.result(), SLOT_SIZE as i32);
+
+    let mut contents = Vec::new();
+    io::Read::read_to_end(&mut file, &mut contents)?;
+    assert_eq!(contents, vec![b'b'; SLOT_SIZE]);
+
+    // Unregistering releases both slots, each posting a CQE with its tag.
+    ring.submitter().unregister_buffers()?;
+    ring.submit_and_wait(2)?;
+
+    let mut tags: Vec<u64> = ring
+        .completion()
+        .map(Into::<cqueue::Entry>::into)
+        .map(|cqe| cqe.user_data())
+        .collect();
+    tags.sort_unstable();
+    assert_eq!(tags, [0x10, 0x20]);
+
+    Ok(())
+}
+
+pub fn test_fixed_from_buf<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    require!(
+        test;
+        test.probe.is_supported(WriteFixed::CODE);
+        test.probe.is_supported(ReadFixed::CODE);
+    );
+
+    println!("test fixed_from_buf");
+
+    let mut file = tempfile::tempfile()?;
+    file.write_all(b"registered buffers")?;
+    let fd = Fd(file.as_raw_fd());
+
+    let mut bufs = [vec![0u8; 64], vec![0u8; 64]];
+    let iovecs: Vec<iovec> = bufs
+        .iter_mut()
+        .map(|buf| iovec {
+            iov_base: buf.as_mut_ptr().cast(),
+            iov_len: buf.len(),
+        })
+        .collect();
+
+    // Safety: `bufs` outlives the registration, which is removed below.
+    unsafe { ring.submitter().register_buffers(&iovecs)? };
+
+    // Read into the middle of the second registered buffer.
+    let read_e = ReadFixed::from_buf(fd, 1, &mut bufs[1][8..18])
+        .offset(0)
+        .build()
+        .user_data(0x50)
+        .into();
+
+    unsafe { ring.submission().push(read_e).expect("queue is full") };
+    ring.submit_and_wait(1)?;
+
+    let cqe: cqueue::Entry = ring.completion().next().expect("cqueue is empty").into();
+    assert_eq!(cqe.user_data(), 0x50);
+    assert_eq!(cqe.result(), 10);
+    assert_eq!(&bufs[1][8..18], b"registered");
+    assert!(bufs[1][..8].iter().chain(&bufs[1][18..]).all(|&b| b == 0));
+
+    // And write it back out from there.
+    let write_e = WriteFixed::from_buf(fd, 1, &bufs[1][8..18])
+        .offset(18)
+        .build()
+        .user_data(0x51)
+        .into();
+
+    unsafe { ring.submission().push(write_e).expect("queue is full") };
+    ring.submit_and_wait(1)?;
+
+    let cqe: cqueue::Entry = ring.completion().next().expect("cqueue is empty").into();
+    assert_eq!(cqe.user_data(), 0x51);
+    assert_eq!(cqe.result(), 10);
+
+    ring.submitter().unregister_buffers()?;
+
+    let mut contents = Vec::new();
+    io::Seek::rewind(&mut file)?;
+    io::Read::read_to_end(&mut file, &mut contents)?;
+    assert_eq!(contents, b"registered buffersregistered");
+
+    Ok(())
+}
+
 const BUFFER_TAG: u64 = 0xbadcafe;
 const TIMEOUT_TAG: u64 = 0xbadf00d;
 
@@ -214,7 +346,7 @@ pub fn test_register_buffers_update<S: squeue::EntryMarker, C: cqueue::EntryMark
     let timeout = opcode::Timeout::new(&timeout as _)
         .build()
         .user_data(TIMEOUT_TAG);
-        // .into();
+    // .into();
     let read_sqe = opcode::ReadFixed::new(
         types::Fd(read.as_raw_fd()),
         buf.as_mut_ptr(),
@@ -234,12 +366,12 @@ pub fn test_register_buffers_update<S: squeue::EntryMarker, C: cqueue::EntryMark
     // We should not receive any other entries than the timeout
     check_only_timeout(ring)?;
 
-    // Register a sparse buffer table of 10 elements
-    ring.submitter().register_buffers_sparse(10)?;
+    // Register a sparse buffer table of 16 elements
+    ring.submitter().register_buffers_sparse(16)?;
 
     // Try read the pipe using a sparse buffer
     let cqe = {
-        write.write("yo".as_bytes())?;
+        write.write_all("yo".as_bytes())?;
 
         unsafe { ring.submission().push(read_sqe.clone().into()).unwrap() };
 
@@ -317,7 +449,9 @@ pub fn test_register_buffers_update<S: squeue::EntryMarker, C: cqueue::EntryMark
         return Err(anyhow::anyhow!("unexpected read buffer data: {:x?}", &buf));
     }
 
-    return Ok(());
+    ring.submitter().unregister_buffers()?;
+
+    Ok(())
 }
 
 /// Create a pipe and return both ends as RAII `File` handles
@@ -222,6 +222,37 @@ pub fn test_register_sync_cancel_unsubmitted<S: squeue::EntryMarker, C: cqueue::
     Ok(())
 }
 
+pub fn test_register_sync_cancel_timeout<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> io::Result<()> {
+    require!(
+        test;
+        test.probe.is_supported(opcode::SendZc::CODE);
+        test.probe.is_supported(opcode::Timeout::CODE);
+    );
+
+    // Arm a timeout that would not fire during the test, and cancel it synchronously by user_data,
+    // bounding the wait for the cancellation itself.
+    const USER_DATA: u64 = 48u64;
+
+    let ts = types::Timespec::new().sec(60);
+    let entry = opcode::Timeout::new(&ts).build().user_data(USER_DATA);
+    unsafe { ring.submission().push(entry.into()).unwrap() };
+    assert_eq!(1, ring.submitter().submit()?);
+
+    let cancel_timeout = types::Timespec::new().sec(1);
+    ring.submitter()
+        .register_sync_cancel(Some(cancel_timeout), CancelBuilder::user_data(USER_DATA))?;
+
+    let completions = wait_get_completions(ring, 1)?;
+    assert_eq!(completions.len(), 1);
+    assert_eq!(completions[0].user_data(), USER_DATA);
+    assert_eq!(completions[0].result(), -libc::ECANCELED);
+
+    Ok(())
+}
+
 /// Blocks for a short amount of time, waiting for completions to arrive.
 ///
 /// Returns all completions that have arrived.
@@ -0,0 +1,871 @@
+use crate::Test;
+use io_uring::cqueue::EntryMarker;
+use io_uring::{cqueue, opcode, squeue, types, IoUring};
+use std::thread;
+use std::time::Duration;
+
+pub fn test_setup_single_issuer<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    _ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    // IORING_SETUP_SINGLE_ISSUER was introduced in kernel 6.0, as was the opcode for SendZc.
+    require!(
+        test;
+        test.probe.is_supported(opcode::SendZc::CODE);
+    );
+
+    println!("test setup_single_issuer");
+
+    let mut ring: IoUring<S, C> = IoUring::builder().setup_single_issuer().build(2)?;
+    assert!(ring.params().is_setup_single_issuer());
+
+    let nop_e = opcode::Nop::new().build().user_data(0x42).into();
+    unsafe {
+        ring.submission().push(nop_e).expect("queue is full");
+    }
+
+    // The ring belongs to the task that created it, so submitting from another thread fails.
+    let err = std::thread::scope(|s| s.spawn(|| ring.submit()).join().unwrap()).unwrap_err();
+    assert_eq!(err.raw_os_error(), Some(libc::EEXIST));
+
+    ring.submit_and_wait(1)?;
+
+    let cqes: Vec<cqueue::Entry> = ring.completion().map(Into::into).collect();
+    assert_eq!(cqes.len(), 1);
+    assert_eq!(cqes[0].user_data(), 0x42);
+    assert_eq!(cqes[0].result(), 0);
+
+    Ok(())
+}
+
+pub fn test_setup_defer_taskrun<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    _ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    // IORING_SETUP_DEFER_TASKRUN was introduced in kernel 6.1, as was the opcode for SendMsgZc.
+    require!(
+        test;
+        test.probe.is_supported(opcode::SendMsgZc::CODE);
+    );
+
+    println!("test setup_defer_taskrun");
+
+    // DEFER_TASKRUN without SINGLE_ISSUER is rejected before the ring is set up.
+    let err = IoUring::<S, C>::builder()
+        .setup_defer_taskrun()
+        .build(2)
+        .err()
+        .expect("setup_defer_taskrun without setup_single_issuer should fail");
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+
+    let mut ring: IoUring<S, C> = IoUring::builder()
+        .setup_single_issuer()
+        .setup_defer_taskrun()
+        .build(2)?;
+    assert!(ring.params().is_setup_single_issuer());
+    assert!(ring.params().is_setup_defer_taskrun());
+
+    let nop_e = opcode::Nop::new().build().user_data(0x42).into();
+    unsafe {
+        ring.submission().push(nop_e).expect("queue is full");
+    }
+
+    ring.submit_and_wait(1)?;
+
+    let cqes: Vec<cqueue::Entry> = ring.completion().map(Into::into).collect();
+    assert_eq!(cqes.len(), 1);
+    assert_eq!(cqes[0].user_data(), 0x42);
+    assert_eq!(cqes[0].result(), 0);
+
+    Ok(())
+}
+
+pub fn test_setup_coop_taskrun<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    _ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    // IORING_SETUP_COOP_TASKRUN was introduced in kernel 5.19, as was the opcode for UringCmd16.
+    require!(
+        test;
+        test.probe.is_supported(opcode::UringCmd16::CODE);
+    );
+
+    println!("test setup_coop_taskrun");
+
+    let mut ring: IoUring<S, C> = IoUring::builder().setup_coop_taskrun().build(2)?;
+    assert!(ring.params().is_setup_coop_taskrun());
+    assert!(!ring.params().is_setup_taskrun_flag());
+
+    // Without the taskrun flag, pending task work is not signalled.
+    let ts = types::Timespec::new().nsec(1_000_000);
+    let timeout_e = opcode::Timeout::new(&ts).build().user_data(0x42).into();
+    unsafe {
+        ring.submission().push(timeout_e).expect("queue is full");
+    }
+    ring.submit()?;
+    thread::sleep(Duration::from_millis(20));
+    assert!(!ring.completion().needs_enter());
+
+    ring.submit_and_wait(1)?;
+
+    let cqes: Vec<cqueue::Entry> = ring.completion().map(Into::into).collect();
+    assert_eq!(cqes.len(), 1);
+    assert_eq!(cqes[0].user_data(), 0x42);
+    assert_eq!(cqes[0].result(), -libc::ETIME);
+
+    Ok(())
+}
+
+pub fn test_setup_taskrun_flag<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    _ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    // IORING_SETUP_TASKRUN_FLAG was introduced in kernel 5.19, but is combined with
+    // IORING_SETUP_DEFER_TASKRUN here, which was introduced in 6.1 as was the opcode for SendMsgZc.
+    require!(
+        test;
+        test.probe.is_supported(opcode::SendMsgZc::CODE);
+    );
+
+    println!("test setup_taskrun_flag");
+
+    // With only COOP_TASKRUN, pending task work also runs on any return to userspace, so the flag
+    // may be cleared before it can be observed. Deferred task work only runs when waiting for
+    // completions, which keeps the flag set until then.
+    let mut ring: IoUring<S, C> = IoUring::builder()
+        .setup_taskrun_flag()
+        .setup_single_issuer()
+        .setup_defer_taskrun()
+        .build(2)?;
+    assert!(ring.params().is_setup_taskrun_flag());
+    assert!(!ring.completion().needs_enter());
+
+    // The timeout expires while the ring is not waiting for completions, so its completion is
+    // left as pending task work and flagged in the ring until the kernel is entered.
+    let ts = types::Timespec::new().nsec(1_000_000);
+    let timeout_e = opcode::Timeout::new(&ts).build().user_data(0x42).into();
+    unsafe {
+        ring.submission().push(timeout_e).expect("queue is full");
+    }
+    ring.submit()?;
+    thread::sleep(Duration::from_millis(20));
+
+    assert!(ring.submission().taskrun());
+    assert!(ring.completion().needs_enter());
+    assert!(ring.completion().is_empty());
+
+    ring.submit_and_wait(1)?;
+    assert!(!ring.completion().needs_enter());
+
+    let cqes: Vec<cqueue::Entry> = ring.completion().map(Into::into).collect();
+    assert_eq!(cqes.len(), 1);
+    assert_eq!(cqes[0].user_data(), 0x42);
+    assert_eq!(cqes[0].result(), -libc::ETIME);
+
+    Ok(())
+}
+
+pub fn test_setup_entry_sizes<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    require!(
+        test;
+    );
+
+    println!("test setup_entry_sizes");
+
+    // The setup flags follow the entry types the ring was built for.
+    assert_eq!(
+        ring.params().is_setup_sqe128(),
+        std::mem::size_of::<S>() == 128
+    );
+    assert_eq!(
+        ring.params().is_setup_cqe32(),
+        std::mem::size_of::<C>() == 32
+    );
+
+    // Nop is valid with either entry size, and round-trips through the larger entries.
+    let nop_e = opcode::Nop::new().build().user_data(0x42).into();
+    unsafe {
+        ring.submission().push(nop_e).expect("queue is full");
+    }
+    ring.submit_and_wait(1)?;
+
+    let cqe = ring.completion().next().expect("cqueue is empty");
+    assert_eq!(cqe.user_data(), 0x42);
+    assert_eq!(cqe.result(), 0);
+
+    Ok(())
+}
+
+pub fn test_setup_r_disabled<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    _ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    // IORING_SETUP_R_DISABLED was introduced in kernel 5.10, before the opcode for Shutdown in 5.11.
+    require!(
+        test;
+        test.probe.is_supported(opcode::Shutdown::CODE);
+    );
+
+    println!("test setup_r_disabled");
+
+    let mut ring: IoUring<S, C> = IoUring::builder().setup_r_disabled().build(2)?;
+
+    let nop_e = opcode::Nop::new().build().user_data(0x42).into();
+    unsafe {
+        ring.submission().push(nop_e).expect("queue is full");
+    }
+
+    // Nothing is processed until the rings are enabled.
+    let err = ring.submit().unwrap_err();
+    assert_eq!(err.raw_os_error(), Some(libc::EBADFD));
+    assert!(ring.completion().is_empty());
+
+    ring.submitter().register_enable_rings()?;
+    ring.submit_and_wait(1)?;
+
+    let cqes: Vec<cqueue::Entry> = ring.completion().map(Into::into).collect();
+    assert_eq!(cqes.len(), 1);
+    assert_eq!(cqes[0].user_data(), 0x42);
+    assert_eq!(cqes[0].result(), 0);
+
+    Ok(())
+}
+
+pub fn test_setup_attach_wq<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    _ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    use std::os::unix::io::AsRawFd;
+
+    // IORING_SETUP_ATTACH_WQ was introduced in kernel 5.6, as was the opcode for EpollCtl.
+    require!(
+        test;
+        test.probe.is_supported(opcode::EpollCtl::CODE);
+    );
+
+    println!("test setup_attach_wq");
+
+    // Only an io_uring file descriptor can be attached to.
+    let file = tempfile::tempfile()?;
+    let err = IoUring::<S, C>::builder()
+        .setup_attach_wq(file.as_raw_fd())
+        .build(2)
+        .err()
+        .expect("setup_attach_wq on a regular file should fail");
+    assert_eq!(err.raw_os_error(), Some(libc::EINVAL));
+
+    let mut primary: IoUring<S, C> = IoUring::builder().build(2)?;
+    let mut attached: IoUring<S, C> = IoUring::builder()
+        .setup_attach_wq(primary.as_raw_fd())
+        .build(2)?;
+
+    // Force both requests through the shared async workers.
+    for ring in [&mut primary, &mut attached] {
+        let nop_e = opcode::Nop::new()
+            .build()
+            .flags(squeue::Flags::ASYNC)
+            .user_data(0x42)
+            .into();
+        unsafe {
+            ring.submission().push(nop_e).expect("queue is full");
+        }
+
+        ring.submit_and_wait(1)?;
+
+        let cqes: Vec<cqueue::Entry> = ring.completion().map(Into::into).collect();
+        assert_eq!(cqes.len(), 1);
+        assert_eq!(cqes[0].user_data(), 0x42);
+        assert_eq!(cqes[0].result(), 0);
+    }
+
+    Ok(())
+}
+
+pub fn test_setup_submit_all<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    _ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    // IORING_SETUP_SUBMIT_ALL was introduced in kernel 5.18, as was the opcode for MsgRing.
+    require!(
+        test;
+        test.probe.is_supported(opcode::MsgRingData::CODE);
+    );
+
+    println!("test setup_submit_all");
+
+    // An unknown personality fails the request before it is issued, which is what stops a
+    // batch. A bad fd would only fail once the request runs.
+    fn push_batch<S: squeue::EntryMarker, C: cqueue::EntryMarker>(ring: &mut IoUring<S, C>) {
+        let batch: [S; 3] = [
+            opcode::Nop::new().build().user_data(1).into(),
+            opcode::Nop::new()
+                .build()
+                .personality(u16::MAX)
+                .user_data(2)
+                .into(),
+            opcode::Nop::new().build().user_data(3).into(),
+        ];
+        unsafe {
+            ring.submission()
+                .push_multiple(batch)
+                .expect("queue is full");
+        }
+    }
+
+    // Without the flag, submission stops after the failing request.
+    let mut ring: IoUring<S, C> = IoUring::builder().build(4)?;
+    assert!(!ring.params().is_setup_submit_all());
+    push_batch(&mut ring);
+    assert_eq!(ring.submit_and_wait(2)?, 2);
+    assert_eq!(ring.submission().len(), 1);
+
+    let mut cqes: Vec<cqueue::Entry> = ring.completion().map(Into::into).collect();
+    cqes.sort_by_key(|cqe| cqe.user_data());
+    assert_eq!(cqes.len(), 2);
+    assert_eq!(cqes[0].user_data(), 1);
+    assert_eq!(cqes[0].result(), 0);
+    assert_eq!(cqes[1].user_data(), 2);
+    assert_eq!(cqes[1].result(), -libc::EINVAL);
+
+    // With the flag, the rest of the batch is still submitted.
+    let mut ring: IoUring<S, C> = IoUring::builder().setup_submit_all().build(4)?;
+    assert!(ring.params().is_setup_submit_all());
+    push_batch(&mut ring);
+    assert_eq!(ring.submit_and_wait(3)?, 3);
+    assert!(ring.submission().is_empty());
+
+    let mut cqes: Vec<cqueue::Entry> = ring.completion().map(Into::into).collect();
+    cqes.sort_by_key(|cqe| cqe.user_data());
+    assert_eq!(cqes.len(), 3);
+    assert_eq!(cqes[0].result(), 0);
+    assert_eq!(cqes[1].result(), -libc::EINVAL);
+    assert_eq!(cqes[2].user_data(), 3);
+    assert_eq!(cqes[2].result(), 0);
+
+    Ok(())
+}
+
+pub fn test_setup_no_mmap<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    _ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    // IORING_SETUP_NO_MMAP was introduced in kernel 6.5, FutexWait in 6.7.
+    require!(
+        test;
+        test.probe.is_supported(opcode::FutexWait::CODE);
+    );
+
+    use std::convert::TryInto;
+
+    println!("test setup_no_mmap");
+
+    // The kernel keeps using the pages until it has torn the ring down, which may be after the
+    // ring is dropped, so use a mapping rather than heap memory that could be reused. Each buffer
+    // fits in a single page, which older kernels require unless it is a huge page.
+    let mem = unsafe {
+        libc::mmap(
+            std::ptr::null_mut(),
+            3 * 4096,
+            libc::PROT_READ | libc::PROT_WRITE,
+            libc::MAP_PRIVATE | libc::MAP_ANONYMOUS,
+            -1,
+            0,
+        )
+    };
+    assert_ne!(mem, libc::MAP_FAILED);
+    let mem = unsafe { std::slice::from_raw_parts_mut(mem.cast::<u8>(), 3 * 4096) };
+    let (rings, rest) = mem.split_at_mut(4096);
+    let (sqes, too_small) = rest.split_at_mut(4096);
+
+    let err = unsafe {
+        IoUring::<S, C>::builder()
+            .setup_no_mmap(&mut too_small[..64], sqes)
+            .build(8)
+    }
+    .err()
+    .expect("setup_no_mmap with a tiny ring buffer should fail");
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+
+    let mut ring: IoUring<S, C> =
+        unsafe { IoUring::builder().setup_no_mmap(rings, sqes).build(8)? };
+    assert_eq!(ring.params().sq_entries(), 8);
+
+    let nop_e = opcode::Nop::new().build().user_data(0x42).into();
+    unsafe {
+        ring.submission().push(nop_e).expect("queue is full");
+    }
+
+    ring.submit_and_wait(1)?;
+
+    let cqes: Vec<cqueue::Entry> = ring.completion().map(Into::into).collect();
+    assert_eq!(cqes.len(), 1);
+    assert_eq!(cqes[0].user_data(), 0x42);
+    assert_eq!(cqes[0].result(), 0);
+
+    // The rings live in the buffer: the SQ tail at offset 4 has moved past the nop.
+    drop(ring);
+    assert_eq!(u32::from_ne_bytes(rings[4..8].try_into()?), 1);
+
+    unsafe {
+        libc::munmap(mem.as_mut_ptr().cast(), 3 * 4096);
+    }
+
+    Ok(())
+}
+
+pub fn test_setup_no_sqarray<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    _ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    // IORING_SETUP_NO_SQARRAY was introduced in kernel 6.6, FutexWait in 6.7.
+    require!(
+        test;
+        test.probe.is_supported(opcode::FutexWait::CODE);
+    );
+
+    println!("test setup_no_sqarray");
+
+    for no_sqarray in [false, true] {
+        let mut builder = IoUring::<S, C>::builder();
+        if no_sqarray {
+            builder.setup_no_sqarray();
+        }
+        let mut ring = builder.build(8)?;
+        assert_eq!(ring.params().is_setup_no_sqarray(), no_sqarray);
+
+        // Go around the ring more than once, so entries are consumed from wrapped slots.
+        for round in 0..3 {
+            let batch: Vec<S> = (0..6)
+                .map(|i| opcode::Nop::new().build().user_data(round * 6 + i).into())
+                .collect();
+            unsafe {
+                ring.submission()
+                    .push_linked(&batch)
+                    .expect("queue is full");
+            }
+
+            ring.submit_and_wait(6)?;
+
+            // The nops are linked, so they complete in the order they were consumed.
+            let user_data: Vec<u64> = ring.completion().map(|cqe| cqe.user_data()).collect();
+            assert_eq!(user_data, (round * 6..round * 6 + 6).collect::<Vec<u64>>());
+        }
+    }
+
+    Ok(())
+}
+
+pub fn test_setup_cqsize<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    _ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    require!(
+        test;
+    );
+
+    println!("test setup_cqsize");
+
+    let ring: IoUring<S, C> = IoUring::builder().setup_cqsize(100).build(8)?;
+    assert_eq!(ring.params().cq_entries(), 128);
+
+    let ring: IoUring<S, C> = IoUring::builder().setup_cqsize(8).build(8)?;
+    assert_eq!(ring.params().cq_entries(), 8);
+
+    for (cq_entries, sq_entries) in [(1 << 20, 8), (u32::MAX, 8), (0, 8), (4, 16)] {
+        let err = IoUring::<S, C>::builder()
+            .setup_cqsize(cq_entries)
+            .build(sq_entries)
+            .err()
+            .expect("setup_cqsize should have been rejected");
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    }
+
+    // With clamping, the kernel caps an oversized completion queue instead.
+    let ring: IoUring<S, C> = IoUring::builder()
+        .setup_cqsize(1 << 20)
+        .setup_clamp()
+        .build(8)?;
+    assert_eq!(ring.params().cq_entries(), 1 << 16);
+
+    Ok(())
+}
+
+pub fn test_params_entries<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    _ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    require!(
+        test;
+    );
+
+    println!("test params_entries");
+
+    // The kernel rounds an odd size up to the next power of two.
+    let mut ring: IoUring<S, C> = IoUring::builder().build(13)?;
+    assert_eq!(ring.params().sq_entries(), 16);
+    assert_eq!(ring.params().cq_entries(), 32);
+    assert_eq!(
+        ring.params().sq_entries() as usize,
+        ring.submission().capacity()
+    );
+    assert_eq!(
+        ring.params().cq_entries() as usize,
+        ring.completion().capacity()
+    );
+
+    let mut ring: IoUring<S, C> = IoUring::builder().setup_cqsize(100).build(5)?;
+    assert_eq!(ring.params().sq_entries(), 8);
+    assert_eq!(ring.params().cq_entries(), 128);
+    assert_eq!(
+        ring.params().sq_entries() as usize,
+        ring.submission().capacity()
+    );
+    assert_eq!(
+        ring.params().cq_entries() as usize,
+        ring.completion().capacity()
+    );
+
+    Ok(())
+}
+
+pub fn test_params_features<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    require!(
+        test;
+    );
+
+    println!("test params_features");
+
+    let params = ring.params();
+    let features = params.features();
+
+    // Each accessor against its `IORING_FEAT_*` bit.
+    let table = [
+        (params.is_feature_single_mmap(), 1 << 0),
+        (params.is_feature_nodrop(), 1 << 1),
+        (params.is_feature_submit_stable(), 1 << 2),
+        (params.is_feature_rw_cur_pos(), 1 << 3),
+        (params.is_feature_cur_personality(), 1 << 4),
+        (params.is_feature_fast_poll(), 1 << 5),
+        (params.is_feature_poll_32bits(), 1 << 6),
+        (params.is_feature_sqpoll_nonfixed(), 1 << 7),
+        (params.is_feature_ext_arg(), 1 << 8),
+        (params.is_feature_native_workers(), 1 << 9),
+        (params.is_feature_rsrc_tags(), 1 << 10),
+        (params.is_feature_cqe_skip(), 1 << 11),
+        (params.is_feature_linked_file(), 1 << 12),
+        (params.is_feature_reg_reg_ring(), 1 << 13),
+        (params.is_feature_recvsend_bundle(), 1 << 14),
+        (params.is_feature_min_timeout(), 1 << 15),
+    ];
+    for (i, &(enabled, bit)) in table.iter().enumerate() {
+        assert_eq!(enabled, features & bit != 0, "feature bit {}", i);
+    }
+
+    assert_eq!(
+        params.is_feature_rsrc_tags(),
+        params.is_feature_resource_tagging()
+    );
+    assert_eq!(
+        params.is_feature_cqe_skip(),
+        params.is_feature_skip_cqe_on_success()
+    );
+
+    Ok(())
+}
+
+pub fn test_setup_hybrid_iopoll<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    _ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    require!(
+        test;
+    );
+
+    println!("test setup_hybrid_iopoll");
+
+    let err = IoUring::<S, C>::builder()
+        .setup_hybrid_iopoll()
+        .build(4)
+        .err()
+        .expect("setup_hybrid_iopoll without setup_iopoll should have been rejected");
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+
+    // Kernels before 6.13 reject the flag themselves.
+    match IoUring::<S, C>::builder()
+        .setup_iopoll()
+        .setup_hybrid_iopoll()
+        .build(4)
+    {
+        Ok(ring) => {
+            assert!(ring.params().is_setup_iopoll());
+            assert!(ring.params().is_setup_hybrid_iopoll());
+        }
+        Err(err) => assert_eq!(err.raw_os_error(), Some(libc::EINVAL)),
+    }
+
+    Ok(())
+}
+
+pub fn test_builder_entry_helpers<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    _ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    require!(
+        test;
+        test.probe.is_supported(opcode::Nop::CODE);
+    );
+
+    println!("test builder_entry_helpers");
+
+    let mut ring = IoUring::builder_cqe32().build(4)?;
+    assert!(ring.params().is_setup_cqe32());
+    assert!(!ring.params().is_setup_sqe128());
+
+    unsafe {
+        ring.submission()
+            .push(opcode::Nop::new().build().user_data(0x32))
+            .expect("queue is full");
+    }
+    ring.submit_and_wait(1)?;
+
+    let cqes: Vec<cqueue::Entry32> = ring.completion().collect();
+    assert_eq!(cqes.len(), 1);
+    assert_eq!(cqes[0].user_data(), 0x32);
+    assert_eq!(cqes[0].result(), 0);
+    // A nop leaves the extra bytes zeroed.
+    assert_eq!(cqes[0].big_cqe(), &[0, 0]);
+
+    let mut ring = IoUring::builder_sqe128().build(4)?;
+    assert!(ring.params().is_setup_sqe128());
+    assert!(!ring.params().is_setup_cqe32());
+
+    unsafe {
+        ring.submission()
+            .push(opcode::Nop::new().build().user_data(0x128).into())
+            .expect("queue is full");
+    }
+    ring.submit_and_wait(1)?;
+
+    let cqes: Vec<cqueue::Entry> = ring.completion().collect();
+    assert_eq!(cqes.len(), 1);
+    assert_eq!(cqes[0].user_data(), 0x128);
+    assert_eq!(cqes[0].result(), 0);
+
+    Ok(())
+}
+
+pub fn test_setup_clamp<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    _ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    require!(
+        test;
+    );
+
+    println!("test setup_clamp");
+
+    let err = IoUring::<S, C>::builder()
+        .build(1 << 20)
+        .err()
+        .expect("an oversized ring should have been rejected");
+    assert_eq!(err.raw_os_error(), Some(libc::EINVAL));
+
+    let mut ring: IoUring<S, C> = IoUring::builder().setup_clamp().build(1 << 20)?;
+    assert_eq!(ring.submission().capacity(), 1 << 15);
+    assert_eq!(ring.completion().capacity(), 1 << 16);
+    assert_eq!(ring.params().cq_entries(), 1 << 16);
+
+    // Sizes within the limits are left alone.
+    let mut ring: IoUring<S, C> = IoUring::builder().setup_clamp().build(16)?;
+    assert_eq!(ring.submission().capacity(), 16);
+    assert_eq!(ring.completion().capacity(), 32);
+
+    Ok(())
+}
+
+pub fn test_setup_sqpoll<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    _ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    // Unprivileged SQPOLL needs 5.11, as does the opcode for Shutdown.
+    require!(
+        test;
+        test.probe.is_supported(opcode::Shutdown::CODE);
+    );
+
+    println!("test setup_sqpoll");
+
+    let err = IoUring::<S, C>::builder()
+        .setup_sqpoll_cpu(0)
+        .build(4)
+        .err()
+        .expect("setup_sqpoll_cpu without setup_sqpoll should have been rejected");
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+
+    let mut ring: IoUring<S, C> = IoUring::builder()
+        .setup_sqpoll(1000)
+        .setup_sqpoll_cpu(0)
+        .build(8)?;
+    assert!(ring.params().is_setup_sqpoll());
+    assert!(ring.params().is_setup_sq_aff());
+
+    // Publishing the entry is enough: the poll thread picks it up without an `io_uring_enter`.
+    let nop_e = opcode::Nop::new().build().user_data(0x47).into();
+    unsafe {
+        ring.submission().push(nop_e).expect("queue is full");
+    }
+
+    let mut tries = 0;
+    let cqe: cqueue::Entry = loop {
+        if let Some(cqe) = ring.completion().next() {
+            break cqe.into();
+        }
+        tries += 1;
+        assert!(tries < 1000, "the poll thread never submitted the entry");
+        thread::sleep(Duration::from_millis(1));
+    };
+    assert_eq!(cqe.user_data(), 0x47);
+    assert_eq!(cqe.result(), 0);
+
+    Ok(())
+}
+
+pub fn test_sqpoll_enter_raw<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    _ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    // Unprivileged SQPOLL needs 5.11, as does the opcode for Shutdown.
+    require!(
+        test;
+        test.probe.is_supported(opcode::Shutdown::CODE);
+    );
+
+    println!("test sqpoll_enter_raw");
+
+    let mut ring: IoUring<S, C> = IoUring::builder().setup_sqpoll(10).build(8)?;
+
+    // Let the poll thread go idle, so it only runs again once it is woken up.
+    let mut tries = 0;
+    while !ring.submission().need_wakeup() {
+        tries += 1;
+        assert!(tries < 100, "the poll thread never went to sleep");
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    let nop_e = opcode::Nop::new().build().user_data(0x46).into();
+    unsafe {
+        ring.submission().push(nop_e).expect("queue is full");
+    }
+
+    let flags = types::EnterFlags::GETEVENTS | types::EnterFlags::SQ_WAKEUP;
+    unsafe {
+        ring.submitter()
+            .enter_raw::<libc::sigset_t>(1, 1, flags, None)?;
+    }
+
+    let cqes: Vec<cqueue::Entry> = ring.completion().map(Into::into).collect();
+    assert_eq!(cqes.len(), 1);
+    assert_eq!(cqes[0].user_data(), 0x46);
+    assert_eq!(cqes[0].result(), 0);
+
+    Ok(())
+}
+
+pub fn test_sqpoll_need_wakeup<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    _ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    // Unprivileged SQPOLL needs 5.11, as does the opcode for Shutdown.
+    require!(
+        test;
+        test.probe.is_supported(opcode::Shutdown::CODE);
+    );
+
+    println!("test sqpoll_need_wakeup");
+
+    fn wait_for_sleep<S: squeue::EntryMarker, C: cqueue::EntryMarker>(ring: &mut IoUring<S, C>) {
+        let mut tries = 0;
+        while !ring.submission().need_wakeup() {
+            tries += 1;
+            assert!(tries < 100, "the poll thread never went to sleep");
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    let mut ring: IoUring<S, C> = IoUring::builder().setup_sqpoll(50).build(8)?;
+
+    for user_data in 0x48..0x4a {
+        wait_for_sleep(&mut ring);
+
+        let nop_e = opcode::Nop::new().build().user_data(user_data).into();
+        unsafe {
+            ring.submission().push(nop_e).expect("queue is full");
+        }
+
+        // `submit_and_wait` wakes the thread up, and it clears the flag before it submits.
+        ring.submit_and_wait(1)?;
+        assert!(!ring.submission().need_wakeup());
+
+        let cqes: Vec<cqueue::Entry> = ring.completion().map(Into::into).collect();
+        assert_eq!(cqes.len(), 1);
+        assert_eq!(cqes[0].user_data(), user_data);
+    }
+
+    Ok(())
+}
+
+pub fn test_setup_dontfork<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    _ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    require!(
+        test;
+    );
+
+    println!("test setup_dontfork");
+
+    // Fork and touch the completion queue from the child, returning how the child exited.
+    fn fork_and_touch<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+        ring: &mut IoUring<S, C>,
+    ) -> anyhow::Result<libc::c_int> {
+        match unsafe { libc::fork() } {
+            -1 => Err(std::io::Error::last_os_error().into()),
+            0 => {
+                let empty = ring.completion().is_empty();
+                unsafe { libc::_exit(empty as _) }
+            }
+            pid => {
+                let mut status = 0;
+                if unsafe { libc::waitpid(pid, &mut status, 0) } == -1 {
+                    return Err(std::io::Error::last_os_error().into());
+                }
+                Ok(status)
+            }
+        }
+    }
+
+    let mut ring: IoUring<S, C> = IoUring::builder().build(8)?;
+    let status = fork_and_touch(&mut ring)?;
+    assert!(libc::WIFEXITED(status));
+    assert_eq!(libc::WEXITSTATUS(status), 1);
+
+    let mut ring: IoUring<S, C> = IoUring::builder().dontfork().build(8)?;
+    let status = fork_and_touch(&mut ring)?;
+    assert!(libc::WIFSIGNALED(status));
+    assert_eq!(libc::WTERMSIG(status), libc::SIGSEGV);
+
+    // The parent's mapping is unaffected.
+    let nop_e: S = opcode::Nop::new().build().user_data(0x42).into();
+    unsafe { ring.submission().push(nop_e).expect("queue is full") };
+    ring.submit_and_wait(1)?;
+    let cqe: cqueue::Entry = ring.completion().next().expect("cqueue is empty").into();
+    assert_eq!(cqe.user_data(), 0x42);
+
+    Ok(())
+}
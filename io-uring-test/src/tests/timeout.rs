@@ -1,6 +1,10 @@
 use crate::Test;
-use io_uring::{cqueue::{self, EntryMarker}, opcode, squeue, types, IoUring};
-use std::time::Instant;
+use io_uring::{
+    cqueue::{self, EntryMarker},
+    opcode, squeue, types, IoUring,
+};
+use std::convert::TryFrom;
+use std::time::{Duration, Instant, SystemTime};
 
 pub fn test_timeout<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
     ring: &mut IoUring<S, C>,
@@ -381,3 +385,245 @@ pub fn test_timeout_submit_args<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
 
     Ok(())
 }
+
+pub fn test_timeout_submit_and_wait_timeout<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    require! {
+        test;
+        ring.params().is_feature_ext_arg();
+    };
+
+    println!("test timeout_submit_and_wait_timeout");
+
+    let ts = types::Timespec::new().nsec(100_000_000);
+
+    // timeout, on an empty ring
+
+    let start = Instant::now();
+    assert_eq!(ring.submitter().submit_and_wait_timeout(1, &ts, None)?, 0);
+    let elapsed = start.elapsed();
+    assert!(elapsed >= Duration::from_millis(100), "{:?}", elapsed);
+    assert!(elapsed < Duration::from_millis(900), "{:?}", elapsed);
+
+    assert!(ring.completion().next().is_none());
+
+    // timeout with a signal mask
+
+    let mut sigmask: libc::sigset_t = unsafe { std::mem::zeroed() };
+    unsafe { libc::sigemptyset(&mut sigmask) };
+
+    let start = Instant::now();
+    assert_eq!(
+        ring.submitter()
+            .submit_and_wait_timeout(1, &ts, Some(&sigmask))?,
+        0
+    );
+    assert!(start.elapsed() >= Duration::from_millis(100));
+
+    // no timeout
+
+    let nop_e = opcode::Nop::new();
+
+    unsafe {
+        ring.submission()
+            .push(nop_e.build().user_data(0x1d).into())
+            .expect("queue is full");
+    }
+
+    let start = Instant::now();
+    assert_eq!(ring.submitter().submit_and_wait_timeout(1, &ts, None)?, 1);
+    assert!(start.elapsed() < Duration::from_millis(100));
+
+    let cqes: Vec<cqueue::Entry> = ring.completion().map(Into::into).collect();
+
+    assert_eq!(cqes.len(), 1);
+    assert_eq!(cqes[0].user_data(), 0x1d);
+    assert_eq!(cqes[0].result(), 0);
+
+    Ok(())
+}
+
+pub fn test_link_timeout_abs<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    use std::os::unix::io::{AsRawFd, FromRawFd};
+
+    require!(
+        test;
+        test.probe.is_supported(opcode::LinkTimeout::CODE);
+        test.probe.is_supported(opcode::Read::CODE);
+    );
+
+    println!("test link_timeout_abs");
+
+    // Nothing is ever written, so the read only ends when the deadline cancels it.
+    let (rx, _tx) = {
+        let mut fds = [0, 0];
+        assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+        unsafe {
+            (
+                std::fs::File::from_raw_fd(fds[0]),
+                std::fs::File::from_raw_fd(fds[1]),
+            )
+        }
+    };
+    let mut buf = [0u8; 16];
+
+    let deadline = SystemTime::now() + Duration::from_millis(50);
+    let ts = types::Timespec::try_from(deadline)?;
+
+    let read_e = opcode::Read::new(types::Fd(rx.as_raw_fd()), buf.as_mut_ptr(), buf.len() as _)
+        .build()
+        .user_data(0x20)
+        .into();
+    let link_timeout_e = opcode::LinkTimeout::new(&ts)
+        .flags(types::TimeoutFlags::ABS | types::TimeoutFlags::REALTIME)
+        .build()
+        .user_data(0x21)
+        .into();
+
+    unsafe {
+        ring.submission()
+            .push_linked(&[read_e, link_timeout_e])
+            .expect("queue is full");
+    }
+
+    ring.submit_and_wait(2)?;
+
+    assert!(SystemTime::now() >= deadline);
+
+    let mut cqes: Vec<cqueue::Entry> = ring.completion().map(Into::into).collect();
+    cqes.sort_by_key(|cqe| cqe.user_data());
+
+    assert_eq!(cqes.len(), 2);
+    assert_eq!(cqes[0].user_data(), 0x20);
+    assert_eq!(cqes[0].result(), -libc::ECANCELED);
+    assert_eq!(cqes[1].user_data(), 0x21);
+    assert_eq!(cqes[1].result(), -libc::ETIME);
+
+    Ok(())
+}
+
+pub fn test_timeout_submit_and_wait_min<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    require!(
+        test;
+        ring.params().is_feature_min_timeout();
+    );
+
+    println!("test timeout_submit_and_wait_min");
+
+    for i in 0..4 {
+        let nop_e = opcode::Nop::new().build().user_data(0x30 + i).into();
+        unsafe {
+            ring.submission().push(nop_e).expect("queue is full");
+        }
+    }
+
+    // Waiting for more completions than will ever arrive returns once the minimum wait is over,
+    // with everything that arrived in the meantime.
+    let start = Instant::now();
+    assert_eq!(
+        ring.submitter()
+            .submit_and_wait_min(8, Duration::from_millis(10))?,
+        4
+    );
+    assert!(start.elapsed() < Duration::from_secs(1));
+
+    let cqes: Vec<cqueue::Entry> = ring.completion().map(Into::into).collect();
+
+    assert_eq!(cqes.len(), 4);
+    for (i, cqe) in cqes.iter().enumerate() {
+        assert_eq!(cqe.user_data(), 0x30 + i as u64);
+        assert_eq!(cqe.result(), 0);
+    }
+
+    Ok(())
+}
+
+pub fn test_timeout_multishot<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    // IORING_TIMEOUT_MULTISHOT was introduced in kernel 6.4, before the opcode for FutexWait in 6.7.
+    require!(
+        test;
+        test.probe.is_supported(opcode::FutexWait::CODE);
+    );
+
+    println!("test timeout_multishot");
+
+    // A 10ms timer expiring three times from a single submission.
+    let ts = types::Timespec::new().nsec(10_000_000);
+    let timeout_e = opcode::Timeout::new(&ts)
+        .count(3)
+        .flags(types::TimeoutFlags::MULTISHOT);
+
+    unsafe {
+        ring.submission()
+            .push(timeout_e.build().user_data(0x1e).into())
+            .expect("queue is full");
+    }
+
+    let start = Instant::now();
+    let mut cqes: Vec<cqueue::Entry> = Vec::new();
+    while cqes.len() < 3 {
+        ring.submit_and_wait(1)?;
+        cqes.extend(ring.completion().map(Into::<cqueue::Entry>::into));
+    }
+    assert!(start.elapsed() >= Duration::from_millis(30));
+
+    assert_eq!(cqes.len(), 3);
+    for cqe in &cqes {
+        assert_eq!(cqe.user_data(), 0x1e);
+        assert_eq!(cqe.result(), -libc::ETIME);
+    }
+    assert!(cqueue::more(cqes[0].flags()));
+    assert!(cqueue::more(cqes[1].flags()));
+    assert!(!cqueue::more(cqes[2].flags()));
+
+    Ok(())
+}
+
+pub fn test_timeout_abs_realtime<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+    ring: &mut IoUring<S, C>,
+    test: &Test,
+) -> anyhow::Result<()> {
+    require!(
+        test;
+        test.probe.is_supported(opcode::Timeout::CODE);
+    );
+
+    println!("test timeout_abs_realtime");
+
+    // A wall clock deadline 50ms from now.
+    let deadline = SystemTime::now() + Duration::from_millis(50);
+    let ts = types::Timespec::try_from(deadline)?;
+    let timeout_e =
+        opcode::Timeout::new(&ts).flags(types::TimeoutFlags::ABS | types::TimeoutFlags::REALTIME);
+
+    unsafe {
+        ring.submission()
+            .push(timeout_e.build().user_data(0x1f).into())
+            .expect("queue is full");
+    }
+
+    ring.submit_and_wait(1)?;
+
+    let now = SystemTime::now();
+    assert!(now >= deadline);
+    assert!(now.duration_since(deadline)? < Duration::from_millis(500));
+
+    let cqes: Vec<cqueue::Entry> = ring.completion().map(Into::into).collect();
+
+    assert_eq!(cqes.len(), 1);
+    assert_eq!(cqes[0].user_data(), 0x1f);
+    assert_eq!(cqes[0].result(), -libc::ETIME);
+
+    Ok(())
+}
@@ -1,16 +1,18 @@
 //! Completion Queue
 
+use std::cmp;
 use std::fmt::{self, Debug};
 use std::mem;
 use std::mem::MaybeUninit;
 use std::sync::atomic;
 
 use crate::sys;
+use crate::types::BufRing;
 use crate::util::{private, unsync_load, Mmap};
 
 pub(crate) struct Inner<E: EntryMarker> {
-    head: *const atomic::AtomicU32,
-    tail: *const atomic::AtomicU32,
+    pub(crate) head: *const atomic::AtomicU32,
+    pub(crate) tail: *const atomic::AtomicU32,
     ring_mask: u32,
     ring_entries: u32,
 
@@ -20,6 +22,8 @@ pub(crate) struct Inner<E: EntryMarker> {
 
     #[allow(dead_code)]
     flags: *const atomic::AtomicU32,
+
+    sq_flags: *const atomic::AtomicU32,
 }
 
 /// An io_uring instance's completion queue. This stores all the I/O operations that have completed.
@@ -57,7 +61,11 @@ fn test_entry_sizes() {
 
 impl<E: EntryMarker> Inner<E> {
     #[rustfmt::skip]
-    pub(crate) unsafe fn new(cq_mmap: &Mmap, p: &sys::io_uring_params) -> Self {
+    pub(crate) unsafe fn new(
+        cq_mmap: &Mmap,
+        sq_flags: *const atomic::AtomicU32,
+        p: &sys::io_uring_params,
+    ) -> Self {
         let head         = cq_mmap.offset(p.cq_off.head         ) as *const atomic::AtomicU32;
         let tail         = cq_mmap.offset(p.cq_off.tail         ) as *const atomic::AtomicU32;
         let ring_mask    = cq_mmap.offset(p.cq_off.ring_mask    ).cast::<u32>().read();
@@ -74,6 +82,7 @@ impl<E: EntryMarker> Inner<E> {
             overflow,
             cqes,
             flags,
+            sq_flags,
         }
     }
 
@@ -90,9 +99,17 @@ impl<E: EntryMarker> Inner<E> {
     pub(crate) fn borrow(&mut self) -> CompletionQueue<'_, E> {
         unsafe { self.borrow_shared() }
     }
+
+    #[inline]
+    pub(crate) fn is_ready(&self) -> bool {
+        unsafe {
+            (*self.head).load(atomic::Ordering::Acquire)
+                != (*self.tail).load(atomic::Ordering::Acquire)
+        }
+    }
 }
 
-impl<E: EntryMarker> CompletionQueue<'_, E> {
+impl<'c, E: EntryMarker> CompletionQueue<'c, E> {
     /// Synchronize this type with the real completion queue.
     ///
     /// This will flush any entries consumed in this iterator and will make available new entries
@@ -121,6 +138,31 @@ impl<E: EntryMarker> CompletionQueue<'_, E> {
         }
     }
 
+    /// The raw flags of the completion queue ring, a combination of `IORING_CQ_*` bits.
+    pub fn flags(&self) -> u32 {
+        unsafe { (*self.queue.flags).load(atomic::Ordering::Acquire) }
+    }
+
+    /// Whether the kernel must be entered before all completions can be observed in this queue.
+    ///
+    /// This is the case when completions have overflowed the ring, or when task work is pending
+    /// that will post completions (the kernel publishes both in the submission queue ring flags,
+    /// see [`SubmissionQueue::cq_overflow`](crate::SubmissionQueue::cq_overflow) and
+    /// [`SubmissionQueue::taskrun`](crate::SubmissionQueue::taskrun)). Task work is only signalled
+    /// this way if the ring was set up with
+    /// [`setup_taskrun_flag`](crate::Builder::setup_taskrun_flag) or
+    /// [`setup_defer_taskrun`](crate::Builder::setup_defer_taskrun).
+    ///
+    /// When this returns `true`, call [`Submitter::submit`](crate::Submitter::submit) (or any of
+    /// the other functions that enter the kernel to get events) to flush the completions.
+    pub fn needs_enter(&self) -> bool {
+        unsafe {
+            (*self.queue.sq_flags).load(atomic::Ordering::Acquire)
+                & (sys::IORING_SQ_CQ_OVERFLOW | sys::IORING_SQ_TASKRUN)
+                != 0
+        }
+    }
+
     /// Get the total number of entries in the completion queue ring buffer.
     #[inline]
     pub fn capacity(&self) -> usize {
@@ -143,7 +185,7 @@ impl<E: EntryMarker> CompletionQueue<'_, E> {
 
     #[inline]
     pub fn fill<'a>(&mut self, entries: &'a mut [MaybeUninit<E>]) -> &'a mut [E] {
-        let len = std::cmp::min(self.len(), entries.len());
+        let len = cmp::min(self.len(), entries.len());
 
         for entry in &mut entries[..len] {
             entry.write(unsafe { self.pop() });
@@ -152,6 +194,107 @@ impl<E: EntryMarker> CompletionQueue<'_, E> {
         unsafe { std::slice::from_raw_parts_mut(entries as *mut _ as *mut E, len) }
     }
 
+    /// Append every entry currently available in the queue to `entries`, returning the number of
+    /// entries appended.
+    ///
+    /// Unlike [`fill`](Self::fill), this grows `entries` as needed.
+    #[inline]
+    pub fn fill_vec(&mut self, entries: &mut Vec<E>) -> usize {
+        let len = self.len();
+        entries.reserve(len);
+        entries.extend(self.by_ref());
+        len
+    }
+
+    /// Get a reference to the next entry in the queue without consuming it, or `None` if the
+    /// queue is empty.
+    ///
+    /// The entry stays in the queue and will be returned again by the next call to
+    /// [`next`](Iterator::next).
+    #[inline]
+    pub fn peek(&self) -> Option<&E> {
+        if self.head != self.tail {
+            Some(unsafe {
+                &*self
+                    .queue
+                    .cqes
+                    .add((self.head & self.queue.ring_mask) as usize)
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Discard every entry currently available in the queue without reading them, returning the
+    /// number of entries that were skipped.
+    ///
+    /// Like entries consumed by iterating, the skipped slots are handed back to the kernel on the
+    /// next [`sync`](Self::sync) or when this queue is dropped.
+    #[inline]
+    pub fn drain(&mut self) -> usize {
+        let len = self.len();
+        self.head = self.tail;
+        len
+    }
+
+    /// The entries in the queue, in order, without consuming them.
+    ///
+    /// The queue is a ring buffer, so the entries come as two contiguous runs: the ones up to the
+    /// end of the ring, and the ones that wrapped around to its start. The second is empty if the
+    /// entries don't wrap. Call [`advance`](Self::advance) once they have been processed.
+    #[inline]
+    pub fn as_slices(&self) -> (&[E], &[E]) {
+        let len = self.len();
+        let start = (self.head & self.queue.ring_mask) as usize;
+        let first = cmp::min(len, self.queue.ring_entries as usize - start);
+
+        unsafe {
+            (
+                std::slice::from_raw_parts(self.queue.cqes.add(start), first),
+                std::slice::from_raw_parts(self.queue.cqes, len - first),
+            )
+        }
+    }
+
+    /// Consume the next `n` entries without reading them, e.g. after processing them through
+    /// [`as_slices`](Self::as_slices). `n` is clamped to the number of entries in the queue, so
+    /// `advance(usize::MAX)` consumes everything, like [`drain`](Self::drain).
+    ///
+    /// Like the entries taken by iterating, they are released to the kernel on the next
+    /// [`sync`](Self::sync) or when this queue is dropped, and the entries after them stay in the
+    /// queue.
+    #[inline]
+    pub fn advance(&mut self, n: usize) {
+        let n = cmp::min(n, self.len());
+        self.head = self.head.wrapping_add(n as u32);
+    }
+
+    /// Pass the entries in the queue to `f` in order, consuming each one it returns `true` for,
+    /// and stop at the first entry it returns `false` for. Returns the number of entries consumed.
+    ///
+    /// The entry `f` rejected and everything after it stay in the queue, and are still visible
+    /// after a [`sync`](Self::sync).
+    #[inline]
+    pub fn for_each_until<F: FnMut(&E) -> bool>(&mut self, mut f: F) -> usize {
+        let mut consumed = 0;
+
+        while let Some(entry) = self.peek() {
+            if !f(entry) {
+                break;
+            }
+            self.head = self.head.wrapping_add(1);
+            consumed += 1;
+        }
+
+        consumed
+    }
+
+    /// Take the next entry from the queue without checking whether the queue is empty.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure the queue is not empty, otherwise a stale or uninitialized entry
+    /// will be read.
     #[inline]
     pub unsafe fn pop(&mut self) -> E {
         let entry = &*self
@@ -161,6 +304,37 @@ impl<E: EntryMarker> CompletionQueue<'_, E> {
         self.head = self.head.wrapping_add(1);
         entry.clone()
     }
+
+    /// Take the next entry from the queue along with its decoded [flags](EntryMarker::flags).
+    ///
+    /// # Safety
+    ///
+    /// The same as [`pop`](Self::pop): the caller must ensure the queue is not empty.
+    #[inline]
+    pub unsafe fn pop_with_meta(&mut self) -> (E, CqeMeta) {
+        let entry = self.pop();
+        let meta = CqeMeta::from_flags(entry.flags());
+        (entry, meta)
+    }
+
+    /// Walk the queue together with the [`BufRing`] its operations select buffers from, pairing
+    /// each entry with the data received into its buffer.
+    ///
+    /// Each [`BufRingCompletion`] returned by [`next_completion`](BufRingCompletions::next_completion)
+    /// hands its buffer back to `buf_ring` when it is dropped, and publishes it to the kernel
+    /// right away. Only one completion can be held at a time.
+    ///
+    /// # Safety
+    ///
+    /// Every buffer added to `buf_ring` must stay valid for writes for as long as the ring is
+    /// registered, since buffers are added back to it without the caller's involvement.
+    #[inline]
+    pub unsafe fn with_buf_ring<'q>(
+        &'q mut self,
+        buf_ring: &'q mut BufRing,
+    ) -> BufRingCompletions<'q, 'c, E> {
+        BufRingCompletions { cq: self, buf_ring }
+    }
 }
 
 impl<E: EntryMarker> Drop for CompletionQueue<'_, E> {
@@ -195,6 +369,86 @@ impl<E: EntryMarker> ExactSizeIterator for CompletionQueue<'_, E> {
     }
 }
 
+/// A completion queue paired with a [`BufRing`], see [`CompletionQueue::with_buf_ring`].
+pub struct BufRingCompletions<'q, 'a, E: EntryMarker = Entry> {
+    cq: &'q mut CompletionQueue<'a, E>,
+    buf_ring: &'q mut BufRing,
+}
+
+impl<E: EntryMarker> BufRingCompletions<'_, '_, E> {
+    /// Take the next entry from the queue, along with the buffer it selected.
+    #[inline]
+    pub fn next_completion(&mut self) -> Option<BufRingCompletion<'_, E>> {
+        let entry = self.cq.next()?;
+        let buf = buffer_select(entry.flags()).and_then(|bid| {
+            self.buf_ring
+                .buffer(bid)
+                .map(|(addr, len)| (addr, len, bid))
+        });
+
+        Some(BufRingCompletion {
+            entry,
+            buf,
+            buf_ring: self.buf_ring,
+        })
+    }
+}
+
+/// A completion queue entry holding the buffer it selected from a [`BufRing`].
+///
+/// The buffer is added back to the ring and published when this is dropped.
+pub struct BufRingCompletion<'r, E: EntryMarker = Entry> {
+    entry: E,
+    buf: Option<(*mut u8, u32, u16)>,
+    buf_ring: &'r mut BufRing,
+}
+
+impl<E: EntryMarker> BufRingCompletion<'_, E> {
+    /// The completion queue entry.
+    #[inline]
+    pub fn entry(&self) -> &E {
+        &self.entry
+    }
+
+    /// The ID of the selected buffer, if the operation selected one that was added to the ring.
+    #[inline]
+    pub fn buffer_id(&self) -> Option<u16> {
+        self.buf.map(|(_, _, bid)| bid)
+    }
+
+    /// The data the operation placed in the selected buffer, which is empty if it failed or no
+    /// buffer was selected.
+    #[inline]
+    pub fn data(&self) -> &[u8] {
+        match self.buf {
+            Some((addr, len, _)) if self.entry.result() > 0 => {
+                let n = (self.entry.result() as u32).min(len);
+                unsafe { std::slice::from_raw_parts(addr, n as usize) }
+            }
+            _ => &[],
+        }
+    }
+}
+
+impl<E: EntryMarker> Drop for BufRingCompletion<'_, E> {
+    #[inline]
+    fn drop(&mut self) {
+        if let Some((addr, len, bid)) = self.buf {
+            unsafe { self.buf_ring.add(addr, len, bid) };
+            self.buf_ring.advance();
+        }
+    }
+}
+
+impl<E: EntryMarker> Debug for BufRingCompletion<'_, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BufRingCompletion")
+            .field("entry", &self.entry)
+            .field("buffer_id", &self.buffer_id())
+            .finish()
+    }
+}
+
 impl private::Sealed for Entry {}
 
 impl EntryMarker for Entry {
@@ -248,6 +502,54 @@ impl Entry32 {
     pub fn big_cqe(&self) -> &[u64; 2] {
         &self.1
     }
+
+    /// Interpret the [additional data](Self::big_cqe) as that of a completion of the operation
+    /// `T` decodes.
+    ///
+    /// Completions don't record which operation posted them, so it is up to the caller to only use
+    /// this on completions of [`T::CODE`](BigCqe::CODE) operations, for instance by keying on
+    /// their user data.
+    #[inline]
+    pub fn decode_big_cqe<T: BigCqe>(&self) -> T {
+        T::from_big_cqe(&self.1)
+    }
+}
+
+/// A decoder for the additional data an operation places in a 32-byte completion queue entry
+/// (see [`Entry32::big_cqe`]).
+pub trait BigCqe: Sized {
+    /// The opcode of the operations whose completions carry this data.
+    const CODE: u8;
+
+    /// Decode the additional data of a completion.
+    fn from_big_cqe(big_cqe: &[u64; 2]) -> Self;
+}
+
+/// The additional data of a [`UringCmd16`](crate::opcode::UringCmd16) or
+/// [`UringCmd80`](crate::opcode::UringCmd80) completion.
+///
+/// Besides the main [result](EntryMarker::result), a command can return a second, 64-bit value,
+/// such as the result field of an NVMe completion for NVMe passthrough commands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UringCmdResult {
+    res2: u64,
+}
+
+impl UringCmdResult {
+    /// The second result of the command, whose meaning depends on the command.
+    #[inline]
+    pub fn res2(&self) -> u64 {
+        self.res2
+    }
+}
+
+impl BigCqe for UringCmdResult {
+    const CODE: u8 = sys::IORING_OP_URING_CMD as u8;
+
+    #[inline]
+    fn from_big_cqe(big_cqe: &[u64; 2]) -> Self {
+        UringCmdResult { res2: big_cqe[0] }
+    }
 }
 
 impl private::Sealed for Entry32 {}
@@ -297,6 +599,54 @@ impl Debug for Entry32 {
     }
 }
 
+/// The metadata carried in the flags of a completion queue entry, decoded once.
+///
+/// This can be obtained from [`CompletionQueue::pop_with_meta`] or
+/// [`CqeMeta::from_flags`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CqeMeta {
+    buffer_id: Option<u16>,
+    more: bool,
+    sock_nonempty: bool,
+    notif: bool,
+}
+
+impl CqeMeta {
+    /// Decode the flags of a completion queue entry.
+    pub fn from_flags(flags: u32) -> CqeMeta {
+        CqeMeta {
+            buffer_id: buffer_select(flags),
+            more: more(flags),
+            sock_nonempty: sock_nonempty(flags),
+            notif: notif(flags),
+        }
+    }
+
+    /// The selected buffer ID, see [`buffer_select`].
+    #[inline]
+    pub fn buffer_id(&self) -> Option<u16> {
+        self.buffer_id
+    }
+
+    /// Whether further completion events will be posted for this operation, see [`more`].
+    #[inline]
+    pub fn more(&self) -> bool {
+        self.more
+    }
+
+    /// Whether the socket has more data ready to read, see [`sock_nonempty`].
+    #[inline]
+    pub fn sock_nonempty(&self) -> bool {
+        self.sock_nonempty
+    }
+
+    /// Whether this completion event is a notification, see [`notif`].
+    #[inline]
+    pub fn notif(&self) -> bool {
+        self.notif
+    }
+}
+
 /// Return which dynamic buffer was used by this operation.
 ///
 /// This corresponds to the `IORING_CQE_F_BUFFER` flag (and related bit-shifting),
@@ -343,3 +693,407 @@ pub fn sock_nonempty(flags: u32) -> bool {
 pub fn notif(flags: u32) -> bool {
     flags & sys::IORING_CQE_F_NOTIF != 0
 }
+
+#[cfg(test)]
+mod tests {
+    use std::cell::UnsafeCell;
+    use std::sync::atomic::AtomicU32;
+
+    use super::*;
+
+    /// A completion queue ring living in ordinary memory, standing in for the one shared with the
+    /// kernel.
+    pub(crate) struct FakeRing {
+        head: AtomicU32,
+        tail: AtomicU32,
+        overflow: AtomicU32,
+        flags: AtomicU32,
+        sq_flags: AtomicU32,
+        cqes: Box<[UnsafeCell<Entry>]>,
+    }
+
+    impl FakeRing {
+        pub(crate) fn new(entries: u32) -> FakeRing {
+            assert!(entries.is_power_of_two());
+
+            FakeRing {
+                head: AtomicU32::new(0),
+                tail: AtomicU32::new(0),
+                overflow: AtomicU32::new(0),
+                flags: AtomicU32::new(0),
+                sq_flags: AtomicU32::new(0),
+                cqes: (0..entries)
+                    .map(|_| UnsafeCell::new(cqe(0, 0, 0)))
+                    .collect(),
+            }
+        }
+
+        pub(crate) fn inner(&self) -> Inner<Entry> {
+            Inner {
+                head: &self.head,
+                tail: &self.tail,
+                ring_mask: self.cqes.len() as u32 - 1,
+                ring_entries: self.cqes.len() as u32,
+                overflow: &self.overflow,
+                cqes: self.cqes.as_ptr().cast(),
+                flags: &self.flags,
+                sq_flags: &self.sq_flags,
+            }
+        }
+
+        /// Post an entry as the kernel would.
+        pub(crate) fn post(&self, entry: Entry) {
+            let tail = self.tail.load(atomic::Ordering::Relaxed);
+            let index = tail as usize & (self.cqes.len() - 1);
+            unsafe { *self.cqes[index].get() = entry };
+            self.tail
+                .store(tail.wrapping_add(1), atomic::Ordering::Release);
+        }
+    }
+
+    pub(crate) fn cqe(user_data: u64, res: i32, flags: u32) -> Entry {
+        Entry(sys::io_uring_cqe {
+            user_data,
+            res,
+            flags,
+            big_cqe: Default::default(),
+        })
+    }
+
+    #[test]
+    fn test_pop_with_meta() {
+        let ring = FakeRing::new(8);
+        let mut inner = ring.inner();
+
+        ring.post(cqe(
+            1,
+            64,
+            sys::IORING_CQE_F_BUFFER | (7 << sys::IORING_CQE_BUFFER_SHIFT) | sys::IORING_CQE_F_MORE,
+        ));
+        ring.post(cqe(
+            2,
+            32,
+            sys::IORING_CQE_F_BUFFER
+                | ((u16::MAX as u32) << sys::IORING_CQE_BUFFER_SHIFT)
+                | sys::IORING_CQE_F_SOCK_NONEMPTY,
+        ));
+        ring.post(cqe(3, 0, sys::IORING_CQE_F_NOTIF));
+
+        let mut cq = inner.borrow();
+        assert_eq!(cq.len(), 3);
+
+        let (entry, meta) = unsafe { cq.pop_with_meta() };
+        assert_eq!(entry.user_data(), 1);
+        assert_eq!(entry.result(), 64);
+        assert_eq!(meta.buffer_id(), Some(7));
+        assert!(meta.more());
+        assert!(!meta.sock_nonempty());
+        assert!(!meta.notif());
+
+        let (entry, meta) = unsafe { cq.pop_with_meta() };
+        assert_eq!(entry.user_data(), 2);
+        assert_eq!(meta.buffer_id(), Some(u16::MAX));
+        assert!(!meta.more());
+        assert!(meta.sock_nonempty());
+        assert!(!meta.notif());
+
+        let (entry, meta) = unsafe { cq.pop_with_meta() };
+        assert_eq!(entry.user_data(), 3);
+        assert_eq!(meta.buffer_id(), None);
+        assert!(!meta.more());
+        assert!(meta.notif());
+        assert_eq!(meta, CqeMeta::from_flags(entry.flags()));
+
+        assert!(cq.is_empty());
+        drop(cq);
+        assert_eq!(ring.head.load(atomic::Ordering::Acquire), 3);
+    }
+
+    #[test]
+    fn test_drain() {
+        let ring = FakeRing::new(16);
+        let mut inner = ring.inner();
+
+        for i in 0..10 {
+            ring.post(cqe(i, 0, 0));
+        }
+
+        let mut cq = inner.borrow();
+        assert_eq!(cq.len(), 10);
+        assert_eq!(cq.drain(), 10);
+        assert!(cq.is_empty());
+        assert_eq!(cq.drain(), 0);
+        drop(cq);
+        assert_eq!(ring.head.load(atomic::Ordering::Acquire), 10);
+
+        ring.post(cqe(10, 0, 0));
+
+        let mut cq = inner.borrow();
+        assert_eq!(cq.next().map(|entry| entry.user_data()), Some(10));
+        assert!(cq.is_empty());
+    }
+
+    #[test]
+    fn test_peek() {
+        let ring = FakeRing::new(4);
+        let mut inner = ring.inner();
+
+        assert!(inner.borrow().peek().is_none());
+
+        ring.post(cqe(1, 10, 0));
+        ring.post(cqe(2, 20, 0));
+
+        let mut cq = inner.borrow();
+        let peeked = cq.peek().cloned().unwrap();
+        assert_eq!(peeked.user_data(), 1);
+        assert_eq!(peeked.result(), 10);
+        assert_eq!(cq.peek().map(|entry| entry.user_data()), Some(1));
+        assert_eq!(cq.len(), 2);
+
+        let next = cq.next().unwrap();
+        assert_eq!(next.user_data(), peeked.user_data());
+        assert_eq!(next.result(), peeked.result());
+        assert_eq!(next.flags(), peeked.flags());
+
+        assert_eq!(cq.peek().map(|entry| entry.user_data()), Some(2));
+        cq.next();
+        assert!(cq.peek().is_none());
+    }
+
+    #[test]
+    fn test_flags() {
+        let ring = FakeRing::new(4);
+        let inner = ring.inner();
+        let cq = unsafe { inner.borrow_shared() };
+
+        assert_eq!(cq.flags(), 0);
+        assert!(!cq.eventfd_disabled());
+        assert!(!cq.needs_enter());
+
+        ring.flags
+            .store(sys::IORING_CQ_EVENTFD_DISABLED, atomic::Ordering::Release);
+        assert_eq!(cq.flags(), sys::IORING_CQ_EVENTFD_DISABLED);
+        assert!(cq.eventfd_disabled());
+        assert!(!cq.needs_enter());
+
+        ring.sq_flags
+            .store(sys::IORING_SQ_TASKRUN, atomic::Ordering::Release);
+        assert!(cq.needs_enter());
+
+        ring.sq_flags
+            .store(sys::IORING_SQ_CQ_OVERFLOW, atomic::Ordering::Release);
+        assert!(cq.needs_enter());
+
+        ring.sq_flags
+            .store(sys::IORING_SQ_NEED_WAKEUP, atomic::Ordering::Release);
+        assert!(!cq.needs_enter());
+
+        ring.flags.store(0, atomic::Ordering::Release);
+        assert_eq!(cq.flags(), 0);
+    }
+
+    #[test]
+    fn test_for_each_until() {
+        let ring = FakeRing::new(8);
+        let mut inner = ring.inner();
+
+        for user_data in [1, 1, 1, 2, 1, 2] {
+            ring.post(cqe(user_data, 0, 0));
+        }
+
+        let mut cq = inner.borrow();
+        let mut seen = 0;
+        let consumed = cq.for_each_until(|entry| {
+            seen += 1;
+            entry.user_data() == 1
+        });
+        assert_eq!(consumed, 3);
+        assert_eq!(seen, 4);
+        assert_eq!(cq.len(), 3);
+
+        cq.sync();
+        assert_eq!(ring.head.load(atomic::Ordering::Acquire), 3);
+        assert_eq!(cq.len(), 3);
+        assert_eq!(cq.peek().map(|entry| entry.user_data()), Some(2));
+
+        // A router that accepts everything empties the queue.
+        assert_eq!(cq.for_each_until(|_| true), 3);
+        assert!(cq.is_empty());
+        assert_eq!(cq.for_each_until(|_| panic!("queue is empty")), 0);
+        drop(cq);
+        assert_eq!(ring.head.load(atomic::Ordering::Acquire), 6);
+    }
+
+    #[test]
+    fn test_fill_vec() {
+        let ring = FakeRing::new(4);
+        let mut inner = ring.inner();
+        let mut entries = Vec::new();
+
+        assert_eq!(inner.borrow().fill_vec(&mut entries), 0);
+        assert!(entries.is_empty());
+
+        for i in 0..3 {
+            ring.post(cqe(i, 0, 0));
+        }
+
+        let mut cq = inner.borrow();
+        let len = cq.len();
+        assert_eq!(cq.fill_vec(&mut entries), len);
+        assert_eq!(entries.len(), len);
+        assert!(cq.is_empty());
+        drop(cq);
+
+        for i in 3..7 {
+            ring.post(cqe(i, 0, 0));
+        }
+
+        let mut cq = inner.borrow();
+        assert_eq!(cq.fill_vec(&mut entries), 4);
+        assert!(cq.is_empty());
+
+        let user_data: Vec<u64> = entries.iter().map(|entry| entry.user_data()).collect();
+        assert_eq!(user_data, (0..7).collect::<Vec<u64>>());
+    }
+
+    #[test]
+    fn test_with_buf_ring() {
+        let ring = FakeRing::new(4);
+        let mut inner = ring.inner();
+
+        let mut bufs = [*b"aaaa", *b"bbbb"];
+        let mut buf_ring = BufRing::new(2).unwrap();
+        for (bid, buf) in bufs.iter_mut().enumerate() {
+            unsafe { buf_ring.add(buf.as_mut_ptr(), buf.len() as _, bid as _) };
+        }
+        buf_ring.advance();
+
+        let entries = buf_ring.ring_addr() as *const crate::types::BufRingEntry;
+        let tail = || unsafe { *crate::types::BufRingEntry::tail(entries) };
+        assert_eq!(tail(), 2);
+
+        ring.post(cqe(
+            1,
+            3,
+            sys::IORING_CQE_F_BUFFER | (1 << sys::IORING_CQE_BUFFER_SHIFT),
+        ));
+        ring.post(cqe(2, -libc::ENOBUFS, 0));
+
+        let mut cq = inner.borrow();
+        let mut completions = unsafe { cq.with_buf_ring(&mut buf_ring) };
+
+        let completion = completions.next_completion().unwrap();
+        assert_eq!(completion.entry().user_data(), 1);
+        assert_eq!(completion.buffer_id(), Some(1));
+        assert_eq!(completion.data(), b"bbb");
+        drop(completion);
+
+        let completion = completions.next_completion().unwrap();
+        assert_eq!(completion.entry().user_data(), 2);
+        assert_eq!(completion.buffer_id(), None);
+        assert!(completion.data().is_empty());
+        drop(completion);
+
+        assert!(completions.next_completion().is_none());
+
+        // Only the selected buffer was handed back to the ring, wrapping around to its first entry.
+        assert_eq!(tail(), 3);
+        let entry = unsafe { &*entries };
+        assert_eq!(entry.bid(), 1);
+        assert_eq!(entry.len(), 4);
+    }
+
+    #[test]
+    fn test_decode_big_cqe() {
+        let entry = Entry32(cqe(0x80, 0, 0), [0x0123_4567_89ab_cdef, 0]);
+
+        let result: UringCmdResult = entry.decode_big_cqe();
+        assert_eq!(result.res2(), 0x0123_4567_89ab_cdef);
+        assert_eq!(UringCmdResult::CODE, crate::opcode::UringCmd80::CODE);
+        assert_eq!(UringCmdResult::CODE, crate::opcode::UringCmd16::CODE);
+
+        let entry = Entry32(cqe(0x16, -libc::EINVAL, 0), [0; 2]);
+        assert_eq!(entry.decode_big_cqe::<UringCmdResult>().res2(), 0);
+    }
+
+    #[test]
+    fn test_as_slices() {
+        let ring = FakeRing::new(8);
+        let mut inner = ring.inner();
+
+        // Move the head close to the end of the ring, so the next entries wrap around.
+        for i in 0..6 {
+            ring.post(cqe(i, 0, 0));
+        }
+        assert_eq!(inner.borrow().drain(), 6);
+
+        let cq = inner.borrow();
+        let (first, second) = cq.as_slices();
+        assert!(first.is_empty() && second.is_empty());
+        drop(cq);
+
+        for i in 6..11 {
+            ring.post(cqe(i, 0, 0));
+        }
+
+        let mut cq = inner.borrow();
+        let (first, second) = cq.as_slices();
+        assert_eq!(first.len(), 2);
+        assert_eq!(second.len(), 3);
+        assert_eq!(first.len() + second.len(), cq.len());
+
+        let user_data: Vec<u64> = first
+            .iter()
+            .chain(second)
+            .map(|entry| entry.user_data())
+            .collect();
+        assert_eq!(user_data, (6..11).collect::<Vec<u64>>());
+
+        // Viewing the entries doesn't consume them.
+        assert_eq!(cq.len(), 5);
+        cq.advance(5);
+        assert!(cq.is_empty());
+        drop(cq);
+        assert_eq!(ring.head.load(atomic::Ordering::Acquire), 11);
+    }
+
+    #[test]
+    fn test_advance() {
+        let ring = FakeRing::new(8);
+        let mut inner = ring.inner();
+
+        for i in 0..5 {
+            ring.post(cqe(i, 0, 0));
+        }
+
+        let mut cq = inner.borrow();
+        cq.advance(2);
+        assert_eq!(cq.len(), 3);
+
+        // The head is only published on sync.
+        assert_eq!(ring.head.load(atomic::Ordering::Acquire), 0);
+        cq.sync();
+        assert_eq!(ring.head.load(atomic::Ordering::Acquire), 2);
+
+        let user_data: Vec<u64> = cq.by_ref().map(|entry| entry.user_data()).collect();
+        assert_eq!(user_data, [2, 3, 4]);
+        drop(cq);
+
+        for i in 5..8 {
+            ring.post(cqe(i, 0, 0));
+        }
+
+        // Advancing past the end stops at the tail.
+        let mut cq = inner.borrow();
+        cq.advance(usize::MAX);
+        assert!(cq.is_empty());
+        cq.advance(1);
+        assert!(cq.is_empty());
+        drop(cq);
+        assert_eq!(ring.head.load(atomic::Ordering::Acquire), 8);
+
+        ring.post(cqe(8, 0, 0));
+        let mut cq = inner.borrow();
+        assert_eq!(cq.next().map(|entry| entry.user_data()), Some(8));
+    }
+}
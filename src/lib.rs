@@ -16,16 +16,21 @@ pub mod types;
 use std::marker::PhantomData;
 use std::mem::ManuallyDrop;
 use std::os::unix::io::{AsRawFd, FromRawFd, RawFd};
+use std::sync::Arc;
 use std::{cmp, io, mem};
 
 #[cfg(feature = "io_safety")]
 use std::os::unix::io::{AsFd, BorrowedFd};
 
 pub use cqueue::CompletionQueue;
-pub use register::Probe;
+use register::CapabilitiesCache;
+pub use register::{Capabilities, Probe};
 pub use squeue::SubmissionQueue;
 pub use submit::Submitter;
-use util::{Mmap, OwnedFd};
+use util::{Mmap, OwnedFd, RegisteredRingFd};
+
+/// The kernel's `IORING_MAX_CQ_ENTRIES`.
+const MAX_CQ_ENTRIES: u32 = 2 * 32768;
 
 /// IoUring instance
 ///
@@ -43,6 +48,8 @@ where
     fd: OwnedFd,
     params: Parameters,
     memory: ManuallyDrop<MemoryMap>,
+    registered_ring_fd: RegisteredRingFd,
+    capabilities: CapabilitiesCache,
 }
 
 #[allow(dead_code)]
@@ -53,6 +60,10 @@ struct MemoryMap {
 }
 
 /// IoUring build params
+///
+/// The entry sizes are selected through the `S` and `C` type parameters rather than setup flags:
+/// using [`squeue::Entry128`] sets `IORING_SETUP_SQE128`, and using [`cqueue::Entry32`] sets
+/// `IORING_SETUP_CQE32`, so that the queues always match the layout the kernel uses.
 #[derive(Clone, Default)]
 pub struct Builder<S = squeue::Entry, C = cqueue::Entry>
 where
@@ -61,6 +72,8 @@ where
 {
     dontfork: bool,
     params: sys::io_uring_params,
+    /// The lengths of the ring and SQE memory passed to `setup_no_mmap`.
+    no_mmap_len: (usize, usize),
     phantom: PhantomData<(S, C)>,
 }
 
@@ -95,6 +108,25 @@ impl IoUring<squeue::Entry, cqueue::Entry> {
     pub unsafe fn from_fd(fd: RawFd, params: Parameters) -> io::Result<Self> {
         Self::with_fd_and_params(OwnedFd::from_raw_fd(fd), params.0)
     }
+
+    /// Create a [`Builder`] for an `IoUring` instance with 32-byte completion queue entries
+    /// ([`cqueue::Entry32`]), whose extra bytes are read with
+    /// [`big_cqe`](cqueue::Entry32::big_cqe).
+    ///
+    /// This is the same as `IoUring::<squeue::Entry, cqueue::Entry32>::builder()`.
+    #[must_use]
+    pub fn builder_cqe32() -> Builder<squeue::Entry, cqueue::Entry32> {
+        IoUring::builder()
+    }
+
+    /// Create a [`Builder`] for an `IoUring` instance with 128-byte submission queue entries
+    /// ([`squeue::Entry128`]), as needed by some [`UringCmd80`](opcode::UringCmd80) commands.
+    ///
+    /// This is the same as `IoUring::<squeue::Entry128, cqueue::Entry>::builder()`.
+    #[must_use]
+    pub fn builder_sqe128() -> Builder<squeue::Entry128, cqueue::Entry> {
+        IoUring::builder()
+    }
 }
 
 impl<S: squeue::EntryMarker, C: cqueue::EntryMarker> IoUring<S, C> {
@@ -112,6 +144,7 @@ impl<S: squeue::EntryMarker, C: cqueue::EntryMarker> IoUring<S, C> {
                 flags: S::BUILD_FLAGS | C::BUILD_FLAGS,
                 ..Default::default()
             },
+            no_mmap_len: (0, 0),
             phantom: PhantomData,
         }
     }
@@ -134,9 +167,31 @@ impl<S: squeue::EntryMarker, C: cqueue::EntryMarker> IoUring<S, C> {
             fd: &OwnedFd,
             p: &sys::io_uring_params,
         ) -> io::Result<(MemoryMap, squeue::Inner<S>, cqueue::Inner<C>)> {
-            let sq_len = p.sq_off.array as usize + p.sq_entries as usize * mem::size_of::<u32>();
+            let sq_len = if p.flags & sys::IORING_SETUP_NO_SQARRAY != 0 {
+                cmp::max(p.sq_off.flags, p.sq_off.dropped) as usize + mem::size_of::<u32>()
+            } else {
+                p.sq_off.array as usize + p.sq_entries as usize * mem::size_of::<u32>()
+            };
             let cq_len = p.cq_off.cqes as usize + p.cq_entries as usize * mem::size_of::<C>();
             let sqe_len = p.sq_entries as usize * mem::size_of::<S>();
+
+            if p.flags & sys::IORING_SETUP_NO_MMAP != 0 {
+                // The rings and SQEs live in the memory given to `setup_no_mmap`, with both rings
+                // sharing one region.
+                let sqe_mmap = Mmap::from_user(p.sq_off.user_addr as _, sqe_len)?;
+                let scq_mmap = Mmap::from_user(p.cq_off.user_addr as _, cmp::max(sq_len, cq_len))?;
+
+                let sq = squeue::Inner::new(&scq_mmap, &sqe_mmap, p);
+                let cq = cqueue::Inner::new(&scq_mmap, sq.flags, p);
+                let mm = MemoryMap {
+                    sq_mmap: scq_mmap,
+                    cq_mmap: None,
+                    sqe_mmap,
+                };
+
+                return Ok((mm, sq, cq));
+            }
+
             let sqe_mmap = Mmap::new(fd, sys::IORING_OFF_SQES as _, sqe_len)?;
 
             if p.features & sys::IORING_FEAT_SINGLE_MMAP != 0 {
@@ -144,7 +199,7 @@ impl<S: squeue::EntryMarker, C: cqueue::EntryMarker> IoUring<S, C> {
                     Mmap::new(fd, sys::IORING_OFF_SQ_RING as _, cmp::max(sq_len, cq_len))?;
 
                 let sq = squeue::Inner::new(&scq_mmap, &sqe_mmap, p);
-                let cq = cqueue::Inner::new(&scq_mmap, p);
+                let cq = cqueue::Inner::new(&scq_mmap, sq.flags, p);
                 let mm = MemoryMap {
                     sq_mmap: scq_mmap,
                     cq_mmap: None,
@@ -157,7 +212,7 @@ impl<S: squeue::EntryMarker, C: cqueue::EntryMarker> IoUring<S, C> {
                 let cq_mmap = Mmap::new(fd, sys::IORING_OFF_CQ_RING as _, cq_len)?;
 
                 let sq = squeue::Inner::new(&sq_mmap, &sqe_mmap, p);
-                let cq = cqueue::Inner::new(&cq_mmap, p);
+                let cq = cqueue::Inner::new(&cq_mmap, sq.flags, p);
                 let mm = MemoryMap {
                     cq_mmap: Some(cq_mmap),
                     sq_mmap,
@@ -176,6 +231,8 @@ impl<S: squeue::EntryMarker, C: cqueue::EntryMarker> IoUring<S, C> {
             fd,
             params: Parameters(p),
             memory: ManuallyDrop::new(mm),
+            registered_ring_fd: RegisteredRingFd::new(),
+            capabilities: CapabilitiesCache::new(),
         })
     }
 
@@ -186,9 +243,12 @@ impl<S: squeue::EntryMarker, C: cqueue::EntryMarker> IoUring<S, C> {
         Submitter::new(
             &self.fd,
             &self.params,
+            Some(&self.registered_ring_fd),
             self.sq.head,
             self.sq.tail,
             self.sq.flags,
+            self.cq.head,
+            self.cq.tail,
         )
     }
 
@@ -198,6 +258,32 @@ impl<S: squeue::EntryMarker, C: cqueue::EntryMarker> IoUring<S, C> {
         &self.params
     }
 
+    /// A submitter that always enters the ring through its fd, for the halves of
+    /// [`into_split`](Self::into_split).
+    #[inline]
+    fn split_submitter(&self) -> Submitter<'_> {
+        Submitter::new(
+            &self.fd,
+            &self.params,
+            None,
+            self.sq.head,
+            self.sq.tail,
+            self.sq.flags,
+            self.cq.head,
+            self.cq.tail,
+        )
+    }
+
+    /// Summarize what the kernel supports. The first successful call issues a
+    /// [`register_probe`](Submitter::register_probe), later calls return the cached result.
+    pub fn capabilities(&self) -> io::Result<Capabilities> {
+        self.capabilities.get_or_try_init(|| {
+            let mut probe = Probe::new();
+            self.submitter().register_probe(&mut probe)?;
+            Ok(Capabilities::new(&probe, &self.params))
+        })
+    }
+
     /// Initiate asynchronous I/O. See [`Submitter::submit`] for more details.
     #[inline]
     pub fn submit(&self) -> io::Result<usize> {
@@ -211,6 +297,59 @@ impl<S: squeue::EntryMarker, C: cqueue::EntryMarker> IoUring<S, C> {
         self.submitter().submit_and_wait(want)
     }
 
+    /// Push all of `entries` and submit them, waiting for at least `want` completion events.
+    ///
+    /// Whenever the submission queue fills up, the entries pushed so far are submitted to make
+    /// room, so `entries` may be larger than the queue. On an [SQPOLL](Builder::setup_sqpoll)
+    /// ring, this instead blocks until the poll thread has consumed some of them. The remaining
+    /// entries are then submitted with a single [`submit_and_wait`](Submitter::submit_and_wait).
+    ///
+    /// Returns how many of `entries` were submitted, counted from the entries the kernel took out
+    /// of the queue rather than from what each submission reported. Entries already queued before
+    /// the call are submitted too, but not counted. On an SQPOLL ring, all of `entries` count as
+    /// submitted once they are queued, as the poll thread picks them up on its own.
+    ///
+    /// Fails with `EBUSY` if the kernel takes none of a full queue, in which case the entries
+    /// pushed so far stay in the queue. Make sure the completion queue can hold the completions,
+    /// for example with [`Builder::setup_cqsize`], as they are only reaped by the caller.
+    ///
+    /// # Safety
+    ///
+    /// Developers must ensure that parameters of all the entries (such as buffer) are valid and
+    /// will be valid for the entire duration of the operation, otherwise it may cause memory
+    /// problems.
+    pub unsafe fn submit_batch(&mut self, entries: &[S], want: usize) -> io::Result<usize> {
+        let sqpoll = self.params.is_setup_sqpoll();
+        let mut rest = entries;
+
+        loop {
+            let pushed = self.sq.borrow().push_partial(rest);
+            rest = &rest[pushed..];
+
+            if rest.is_empty() {
+                break;
+            }
+
+            if sqpoll {
+                // Wake the poll thread if it went to sleep, then wait for it to free a slot.
+                self.submit()?;
+                self.submitter().squeue_wait()?;
+            } else if self.submit()? == 0 {
+                return Err(io::Error::from_raw_os_error(libc::EBUSY));
+            }
+        }
+
+        self.submit_and_wait(want)?;
+
+        if sqpoll {
+            return Ok(entries.len());
+        }
+
+        // The kernel consumes the queue in order, so whatever it left behind is ours.
+        let left = self.sq.borrow().len();
+        Ok(entries.len() - cmp::min(left, entries.len()))
+    }
+
     /// Get the submitter, submission queue and completion queue of the io_uring instance. This can
     /// be used to operate on the different parts of the io_uring instance independently.
     ///
@@ -228,13 +367,27 @@ impl<S: squeue::EntryMarker, C: cqueue::EntryMarker> IoUring<S, C> {
         let submit = Submitter::new(
             &self.fd,
             &self.params,
+            Some(&self.registered_ring_fd),
             self.sq.head,
             self.sq.tail,
             self.sq.flags,
+            self.cq.head,
+            self.cq.tail,
         );
         (submit, self.sq.borrow(), self.cq.borrow())
     }
 
+    /// Whether there are completion queue events waiting to be processed.
+    ///
+    /// This only reads the ring, so it can be used from an external event loop: poll the ring fd
+    /// (see [`AsRawFd`]) for `POLLIN`, or wait on an eventfd registered with
+    /// [`Submitter::register_eventfd`], and drain the [`completion`](Self::completion) queue once
+    /// this returns `true`.
+    #[inline]
+    pub fn completion_ready(&self) -> bool {
+        self.cq.is_ready()
+    }
+
     /// Get the submission queue of the io_uring instance. This is used to send I/O requests to the
     /// kernel.
     #[inline]
@@ -268,10 +421,105 @@ impl<S: squeue::EntryMarker, C: cqueue::EntryMarker> IoUring<S, C> {
     pub unsafe fn completion_shared(&self) -> CompletionQueue<'_, C> {
         self.cq.borrow_shared()
     }
+
+    /// Split the io_uring instance into owned submission and completion halves, which can be
+    /// moved to different threads so that one submits while the other reaps completions.
+    ///
+    /// The ring is closed once both halves are dropped. The halves should not be used across
+    /// threads if the ring was set up with [`Builder::setup_single_issuer`].
+    ///
+    /// A ring fd registered with [`Submitter::register_ring_fd`] belongs to the thread that
+    /// registered it, so the halves ignore it and always enter the ring through its fd, and they
+    /// can't register it themselves.
+    pub fn into_split(self) -> (SubmissionHalf<S, C>, CompletionHalf<S, C>) {
+        let ring = Arc::new(self);
+        (
+            SubmissionHalf { ring: ring.clone() },
+            CompletionHalf { ring },
+        )
+    }
+}
+
+/// The submission half of an [`IoUring`], created by [`IoUring::into_split`].
+pub struct SubmissionHalf<S = squeue::Entry, C = cqueue::Entry>
+where
+    S: squeue::EntryMarker,
+    C: cqueue::EntryMarker,
+{
+    ring: Arc<IoUring<S, C>>,
+}
+
+impl<S: squeue::EntryMarker, C: cqueue::EntryMarker> SubmissionHalf<S, C> {
+    /// Get the submitter of this io_uring instance.
+    #[inline]
+    pub fn submitter(&self) -> Submitter<'_> {
+        self.ring.split_submitter()
+    }
+
+    /// Get the parameters that were used to construct this instance.
+    #[inline]
+    pub fn params(&self) -> &Parameters {
+        self.ring.params()
+    }
+
+    /// Get the submission queue of the io_uring instance.
+    #[inline]
+    pub fn submission(&mut self) -> SubmissionQueue<'_, S> {
+        // Only this half touches the submission queue, and `&mut self` makes the borrow unique.
+        unsafe { self.ring.submission_shared() }
+    }
+
+    /// Initiate asynchronous I/O. See [`Submitter::submit`] for more details.
+    #[inline]
+    pub fn submit(&self) -> io::Result<usize> {
+        self.submitter().submit()
+    }
+}
+
+/// The completion half of an [`IoUring`], created by [`IoUring::into_split`].
+pub struct CompletionHalf<S = squeue::Entry, C = cqueue::Entry>
+where
+    S: squeue::EntryMarker,
+    C: cqueue::EntryMarker,
+{
+    ring: Arc<IoUring<S, C>>,
+}
+
+impl<S: squeue::EntryMarker, C: cqueue::EntryMarker> CompletionHalf<S, C> {
+    /// Get the parameters that were used to construct this instance.
+    #[inline]
+    pub fn params(&self) -> &Parameters {
+        self.ring.params()
+    }
+
+    /// Get the completion queue of the io_uring instance.
+    #[inline]
+    pub fn completion(&mut self) -> CompletionQueue<'_, C> {
+        // Only this half touches the completion queue, and `&mut self` makes the borrow unique.
+        unsafe { self.ring.completion_shared() }
+    }
+
+    /// Wait for at least `want` completion events, without submitting anything.
+    pub fn wait(&self, want: usize) -> io::Result<usize> {
+        unsafe {
+            self.ring.split_submitter().enter::<libc::sigset_t>(
+                0,
+                want as _,
+                sys::IORING_ENTER_GETEVENTS,
+                None,
+            )
+        }
+    }
 }
 
 impl<S: squeue::EntryMarker, C: cqueue::EntryMarker> Drop for IoUring<S, C> {
     fn drop(&mut self) {
+        // The registered ring fd holds a reference to the ring until the task exits. Only the
+        // registering thread can unregister it: elsewhere, the index may belong to another ring.
+        if self.registered_ring_fd.get().is_some() {
+            let _ = self.submitter().unregister_ring_fd();
+        }
+
         // Ensure that `MemoryMap` is released before `fd`.
         unsafe {
             ManuallyDrop::drop(&mut self.memory);
@@ -281,6 +529,11 @@ impl<S: squeue::EntryMarker, C: cqueue::EntryMarker> Drop for IoUring<S, C> {
 
 impl<S: squeue::EntryMarker, C: cqueue::EntryMarker> Builder<S, C> {
     /// Do not make this io_uring instance accessible by child processes after a fork.
+    ///
+    /// The ring memory is marked with `MADV_DONTFORK` once it is mapped, so a child gets no
+    /// mapping of it rather than a copy-on-write view that no longer matches the kernel's state.
+    /// Touching the ring from the child then faults instead of silently corrupting it. This is the
+    /// equivalent of liburing's `io_uring_ring_dontfork`.
     pub fn dontfork(&mut self) -> &mut Self {
         self.dontfork = true;
         self
@@ -290,11 +543,28 @@ impl<S: squeue::EntryMarker, C: cqueue::EntryMarker> Builder<S, C> {
     /// asynchronous IRQ (Interrupt Request). This will reduce latency, but increases CPU usage.
     ///
     /// This is only usable on file systems that support polling and files opened with `O_DIRECT`.
+    /// Operations on other files fail with `EOPNOTSUPP`, and only reads and writes can be used.
+    ///
+    /// Completions are not posted on their own: they are only reaped when the ring is entered
+    /// with `IORING_ENTER_GETEVENTS`, which [`Submitter::submit`] and friends always do on such a
+    /// ring. Checking the completion queue without entering will spin forever, so use
+    /// [`Submitter::iopoll_wait`] to wait for completions instead.
     pub fn setup_iopoll(&mut self) -> &mut Self {
         self.params.flags |= sys::IORING_SETUP_IOPOLL;
         self
     }
 
+    /// Sleep for a short while before busy-polling for a completion, instead of polling right
+    /// away. The sleep is based on how long previous requests took to complete, which saves CPU
+    /// time on devices with a predictable latency, at the cost of some latency.
+    ///
+    /// This requires [`setup_iopoll`](Self::setup_iopoll): [`build`](Self::build) fails with
+    /// [`InvalidInput`](io::ErrorKind::InvalidInput) otherwise. Available since kernel 6.13.
+    pub fn setup_hybrid_iopoll(&mut self) -> &mut Self {
+        self.params.flags |= sys::IORING_SETUP_HYBRID_IOPOLL;
+        self
+    }
+
     /// Use a kernel thread to perform submission queue polling. This allows your application to
     /// issue I/O without ever context switching into the kernel, however it does use up a lot more
     /// CPU. You should use it when you are expecting very large amounts of I/O.
@@ -319,23 +589,35 @@ impl<S: squeue::EntryMarker, C: cqueue::EntryMarker> Builder<S, C> {
     }
 
     /// Bind the kernel's poll thread to the specified cpu. This flag is only meaningful when
-    /// [`Builder::setup_sqpoll`] is enabled.
+    /// [`Builder::setup_sqpoll`] is enabled, and [`build`](Self::build) fails with
+    /// [`InvalidInput`](io::ErrorKind::InvalidInput) without it.
     pub fn setup_sqpoll_cpu(&mut self, cpu: u32) -> &mut Self {
         self.params.flags |= sys::IORING_SETUP_SQ_AFF;
         self.params.sq_thread_cpu = cpu;
         self
     }
 
-    /// Create the completion queue with the specified number of entries. The value must be greater
-    /// than `entries`, and may be rounded up to the next power-of-two.
+    /// Create the completion queue with the specified number of entries, rounded up to the next
+    /// power-of-two.
+    ///
+    /// [`build`](Self::build) fails with [`InvalidInput`](io::ErrorKind::InvalidInput) if the
+    /// rounded value is zero, smaller than the submission queue, or larger than the kernel's
+    /// limit of 65536 entries, unless [`setup_clamp`](Self::setup_clamp) is also set.
     pub fn setup_cqsize(&mut self, entries: u32) -> &mut Self {
         self.params.flags |= sys::IORING_SETUP_CQSIZE;
-        self.params.cq_entries = entries;
+        self.params.cq_entries = match entries {
+            0 => 0,
+            n => n.checked_next_power_of_two().unwrap_or(u32::MAX),
+        };
         self
     }
 
     /// Clamp the sizes of the submission queue and completion queue at their maximum values instead
     /// of returning an error when you attempt to resize them beyond their maximum values.
+    ///
+    /// The kernel caps the submission queue at 32768 entries and the completion queue at 65536.
+    /// The sizes the ring ended up with can be read from [`Parameters::sq_entries`] and
+    /// [`Parameters::cq_entries`].
     pub fn setup_clamp(&mut self) -> &mut Self {
         self.params.flags |= sys::IORING_SETUP_CLAMP;
         self
@@ -391,7 +673,8 @@ impl<S: squeue::EntryMarker, C: cqueue::EntryMarker> Builder<S, C> {
     /// should be processed. As an example, liburing will check for this flag even when doing
     /// io_uring_peek_cqe(3) and enter the kernel to process them, and applications can do the
     /// same. This makes IORING_SETUP_TASKRUN_FLAG safe to use even when applications rely on a
-    /// peek style operation on the CQ ring to see if anything might be pending to reap. Available
+    /// peek style operation on the CQ ring to see if anything might be pending to reap. See
+    /// [`CompletionQueue::needs_enter`](cqueue::CompletionQueue::needs_enter). Available
     /// since 5.19.
     pub fn setup_taskrun_flag(&mut self) -> &mut Self {
         self.params.flags |= sys::IORING_SETUP_TASKRUN_FLAG;
@@ -407,7 +690,9 @@ impl<S: squeue::EntryMarker, C: cqueue::EntryMarker> Builder<S, C> {
     /// io_uring_enter(2) is called from the same thread that submitted requests. Note that if this
     /// flag is set then it is the application's responsibility to periodically trigger work (for
     /// example via any of the CQE waiting functions) or else completions may not be delivered.
-    /// Available since 6.1.
+    ///
+    /// [`build`](Self::build) fails with [`InvalidInput`](io::ErrorKind::InvalidInput) unless
+    /// [`Builder::setup_single_issuer`] is also set. Available since 6.1.
     pub fn setup_defer_taskrun(&mut self) -> &mut Self {
         self.params.flags |= sys::IORING_SETUP_DEFER_TASKRUN;
         self
@@ -416,15 +701,120 @@ impl<S: squeue::EntryMarker, C: cqueue::EntryMarker> Builder<S, C> {
     /// Hint the kernel that a single task will submit requests. Used for optimizations. This is
     /// enforced by the kernel, and request that don't respect that will fail with -EEXIST.
     /// If [`Builder::setup_sqpoll`] is enabled, the polling task is doing the submissions and multiple
-    /// userspace tasks can call [`Submitter::enter`] and higher level APIs. Available since 6.0.
+    /// userspace tasks can call [`Submitter::enter`] and higher level APIs. This is required by
+    /// [`Builder::setup_defer_taskrun`]. Available since 6.0.
     pub fn setup_single_issuer(&mut self) -> &mut Self {
         self.params.flags |= sys::IORING_SETUP_SINGLE_ISSUER;
         self
     }
 
+    /// Place the rings and the submission queue entries in memory provided by the application,
+    /// such as huge pages, instead of memory the kernel allocates and this crate maps.
+    ///
+    /// `rings` holds both the submission and completion queue rings, and must be at least `512 +
+    /// cq_entries * size_of::<C>() + sq_entries * 4` bytes. `sqes` holds the entries, and must be
+    /// at least `sq_entries * size_of::<S>()` bytes. The entry counts are the ones the ring is
+    /// built with, rounded up to a power of two. [`build`](Self::build) fails with
+    /// [`InvalidInput`](io::ErrorKind::InvalidInput) if either is too small. Both must be page
+    /// aligned, and on older kernels a buffer larger than a page must be a single huge page.
+    /// Available since 6.5.
+    ///
+    /// # Safety
+    ///
+    /// The memory must outlive every [`IoUring`] built from this builder, and must not be
+    /// accessed through any other path while they exist. The kernel may still write to it after
+    /// the ring is dropped, until it has finished tearing the ring down, so the memory must not be
+    /// reused for anything else afterwards. Unmapping it is fine, as the kernel holds on to the
+    /// pages itself.
+    pub unsafe fn setup_no_mmap(&mut self, rings: &mut [u8], sqes: &mut [u8]) -> &mut Self {
+        self.params.flags |= sys::IORING_SETUP_NO_MMAP;
+        self.params.cq_off.user_addr = rings.as_mut_ptr() as _;
+        self.params.sq_off.user_addr = sqes.as_mut_ptr() as _;
+        self.no_mmap_len = (rings.len(), sqes.len());
+        self
+    }
+
+    /// Remove the submission queue's indirection array, so the kernel consumes entries in ring
+    /// order. This saves memory and a cache line per submission, and changes nothing for this
+    /// crate, which always pushes entries in ring order. Available since 6.6.
+    pub fn setup_no_sqarray(&mut self) -> &mut Self {
+        self.params.flags |= sys::IORING_SETUP_NO_SQARRAY;
+        self
+    }
+
     /// Build an [IoUring], with the specified number of entries in the submission queue and
     /// completion queue unless [`setup_cqsize`](Self::setup_cqsize) has been called.
     pub fn build(&self, entries: u32) -> io::Result<IoUring<S, C>> {
+        if self.params.flags & sys::IORING_SETUP_DEFER_TASKRUN != 0
+            && self.params.flags & sys::IORING_SETUP_SINGLE_ISSUER == 0
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "setup_defer_taskrun requires setup_single_issuer",
+            ));
+        }
+
+        if self.params.flags & sys::IORING_SETUP_HYBRID_IOPOLL != 0
+            && self.params.flags & sys::IORING_SETUP_IOPOLL == 0
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "setup_hybrid_iopoll requires setup_iopoll",
+            ));
+        }
+
+        if self.params.flags & sys::IORING_SETUP_SQ_AFF != 0
+            && self.params.flags & sys::IORING_SETUP_SQPOLL == 0
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "setup_sqpoll_cpu requires setup_sqpoll",
+            ));
+        }
+
+        if self.params.flags & sys::IORING_SETUP_CQSIZE != 0
+            && self.params.flags & sys::IORING_SETUP_CLAMP == 0
+        {
+            let cq_entries = self.params.cq_entries;
+            let sq_entries = entries.checked_next_power_of_two().unwrap_or(u32::MAX);
+
+            if cq_entries == 0 || cq_entries > MAX_CQ_ENTRIES {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "setup_cqsize must be between 1 and 65536 entries",
+                ));
+            }
+            if cq_entries < sq_entries {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "setup_cqsize must not be smaller than the submission queue",
+                ));
+            }
+        }
+
+        if self.params.flags & sys::IORING_SETUP_NO_MMAP != 0 {
+            // The kernel writes to the memory as soon as the ring is set up, so check its size
+            // beforehand. The 512 bytes cover the ring header and alignment on any cache line size.
+            let sq_entries = entries.checked_next_power_of_two().unwrap_or(0) as usize;
+            let cq_entries = if self.params.flags & sys::IORING_SETUP_CQSIZE != 0 {
+                self.params
+                    .cq_entries
+                    .checked_next_power_of_two()
+                    .unwrap_or(0) as usize
+            } else {
+                sq_entries * 2
+            };
+            let rings_len = 512 + cq_entries * mem::size_of::<C>() + sq_entries * 4;
+            let sqes_len = sq_entries * mem::size_of::<S>();
+
+            if self.no_mmap_len.0 < rings_len || self.no_mmap_len.1 < sqes_len {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "setup_no_mmap memory is too small",
+                ));
+            }
+        }
+
         let ring = IoUring::with_params(entries, self.params)?;
 
         if self.dontfork {
@@ -445,17 +835,71 @@ impl Parameters {
         self.0.flags & sys::IORING_SETUP_SQPOLL != 0
     }
 
+    /// Whether the kernel's poll thread is bound to a cpu. Enabled with
+    /// [`Builder::setup_sqpoll_cpu`].
+    pub fn is_setup_sq_aff(&self) -> bool {
+        self.0.flags & sys::IORING_SETUP_SQ_AFF != 0
+    }
+
     /// Whether waiting for completion events is done with a busy loop instead of using IRQs.
     /// Enabled with [`Builder::setup_iopoll`].
     pub fn is_setup_iopoll(&self) -> bool {
         self.0.flags & sys::IORING_SETUP_IOPOLL != 0
     }
 
+    /// Whether polling for completions sleeps for a while first. Enabled with
+    /// [`Builder::setup_hybrid_iopoll`].
+    pub fn is_setup_hybrid_iopoll(&self) -> bool {
+        self.0.flags & sys::IORING_SETUP_HYBRID_IOPOLL != 0
+    }
+
+    /// Whether submission queue entries are 128 bytes, i.e. the ring was built for
+    /// [`squeue::Entry128`].
+    pub fn is_setup_sqe128(&self) -> bool {
+        self.0.flags & sys::IORING_SETUP_SQE128 != 0
+    }
+
+    /// Whether completion queue entries are 32 bytes, i.e. the ring was built for
+    /// [`cqueue::Entry32`].
+    pub fn is_setup_cqe32(&self) -> bool {
+        self.0.flags & sys::IORING_SETUP_CQE32 != 0
+    }
+
     /// Whether the single issuer hint is enabled. Enabled with [`Builder::setup_single_issuer`].
     pub fn is_setup_single_issuer(&self) -> bool {
         self.0.flags & sys::IORING_SETUP_SINGLE_ISSUER != 0
     }
 
+    /// Whether the submission queue has no indirection array. Enabled with
+    /// [`Builder::setup_no_sqarray`].
+    pub fn is_setup_no_sqarray(&self) -> bool {
+        self.0.flags & sys::IORING_SETUP_NO_SQARRAY != 0
+    }
+
+    /// Whether a failing request doesn't stop the submission of the rest of the batch. Enabled
+    /// with [`Builder::setup_submit_all`].
+    pub fn is_setup_submit_all(&self) -> bool {
+        self.0.flags & sys::IORING_SETUP_SUBMIT_ALL != 0
+    }
+
+    /// Whether task work is only run when the application enters the kernel, instead of
+    /// interrupting it. Enabled with [`Builder::setup_coop_taskrun`].
+    pub fn is_setup_coop_taskrun(&self) -> bool {
+        self.0.flags & sys::IORING_SETUP_COOP_TASKRUN != 0
+    }
+
+    /// Whether the kernel flags pending task work in the submission queue ring. Enabled with
+    /// [`Builder::setup_taskrun_flag`].
+    pub fn is_setup_taskrun_flag(&self) -> bool {
+        self.0.flags & sys::IORING_SETUP_TASKRUN_FLAG != 0
+    }
+
+    /// Whether completion task work is deferred until the application waits for completions.
+    /// Enabled with [`Builder::setup_defer_taskrun`].
+    pub fn is_setup_defer_taskrun(&self) -> bool {
+        self.0.flags & sys::IORING_SETUP_DEFER_TASKRUN != 0
+    }
+
     /// If this flag is set, the SQ and CQ rings were mapped with a single `mmap(2)` call. This
     /// means that only two syscalls were used instead of three.
     pub fn is_feature_single_mmap(&self) -> bool {
@@ -568,6 +1012,13 @@ impl Parameters {
         self.0.features & sys::IORING_FEAT_RSRC_TAGS != 0
     }
 
+    /// The same as [`is_feature_resource_tagging`](Self::is_feature_resource_tagging), under the
+    /// kernel's name for the flag.
+    #[inline]
+    pub fn is_feature_rsrc_tags(&self) -> bool {
+        self.is_feature_resource_tagging()
+    }
+
     /// Whether the kernel supports `IOSQE_CQE_SKIP_SUCCESS`.
     ///
     /// This feature allows skipping the generation of a CQE if a SQE executes normally. Available
@@ -576,6 +1027,13 @@ impl Parameters {
         self.0.features & sys::IORING_FEAT_CQE_SKIP != 0
     }
 
+    /// The same as [`is_feature_skip_cqe_on_success`](Self::is_feature_skip_cqe_on_success), under
+    /// the kernel's name for the flag.
+    #[inline]
+    pub fn is_feature_cqe_skip(&self) -> bool {
+        self.is_feature_skip_cqe_on_success()
+    }
+
     /// Whether the kernel supports deferred file assignment.
     ///
     /// If this flag is set, then io_uring supports sane assignment of files for SQEs that have
@@ -589,6 +1047,16 @@ impl Parameters {
         self.0.features & sys::IORING_FEAT_LINKED_FILE != 0
     }
 
+    /// Whether `io_uring_register` accepts the index of a ring registered with
+    /// [`Submitter::register_ring_fd`] in place of its file descriptor.
+    ///
+    /// Entering a ring through its registered index is possible since kernel 5.18; this flag
+    /// means the same is possible for registrations, with `IORING_REGISTER_USE_REGISTERED_RING`.
+    /// Available since kernel 6.3.
+    pub fn is_feature_reg_reg_ring(&self) -> bool {
+        self.0.features & sys::IORING_FEAT_REG_REG_RING != 0
+    }
+
     /// Whether the kernel supports `IORING_RECVSEND_BUNDLE`.
     ///
     /// This feature allows sending and recieving multiple buffers as a single bundle. Available
@@ -597,12 +1065,32 @@ impl Parameters {
         self.0.features & sys::IORING_FEAT_RECVSEND_BUNDLE != 0
     }
 
+    /// Whether the kernel supports a minimum batch wait time, see
+    /// [`Submitter::submit_and_wait_min`]. Available since kernel 6.12.
+    pub fn is_feature_min_timeout(&self) -> bool {
+        self.0.features & sys::IORING_FEAT_MIN_TIMEOUT != 0
+    }
+
+    /// The raw `IORING_FEAT_*` bits reported by the kernel, including any this crate has no
+    /// accessor for.
+    pub fn features(&self) -> u32 {
+        self.0.features
+    }
+
     /// The number of submission queue entries allocated.
+    ///
+    /// This is the size the kernel settled on, which may differ from the one requested: it is
+    /// rounded up to a power of two, and capped if [`Builder::setup_clamp`] is set. It always
+    /// matches the [capacity](squeue::SubmissionQueue::capacity) of the submission queue.
     pub fn sq_entries(&self) -> u32 {
         self.0.sq_entries
     }
 
     /// The number of completion queue entries allocated.
+    ///
+    /// Unless [`Builder::setup_cqsize`] is used, this is twice [`sq_entries`](Self::sq_entries).
+    /// It always matches the [capacity](cqueue::CompletionQueue::capacity) of the completion
+    /// queue.
     pub fn cq_entries(&self) -> u32 {
         self.0.cq_entries
     }
@@ -612,8 +1100,15 @@ impl std::fmt::Debug for Parameters {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Parameters")
             .field("is_setup_sqpoll", &self.is_setup_sqpoll())
+            .field("is_setup_sq_aff", &self.is_setup_sq_aff())
             .field("is_setup_iopoll", &self.is_setup_iopoll())
+            .field("is_setup_hybrid_iopoll", &self.is_setup_hybrid_iopoll())
+            .field("is_setup_sqe128", &self.is_setup_sqe128())
+            .field("is_setup_cqe32", &self.is_setup_cqe32())
             .field("is_setup_single_issuer", &self.is_setup_single_issuer())
+            .field("is_setup_coop_taskrun", &self.is_setup_coop_taskrun())
+            .field("is_setup_taskrun_flag", &self.is_setup_taskrun_flag())
+            .field("is_setup_defer_taskrun", &self.is_setup_defer_taskrun())
             .field("is_feature_single_mmap", &self.is_feature_single_mmap())
             .field("is_feature_nodrop", &self.is_feature_nodrop())
             .field("is_feature_submit_stable", &self.is_feature_submit_stable())
@@ -629,6 +1124,12 @@ impl std::fmt::Debug for Parameters {
     }
 }
 
+/// The ring fd, e.g. to target this ring with [`MsgRingData`](opcode::MsgRingData), to share its
+/// workers with [`Builder::setup_attach_wq`], or to poll it for completions from another event
+/// loop.
+///
+/// The fd remains owned by the `IoUring` and is closed when it is dropped; it must not be closed
+/// by the caller.
 impl<S: squeue::EntryMarker, C: cqueue::EntryMarker> AsRawFd for IoUring<S, C> {
     fn as_raw_fd(&self) -> RawFd {
         self.fd.as_raw_fd()
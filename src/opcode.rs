@@ -105,16 +105,26 @@ opcode! {
     ///
     /// This is useful for testing the performance of the io_uring implementation itself.
     #[derive(Debug)]
-    pub struct Nop { ;; }
+    pub struct Nop {
+        ;;
+        /// Complete with this result instead of 0, which is useful for testing how completions
+        /// with errors such as `-libc::EINVAL` are handled. Older kernels ignore it and complete
+        /// with 0.
+        result: i32 = 0
+    }
 
     pub const CODE = sys::IORING_OP_NOP;
 
     pub fn build(self) -> Entry {
-        let Nop {} = self;
+        let Nop { result } = self;
 
         let mut sqe = sqe_zeroed();
         sqe.opcode = Self::CODE;
         sqe.fd = -1;
+        if result != 0 {
+            sqe.__bindgen_anon_3.nop_flags = sys::IORING_NOP_INJECT_RESULT;
+            sqe.len = result as _;
+        }
         Entry(sqe)
     }
 }
@@ -269,6 +279,16 @@ opcode! {
     }
 }
 
+impl ReadFixed {
+    /// Read into `buf`, which must lie within the fixed buffer registered at `buf_index`. The
+    /// pointer and length are taken from `buf`, capped at `u32::MAX` bytes.
+    #[inline]
+    pub fn from_buf(fd: impl sealed::UseFixed, buf_index: u16, buf: &mut [u8]) -> Self {
+        let len = buf.len().min(u32::MAX as usize) as u32;
+        ReadFixed::new(fd, buf.as_mut_ptr(), len, buf_index)
+    }
+}
+
 opcode! {
     /// Write to a file from a fixed buffer that have been previously registered with
     /// [`Submitter::register_buffers`](crate::Submitter::register_buffers).
@@ -312,6 +332,16 @@ opcode! {
     }
 }
 
+impl WriteFixed {
+    /// Write from `buf`, which must lie within the fixed buffer registered at `buf_index`. The
+    /// pointer and length are taken from `buf`, capped at `u32::MAX` bytes.
+    #[inline]
+    pub fn from_buf(fd: impl sealed::UseFixed, buf_index: u16, buf: &[u8]) -> Self {
+        let len = buf.len().min(u32::MAX as usize) as u32;
+        WriteFixed::new(fd, buf.as_ptr(), len, buf_index)
+    }
+}
+
 opcode! {
     /// Poll the specified fd.
     ///
@@ -322,8 +352,9 @@ opcode! {
     /// repeatedly trigger when the requested event becomes true, and hence multiple CQEs can be
     /// generated from this single submission. The CQE flags field will have IORING_CQE_F_MORE set
     /// on completion if the application should expect further CQE entries from the original
-    /// request. If this flag isn't set on completion, then the poll request has been terminated
-    /// and no further events will be generated. This mode is available since 5.13.
+    /// request (see [`cqueue::more`](crate::cqueue::more)). If this flag isn't set on completion,
+    /// then the poll request has been terminated and no further events will be generated. This
+    /// mode is available since 5.13.
     #[derive(Debug)]
     pub struct PollAdd {
         /// The bits that may be set in `flags` are defined in `<poll.h>`,
@@ -422,6 +453,8 @@ opcode! {
     ///
     /// fd must be set to the socket file descriptor, addr must contains a pointer to the msghdr
     /// structure, and flags holds the flags associated with the system call.
+    /// [`SendMsg::from_msghdr`] takes a [`types::MsgHdr`] instead, which keeps its buffers
+    /// borrowed.
     #[derive(Debug)]
     pub struct SendMsg {
         fd: { impl sealed::UseFixed },
@@ -447,6 +480,15 @@ opcode! {
     }
 }
 
+impl SendMsg {
+    /// Send the message described by `msg`. The header is read when the request is submitted,
+    /// and its buffers when it executes, so `msg` must outlive the request.
+    #[inline]
+    pub fn from_msghdr(fd: impl sealed::UseFixed, msg: &types::MsgHdr<'_>) -> Self {
+        SendMsg::new(fd, msg.as_ptr())
+    }
+}
+
 opcode! {
     /// Receive a message on a socket, equivalent to `recvmsg(2)`.
     ///
@@ -478,6 +520,16 @@ opcode! {
     }
 }
 
+impl RecvMsg {
+    /// Receive a message into the buffers of `msg`, which also gets the source address and
+    /// ancillary data lengths. The kernel writes to `msg` until the request completes, so it must
+    /// outlive the request.
+    #[inline]
+    pub fn from_msghdr(fd: impl sealed::UseFixed, msg: &mut types::MsgHdr<'_>) -> Self {
+        RecvMsg::new(fd, msg.as_mut_ptr())
+    }
+}
+
 opcode! {
     /// Receive multiple messages on a socket, equivalent to `recvmsg(2)`.
     ///
@@ -542,7 +594,9 @@ opcode! {
     pub struct Timeout {
         timespec: { *const types::Timespec },
         ;;
-        /// `count` may contain a completion event count.
+        /// `count` may contain a completion event count. With
+        /// [`types::TimeoutFlags::MULTISHOT`], it is instead the number of expiries, 0 meaning
+        /// unlimited.
         count: u32 = 0,
 
         /// `flags` may contain [types::TimeoutFlags::ABS] for an absolute timeout value, or 0 for a relative timeout.
@@ -643,6 +697,8 @@ opcode! {
 
 opcode! {
     /// Set a socket option.
+    ///
+    /// Available since 6.7.
     pub struct SetSockOpt {
         fd: { impl sealed::UseFixed },
         level: { u32 },
@@ -671,24 +727,67 @@ opcode! {
     }
 }
 
+opcode! {
+    /// Get a socket option.
+    ///
+    /// On success the result is the length of the option value written to `optval`.
+    /// The kernel only supports `SOL_SOCKET` as the `level`.
+    ///
+    /// Available since 6.7.
+    pub struct GetSockOpt {
+        fd: { impl sealed::UseFixed },
+        level: { u32 },
+        optname: { u32 },
+        optval: { *mut libc::c_void },
+        optlen: { u32 },
+        ;;
+        flags: u32 = 0
+    }
+
+    pub const CODE = sys::IORING_OP_URING_CMD;
+
+    pub fn build(self) -> Entry {
+        let GetSockOpt { fd, level, optname, optval, optlen, flags } = self;
+        let mut sqe = sqe_zeroed();
+        sqe.opcode = Self::CODE;
+        assign_fd!(sqe.fd = fd);
+        sqe.__bindgen_anon_1.__bindgen_anon_1.cmd_op = sys::SOCKET_URING_OP_GETSOCKOPT;
+
+        sqe.__bindgen_anon_2.__bindgen_anon_1.level = level;
+        sqe.__bindgen_anon_2.__bindgen_anon_1.optname = optname;
+        sqe.__bindgen_anon_3.uring_cmd_flags = flags;
+        sqe.__bindgen_anon_5.optlen = optlen;
+        unsafe { *sqe.__bindgen_anon_6.optval.as_mut() = optval as u64 };
+        Entry(sqe)
+    }
+}
+
 opcode! {
     /// Attempt to cancel an already issued request.
+    ///
+    /// This only matches requests by `user_data`, and by default cancels just the first request
+    /// found; to cancel by file descriptor or other criteria, use [`AsyncCancel2`].
     pub struct AsyncCancel {
         user_data: { u64 }
         ;;
-
-        // TODO flags
+        /// Cancel every request matching `user_data` instead of only the first
+        /// (`IORING_ASYNC_CANCEL_ALL`). The result is then the number of requests canceled.
+        /// Available since 5.19.
+        all: bool = false
     }
 
     pub const CODE = sys::IORING_OP_ASYNC_CANCEL;
 
     pub fn build(self) -> Entry {
-        let AsyncCancel { user_data } = self;
+        let AsyncCancel { user_data, all } = self;
 
         let mut sqe = sqe_zeroed();
         sqe.opcode = Self::CODE;
         sqe.fd = -1;
         sqe.__bindgen_anon_2.addr = user_data;
+        if all {
+            sqe.__bindgen_anon_3.cancel_flags = sys::IORING_ASYNC_CANCEL_ALL;
+        }
         Entry(sqe)
     }
 }
@@ -697,9 +796,16 @@ opcode! {
     /// This request must be linked with another request through
     /// [`Flags::IO_LINK`](crate::squeue::Flags::IO_LINK) which is described below.
     /// Unlike [`Timeout`], [`LinkTimeout`] acts on the linked request, not the completion queue.
+    ///
+    /// If the timeout expires, the linked request is cancelled and completes with `-ECANCELED`,
+    /// while the timeout itself completes with `-ETIME`.
     pub struct LinkTimeout {
         timespec: { *const types::Timespec },
         ;;
+        /// As for [`Timeout`], `flags` may contain [`types::TimeoutFlags::ABS`] to make the timespec
+        /// an absolute deadline, which several linked requests can share, and
+        /// [`types::TimeoutFlags::BOOTTIME`] or [`types::TimeoutFlags::REALTIME`] to choose the
+        /// clock.
         flags: types::TimeoutFlags = types::TimeoutFlags::empty()
     }
 
@@ -741,10 +847,22 @@ opcode! {
     }
 }
 
+impl Connect {
+    /// Connect to `addr`. The address is read when the request is submitted, so `addr` must
+    /// outlive the submission, like any other buffer passed to an operation.
+    #[inline]
+    pub fn from_addr(fd: impl sealed::UseFixed, addr: &types::SockAddr) -> Self {
+        Connect::new(fd, addr.as_ptr(), addr.addr_len())
+    }
+}
+
 // === 5.6 ===
 
 opcode! {
     /// Preallocate or deallocate space to a file, equivalent to `fallocate(2)`.
+    ///
+    /// `mode` selects what happens to the range of `len` bytes at `offset`, see `fallocate(2)`
+    /// and [`Fallocate::fallocate_flags`].
     pub struct Fallocate {
         fd: { impl sealed::UseFixed },
         len: { u64 },
@@ -768,6 +886,15 @@ opcode! {
     }
 }
 
+impl Fallocate {
+    /// Set the mode from [`FallocateFlags`](types::FallocateFlags), replacing any set with
+    /// [`mode`](Self::mode).
+    #[inline]
+    pub const fn fallocate_flags(self, flags: types::FallocateFlags) -> Self {
+        self.mode(flags.bits() as i32)
+    }
+}
+
 opcode! {
     /// Open a file, equivalent to `openat(2)`.
     pub struct OpenAt {
@@ -979,6 +1106,8 @@ opcode! {
 
 opcode! {
     /// Predeclare an access pattern for file data, equivalent to `posix_fadvise(2)`.
+    ///
+    /// A `len` that does not fit in 32 bits requires 6.12.
     pub struct Fadvise {
         fd: { impl sealed::UseFixed },
         len: { libc::off_t },
@@ -995,15 +1124,29 @@ opcode! {
         let mut sqe = sqe_zeroed();
         sqe.opcode = Self::CODE;
         assign_fd!(sqe.fd = fd);
-        sqe.len = len as _;
+        // The kernel reads a 64-bit length from `addr` when `len` is zero.
+        match u32::try_from(len) {
+            Ok(len) => sqe.len = len,
+            Err(_) => sqe.__bindgen_anon_2.addr = len as _,
+        }
         sqe.__bindgen_anon_1.off = offset;
         sqe.__bindgen_anon_3.fadvise_advice = advice as _;
         Entry(sqe)
     }
 }
 
+impl Fadvise {
+    /// Give typed [`Advice`](types::Advice) rather than a raw `POSIX_FADV_*` value.
+    #[inline]
+    pub fn from_advice(fd: impl sealed::UseFixed, len: libc::off_t, advice: types::Advice) -> Self {
+        Fadvise::new(fd, len, advice as i32)
+    }
+}
+
 opcode! {
     /// Give advice about use of memory, equivalent to `madvise(2)`.
+    ///
+    /// A `len` that does not fit in 32 bits requires 6.12.
     pub struct Madvise {
         addr: { *const libc::c_void },
         len: { libc::off_t },
@@ -1020,14 +1163,35 @@ opcode! {
         sqe.opcode = Self::CODE;
         sqe.fd = -1;
         sqe.__bindgen_anon_2.addr = addr as _;
-        sqe.len = len as _;
+        // The kernel reads a 64-bit length from `off` when `len` is zero.
+        match u32::try_from(len) {
+            Ok(len) => sqe.len = len,
+            Err(_) => sqe.__bindgen_anon_1.off = len as _,
+        }
         sqe.__bindgen_anon_3.fadvise_advice = advice as _;
         Entry(sqe)
     }
 }
 
+impl Madvise {
+    /// Give typed [`MemoryAdvice`](types::MemoryAdvice) rather than a raw `MADV_*` value.
+    #[inline]
+    pub fn from_advice(
+        addr: *const libc::c_void,
+        len: libc::off_t,
+        advice: types::MemoryAdvice,
+    ) -> Self {
+        Madvise::new(addr, len, advice as i32)
+    }
+}
+
 opcode! {
     /// Send a message on a socket, equivalent to `send(2)`.
+    ///
+    /// To send from a buffer registered with
+    /// [`Submitter::register_buffers`](crate::Submitter::register_buffers), use
+    /// [`SendZc::buf_index`] instead, as the kernel only supports fixed buffers for zero-copy
+    /// sends.
     pub struct Send {
         fd: { impl sealed::UseFixed },
         buf: { *const u8 },
@@ -1041,16 +1205,23 @@ opcode! {
         /// See also `man 3 io_uring_prep_send_set_addr`.
         dest_addr: *const libc::sockaddr = core::ptr::null(),
         dest_addr_len: libc::socklen_t = 0,
+        /// Skip the initial attempt and wait for the socket to become ready straight away
+        /// (`IORING_RECVSEND_POLL_FIRST`). This saves a wasted attempt when it is unlikely to
+        /// be ready yet. Available since 5.19.
+        poll_first: bool = false,
     }
 
     pub const CODE = sys::IORING_OP_SEND;
 
     pub fn build(self) -> Entry {
-        let Send { fd, buf, len, flags, dest_addr, dest_addr_len } = self;
+        let Send { fd, buf, len, flags, dest_addr, dest_addr_len, poll_first } = self;
 
         let mut sqe = sqe_zeroed();
         sqe.opcode = Self::CODE;
         assign_fd!(sqe.fd = fd);
+        if poll_first {
+            sqe.ioprio |= sys::IORING_RECVSEND_POLL_FIRST as u16;
+        }
         sqe.__bindgen_anon_2.addr = buf as _;
         sqe.__bindgen_anon_1.addr2 = dest_addr as _;
         sqe.__bindgen_anon_5.__bindgen_anon_1.addr_len = dest_addr_len as _;
@@ -1068,17 +1239,24 @@ opcode! {
         len: { u32 },
         ;;
         flags: i32 = 0,
-        buf_group: u16 = 0
+        buf_group: u16 = 0,
+        /// Skip the initial attempt and wait for the socket to become ready straight away
+        /// (`IORING_RECVSEND_POLL_FIRST`). This saves a wasted attempt when it is unlikely to
+        /// be ready yet. Available since 5.19.
+        poll_first: bool = false
     }
 
     pub const CODE = sys::IORING_OP_RECV;
 
     pub fn build(self) -> Entry {
-        let Recv { fd, buf, len, flags, buf_group } = self;
+        let Recv { fd, buf, len, flags, buf_group, poll_first } = self;
 
         let mut sqe = sqe_zeroed();
         sqe.opcode = Self::CODE;
         assign_fd!(sqe.fd = fd);
+        if poll_first {
+            sqe.ioprio |= sys::IORING_RECVSEND_POLL_FIRST as u16;
+        }
         sqe.__bindgen_anon_2.addr = buf as _;
         sqe.len = len;
         sqe.__bindgen_anon_3.msg_flags = flags as _;
@@ -1128,6 +1306,8 @@ opcode! {
 
 opcode! {
     /// Open a file, equivalent to `openat2(2)`.
+    ///
+    /// `how` must stay valid until the request is submitted.
     pub struct OpenAt2 {
         dirfd: { impl sealed::UseFd },
         pathname: { *const libc::c_char },
@@ -1156,6 +1336,9 @@ opcode! {
 
 opcode! {
     /// Modify an epoll file descriptor, equivalent to `epoll_ctl(2)`.
+    ///
+    /// `op` is one of `EPOLL_CTL_ADD`, `EPOLL_CTL_MOD` or `EPOLL_CTL_DEL`, and `ev` points to
+    /// a [`libc::epoll_event`] that must stay valid until the request completes.
     pub struct EpollCtl {
         epfd: { impl sealed::UseFixed },
         fd: { impl sealed::UseFd },
@@ -1184,8 +1367,8 @@ opcode! {
 opcode! {
     /// Splice data to/from a pipe, equivalent to `splice(2)`.
     ///
-    /// if `fd_in` refers to a pipe, `off_in` must be `-1`;
-    /// The description of `off_in` also applied to `off_out`.
+    /// An offset of -1 uses the current file position, and must be used for a pipe. See
+    /// [`Splice::from_offsets`] to pass `None` instead.
     pub struct Splice {
         fd_in: { impl sealed::UseFixed },
         off_in: { i64 },
@@ -1222,6 +1405,34 @@ opcode! {
     }
 }
 
+impl Splice {
+    /// Splice with optional offsets, where `None` uses the current file position instead of
+    /// the `-1` sentinel taken by [`Splice::new`].
+    #[inline]
+    pub fn from_offsets(
+        fd_in: impl sealed::UseFixed,
+        off_in: Option<i64>,
+        fd_out: impl sealed::UseFixed,
+        off_out: Option<i64>,
+        len: u32,
+    ) -> Self {
+        Splice::new(
+            fd_in,
+            off_in.unwrap_or(-1),
+            fd_out,
+            off_out.unwrap_or(-1),
+            len,
+        )
+    }
+
+    /// Set the flags from [`SpliceFlags`](types::SpliceFlags), replacing any set with
+    /// [`flags`](Self::flags).
+    #[inline]
+    pub const fn splice_flags(self, flags: types::SpliceFlags) -> Self {
+        self.flags(flags.bits())
+    }
+}
+
 opcode! {
     /// Register `nbufs` buffers that each have the length `len` with ids starting from `bid` in the
     /// group `bgid` that can be used for any request. See
@@ -1277,6 +1488,9 @@ opcode! {
 
 opcode! {
     /// Duplicate pipe content, equivalent to `tee(2)`.
+    ///
+    /// Up to `len` bytes of the pipe `fd_in` are copied into the pipe `fd_out`, without consuming
+    /// them from `fd_in`.
     pub struct Tee {
         fd_in: { impl sealed::UseFixed },
         fd_out: { impl sealed::UseFixed },
@@ -1310,10 +1524,20 @@ opcode! {
     }
 }
 
+impl Tee {
+    /// Set the flags from [`SpliceFlags`](types::SpliceFlags), replacing any set with
+    /// [`flags`](Self::flags).
+    #[inline]
+    pub const fn splice_flags(self, flags: types::SpliceFlags) -> Self {
+        self.flags(flags.bits())
+    }
+}
+
 // === 5.11 ===
 
 opcode! {
     /// Shut down all or part of a full duplex connection on a socket, equivalent to `shutdown(2)`.
+    /// `how` is one of `libc::SHUT_RD`, `libc::SHUT_WR` or `libc::SHUT_RDWR`.
     /// Available since kernel 5.11.
     pub struct Shutdown {
         fd: { impl sealed::UseFixed },
@@ -1335,8 +1559,12 @@ opcode! {
 }
 
 opcode! {
-    // Change the name or location of a file, equivalent to `renameat2(2)`.
-    // Available since kernel 5.11.
+    /// Change the name or location of a file, equivalent to `renameat2(2)`.
+    ///
+    /// `flags` may contain `libc::RENAME_NOREPLACE`, `libc::RENAME_EXCHANGE` or
+    /// `libc::RENAME_WHITEOUT`.
+    ///
+    /// Available since kernel 5.11.
     pub struct RenameAt {
         olddirfd: { impl sealed::UseFd },
         oldpath: { *const libc::c_char },
@@ -1367,8 +1595,11 @@ opcode! {
 }
 
 opcode! {
-    // Delete a name and possible the file it refers to, equivalent to `unlinkat(2)`.
-    // Available since kernel 5.11.
+    /// Delete a name and possibly the file it refers to, equivalent to `unlinkat(2)`.
+    ///
+    /// Pass `libc::AT_REMOVEDIR` in `flags` to remove a directory instead.
+    ///
+    /// Available since kernel 5.11.
     pub struct UnlinkAt {
         dirfd: { impl sealed::UseFd },
         pathname: { *const libc::c_char },
@@ -1469,6 +1700,11 @@ opcode! {
 
 opcode! {
     /// Send a message (with data) to a target ring.
+    ///
+    /// The target ring gets a completion with the given `result` and `user_data`, and with
+    /// `user_flags` as its flags if they are set (which requires 6.3).
+    ///
+    /// Available since 5.18.
     pub struct MsgRingData {
         ring_fd: { impl sealed::UseFd },
         result: { i32 },
@@ -1525,6 +1761,10 @@ opcode! {
 
 opcode! {
     /// A file/device-specific 16-byte command, akin (but not equivalent) to `ioctl(2)`.
+    ///
+    /// The meaning of `cmd_op` and `cmd` is defined by the file, e.g. NVMe passthrough.
+    ///
+    /// Available since 5.19.
     pub struct UringCmd16 {
         fd: { impl sealed::UseFixed },
         cmd_op: { u32 },
@@ -1558,6 +1798,11 @@ opcode! {
 
 opcode! {
     /// A file/device-specific 80-byte command, akin (but not equivalent) to `ioctl(2)`.
+    ///
+    /// The command spills into the second half of the entry, so this can only be pushed to a
+    /// ring set up with 128-byte submission queue entries.
+    ///
+    /// Available since 5.19.
     pub struct UringCmd80 {
         fd: { impl sealed::UseFixed },
         cmd_op: { u32 },
@@ -1601,6 +1846,10 @@ opcode! {
     /// have registered a file table, and the target slot should fit into
     /// it.
     ///
+    /// With [`DestinationSlot::auto_target`](types::DestinationSlot::auto_target), the kernel
+    /// picks a free slot and the result is its index, ready to be used as a
+    /// [`Fixed`](types::Fixed) file. With an explicit slot, the result is 0.
+    ///
     /// Available since 5.19.
     pub struct Socket {
         domain: { i32 },
@@ -1632,7 +1881,9 @@ opcode! {
 opcode! {
     /// Accept multiple new connections on a socket.
     ///
-    /// Set the `allocate_file_index` property if fixed file table entries should be used.
+    /// Each accepted connection posts a CQE with [`cqueue::more`](crate::cqueue::more) set, whose
+    /// result is the new file descriptor. Set the `allocate_file_index` property if fixed file
+    /// table entries should be used, in which case the result is the index of the allocated slot.
     ///
     /// Available since 5.19.
     pub struct AcceptMulti {
@@ -1661,10 +1912,137 @@ opcode! {
     }
 }
 
+opcode! {
+    /// Set an extended attribute of a file, equivalent to `setxattr(2)`.
+    ///
+    /// `flags` may be `libc::XATTR_CREATE` or `libc::XATTR_REPLACE`.
+    ///
+    /// Available since 5.19.
+    pub struct SetXattr {
+        path: { *const libc::c_char },
+        name: { *const libc::c_char },
+        value: { *const libc::c_void },
+        len: { u32 },
+        ;;
+        flags: i32 = 0
+    }
+
+    pub const CODE = sys::IORING_OP_SETXATTR;
+
+    pub fn build(self) -> Entry {
+        let SetXattr { path, name, value, len, flags } = self;
+
+        let mut sqe = sqe_zeroed();
+        sqe.opcode = Self::CODE;
+        sqe.__bindgen_anon_2.addr = name as _;
+        sqe.__bindgen_anon_1.addr2 = value as _;
+        sqe.len = len;
+        sqe.__bindgen_anon_3.xattr_flags = flags as _;
+        unsafe { sqe.__bindgen_anon_6.__bindgen_anon_1.as_mut().addr3 = path as _ };
+        Entry(sqe)
+    }
+}
+
+opcode! {
+    /// Get an extended attribute of a file, equivalent to `getxattr(2)`.
+    ///
+    /// The result is the size of the attribute value. If `len` is zero, the size is returned
+    /// without reading the value.
+    ///
+    /// Available since 5.19.
+    pub struct GetXattr {
+        path: { *const libc::c_char },
+        name: { *const libc::c_char },
+        value: { *mut libc::c_void },
+        len: { u32 },
+        ;;
+    }
+
+    pub const CODE = sys::IORING_OP_GETXATTR;
+
+    pub fn build(self) -> Entry {
+        let GetXattr { path, name, value, len } = self;
+
+        let mut sqe = sqe_zeroed();
+        sqe.opcode = Self::CODE;
+        sqe.__bindgen_anon_2.addr = name as _;
+        sqe.__bindgen_anon_1.addr2 = value as _;
+        sqe.len = len;
+        unsafe { sqe.__bindgen_anon_6.__bindgen_anon_1.as_mut().addr3 = path as _ };
+        Entry(sqe)
+    }
+}
+
+opcode! {
+    /// Set an extended attribute of an open file, equivalent to `fsetxattr(2)`.
+    ///
+    /// `flags` may be `libc::XATTR_CREATE` or `libc::XATTR_REPLACE`. Fixed files are not
+    /// supported.
+    ///
+    /// Available since 5.19.
+    pub struct FSetXattr {
+        fd: { impl sealed::UseFd },
+        name: { *const libc::c_char },
+        value: { *const libc::c_void },
+        len: { u32 },
+        ;;
+        flags: i32 = 0
+    }
+
+    pub const CODE = sys::IORING_OP_FSETXATTR;
+
+    pub fn build(self) -> Entry {
+        let FSetXattr { fd, name, value, len, flags } = self;
+
+        let mut sqe = sqe_zeroed();
+        sqe.opcode = Self::CODE;
+        sqe.fd = fd;
+        sqe.__bindgen_anon_2.addr = name as _;
+        sqe.__bindgen_anon_1.addr2 = value as _;
+        sqe.len = len;
+        sqe.__bindgen_anon_3.xattr_flags = flags as _;
+        Entry(sqe)
+    }
+}
+
+opcode! {
+    /// Get an extended attribute of an open file, equivalent to `fgetxattr(2)`.
+    ///
+    /// The result is the size of the attribute value. If `len` is zero, the size is returned
+    /// without reading the value. Fixed files are not supported.
+    ///
+    /// Available since 5.19.
+    pub struct FGetXattr {
+        fd: { impl sealed::UseFd },
+        name: { *const libc::c_char },
+        value: { *mut libc::c_void },
+        len: { u32 },
+        ;;
+    }
+
+    pub const CODE = sys::IORING_OP_FGETXATTR;
+
+    pub fn build(self) -> Entry {
+        let FGetXattr { fd, name, value, len } = self;
+
+        let mut sqe = sqe_zeroed();
+        sqe.opcode = Self::CODE;
+        sqe.fd = fd;
+        sqe.__bindgen_anon_2.addr = name as _;
+        sqe.__bindgen_anon_1.addr2 = value as _;
+        sqe.len = len;
+        Entry(sqe)
+    }
+}
+
 // === 6.0 ===
 
 opcode! {
     /// Send a message (with fixed FD) to a target ring.
+    ///
+    /// The fixed file `fixed_slot_src` of the source ring is installed into the fixed file table
+    /// of the target ring, which gets a completion with `user_data`. `opcode_flags` may contain
+    /// [`types::MSG_RING_CQE_SKIP`].
     pub struct MsgRingSendFd {
         ring_fd: { impl sealed::UseFd },
         fixed_slot_src: { types::Fixed },
@@ -1718,17 +2096,26 @@ opcode! {
         /// The buf and len arguments must fall within a region specified by buf_index in the
         /// previously registered buffer. The buffer need not be aligned with the start of the
         /// registered buffer.
+        ///
+        /// Setting it also sets `IORING_RECVSEND_FIXED_BUF`, which the kernel only accepts
+        /// together with a `buf_index`, so that flag has no builder of its own.
         buf_index: Option<u16> = None,
         dest_addr: *const libc::sockaddr = core::ptr::null(),
         dest_addr_len: libc::socklen_t = 0,
         flags: i32 = 0,
         zc_flags: u16 = 0,
+        /// Skip the initial attempt and wait for the socket to become ready straight away
+        /// (`IORING_RECVSEND_POLL_FIRST`). This saves a wasted attempt when it is unlikely to
+        /// be ready yet.
+        poll_first: bool = false,
     }
 
     pub const CODE = sys::IORING_OP_SEND_ZC;
 
     pub fn build(self) -> Entry {
-        let SendZc { fd, buf, len, buf_index, dest_addr, dest_addr_len, flags, zc_flags } = self;
+        let SendZc {
+            fd, buf, len, buf_index, dest_addr, dest_addr_len, flags, zc_flags, poll_first
+        } = self;
 
         let mut sqe = sqe_zeroed();
         sqe.opcode = Self::CODE;
@@ -1737,6 +2124,9 @@ opcode! {
         sqe.len = len;
         sqe.__bindgen_anon_3.msg_flags = flags as _;
         sqe.ioprio = zc_flags;
+        if poll_first {
+            sqe.ioprio |= sys::IORING_RECVSEND_POLL_FIRST as u16;
+        }
         if let Some(buf_index) = buf_index {
             sqe.__bindgen_anon_4.buf_index = buf_index;
             sqe.ioprio |= sys::IORING_RECVSEND_FIXED_BUF as u16;
@@ -1750,10 +2140,15 @@ opcode! {
 // === 6.1 ===
 
 opcode! {
-    /// Send a zerocopy message on a socket, equivalent to `send(2)`.
+    /// Send a zerocopy message on a socket, equivalent to `sendmsg(2)`.
     ///
     /// fd must be set to the socket file descriptor, addr must contains a pointer to the msghdr
     /// structure, and flags holds the flags associated with the system call.
+    ///
+    /// Like [`SendZc`], this usually posts two completion queue entries: the first carries the
+    /// result of the send and has [more](crate::cqueue::more) set, and the second is a
+    /// [notification](crate::cqueue::notif) that the buffers are no longer used by the kernel and
+    /// may be reused. Zero-copy sends do not support bundles; see [`SendBundle`] for that.
     #[derive(Debug)]
     pub struct SendMsgZc {
         fd: { impl sealed::UseFixed },
@@ -1781,6 +2176,42 @@ opcode! {
 
 // === 6.7 ===
 
+opcode! {
+    /// Wait for a child process to change state, equivalent to `waitid(2)`.
+    ///
+    /// `idtype` and `id` select the children to wait for (e.g. `libc::P_PID` and a process id),
+    /// and `options` holds the `W*` flags of `waitid(2)`. On completion, the `siginfo_t` pointed
+    /// to by `infop` is filled in with the state of the child, as with the system call. Unlike
+    /// `wait4(2)`, resource usage of the child is not reported. `flags` are currently unused and
+    /// hence `0` must be passed.
+    ///
+    /// Available since 6.7.
+    #[derive(Debug)]
+    pub struct WaitId {
+        idtype: { libc::idtype_t },
+        id: { libc::id_t },
+        infop: { *mut libc::siginfo_t },
+        options: { i32 },
+        ;;
+        flags: u32 = 0
+    }
+
+    pub const CODE = sys::IORING_OP_WAITID;
+
+    pub fn build(self) -> Entry {
+        let WaitId { idtype, id, infop, options, flags } = self;
+
+        let mut sqe = sqe_zeroed();
+        sqe.opcode = Self::CODE;
+        sqe.fd = id as _;
+        sqe.len = idtype as _;
+        sqe.__bindgen_anon_3.waitid_flags = flags;
+        sqe.__bindgen_anon_5.file_index = options as _;
+        sqe.__bindgen_anon_1.addr2 = infop as _;
+        Entry(sqe)
+    }
+}
+
 opcode! {
     /// Wait on a futex, like but not equivalant to `futex(2)`'s `FUTEX_WAIT_BITSET`.
     ///
@@ -1884,7 +2315,13 @@ opcode! {
     /// Install a fixed file descriptor
     ///
     /// Turns a direct descriptor into a regular file descriptor that can be later used by regular
-    /// system calls that take a normal raw file descriptor
+    /// system calls that take a normal raw file descriptor. The new file descriptor is returned as
+    /// the result of the completion.
+    ///
+    /// The installed file descriptor is marked close-on-exec unless `file_flags` contains
+    /// [`types::FIXED_FD_NO_CLOEXEC`].
+    ///
+    /// Available since 6.8.
     #[derive(Debug)]
     pub struct FixedFdInstall {
         fd: { types::Fixed },
@@ -1910,6 +2347,8 @@ opcode! {
 
 opcode! {
     /// Perform file truncation, equivalent to `ftruncate(2)`.
+    ///
+    /// Available since 6.9.
     #[derive(Debug)]
     pub struct Ftruncate {
         fd: { impl sealed::UseFixed },
@@ -1934,6 +2373,11 @@ opcode! {
 
 opcode! {
     /// Send a bundle of messages on a socket in a single request.
+    ///
+    /// The data is taken from as many buffers of the provided buffer group `buf_group` as are
+    /// available, up to `len` bytes if it is set.
+    ///
+    /// Available since 6.10.
     pub struct SendBundle {
         fd: { impl sealed::UseFixed },
         buf_group: { u16 },
@@ -2033,3 +2477,1102 @@ opcode! {
         Entry(sqe)
     }
 }
+
+// === 6.11 ===
+
+opcode! {
+    /// Bind a socket to an address, equivalent to `bind(2)`.
+    ///
+    /// Available since 6.11.
+    pub struct Bind {
+        fd: { impl sealed::UseFixed },
+        addr: { *const libc::sockaddr },
+        addrlen: { libc::socklen_t }
+        ;;
+    }
+
+    pub const CODE = sys::IORING_OP_BIND;
+
+    pub fn build(self) -> Entry {
+        let Bind { fd, addr, addrlen } = self;
+
+        let mut sqe = sqe_zeroed();
+        sqe.opcode = Self::CODE;
+        assign_fd!(sqe.fd = fd);
+        sqe.__bindgen_anon_2.addr = addr as _;
+        sqe.__bindgen_anon_1.addr2 = addrlen as _;
+        Entry(sqe)
+    }
+}
+
+opcode! {
+    /// Listen for connections on a socket, equivalent to `listen(2)`.
+    ///
+    /// Available since 6.11.
+    pub struct Listen {
+        fd: { impl sealed::UseFixed },
+        backlog: { i32 },
+        ;;
+    }
+
+    pub const CODE = sys::IORING_OP_LISTEN;
+
+    pub fn build(self) -> Entry {
+        let Listen { fd, backlog } = self;
+
+        let mut sqe = sqe_zeroed();
+        sqe.opcode = Self::CODE;
+        assign_fd!(sqe.fd = fd);
+        sqe.len = backlog as _;
+        Entry(sqe)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_openat2_build() {
+        let how = types::OpenHow::new()
+            .flags(libc::O_RDONLY as _)
+            .resolve(types::RESOLVE_BENEATH | types::RESOLVE_NO_SYMLINKS);
+        let path = b"file\0";
+
+        let sqe = OpenAt2::new(types::Fd(5), path.as_ptr().cast(), &how)
+            .build()
+            .0;
+        assert_eq!(sqe.opcode, sys::IORING_OP_OPENAT2 as u8);
+        assert_eq!(sqe.fd, 5);
+        assert_eq!(unsafe { sqe.__bindgen_anon_2.addr }, path.as_ptr() as u64);
+        assert_eq!(
+            unsafe { sqe.__bindgen_anon_1.off },
+            &how as *const types::OpenHow as u64
+        );
+        assert_eq!(sqe.len as usize, mem::size_of::<sys::open_how>());
+        assert_eq!(sqe.len, 24);
+        assert_eq!(unsafe { sqe.__bindgen_anon_5.file_index }, 0);
+    }
+
+    #[test]
+    fn test_advise_build() {
+        let sqe = Fadvise::from_advice(types::Fd(5), 4096, types::Advice::DontNeed)
+            .offset(8192)
+            .build()
+            .0;
+        assert_eq!(sqe.opcode, sys::IORING_OP_FADVISE as u8);
+        assert_eq!(sqe.fd, 5);
+        assert_eq!(sqe.len, 4096);
+        assert_eq!(unsafe { sqe.__bindgen_anon_2.addr }, 0);
+        assert_eq!(unsafe { sqe.__bindgen_anon_1.off }, 8192);
+        assert_eq!(
+            unsafe { sqe.__bindgen_anon_3.fadvise_advice },
+            libc::POSIX_FADV_DONTNEED as u32
+        );
+
+        let sqe = Fadvise::new(types::Fd(5), 1 << 33, libc::POSIX_FADV_WILLNEED)
+            .build()
+            .0;
+        assert_eq!(sqe.len, 0);
+        assert_eq!(unsafe { sqe.__bindgen_anon_2.addr }, 1 << 33);
+        assert_eq!(
+            unsafe { sqe.__bindgen_anon_3.fadvise_advice },
+            libc::POSIX_FADV_WILLNEED as u32
+        );
+
+        let addr = 0x10000 as *const libc::c_void;
+        let sqe = Madvise::from_advice(addr, 4096, types::MemoryAdvice::Sequential)
+            .build()
+            .0;
+        assert_eq!(sqe.opcode, sys::IORING_OP_MADVISE as u8);
+        assert_eq!(sqe.fd, -1);
+        assert_eq!(unsafe { sqe.__bindgen_anon_2.addr }, 0x10000);
+        assert_eq!(sqe.len, 4096);
+        assert_eq!(unsafe { sqe.__bindgen_anon_1.off }, 0);
+        assert_eq!(
+            unsafe { sqe.__bindgen_anon_3.fadvise_advice },
+            libc::MADV_SEQUENTIAL as u32
+        );
+
+        let sqe = Madvise::new(addr, 1 << 33, libc::MADV_DONTNEED).build().0;
+        assert_eq!(sqe.len, 0);
+        assert_eq!(unsafe { sqe.__bindgen_anon_1.off }, 1 << 33);
+    }
+
+    #[test]
+    fn test_tee_build() {
+        let sqe = Tee::new(types::Fd(3), types::Fd(4), 256)
+            .splice_flags(types::SpliceFlags::NONBLOCK)
+            .build()
+            .0;
+        assert_eq!(sqe.opcode, sys::IORING_OP_TEE as u8);
+        assert_eq!(sqe.fd, 4);
+        assert_eq!(sqe.flags, 0);
+        assert_eq!(sqe.len, 256);
+        assert_eq!(unsafe { sqe.__bindgen_anon_5.splice_fd_in }, 3);
+        assert_eq!(
+            unsafe { sqe.__bindgen_anon_3.splice_flags },
+            libc::SPLICE_F_NONBLOCK
+        );
+
+        let sqe = Tee::new(types::Fixed(1), types::Fixed(2), 256)
+            .flags(libc::SPLICE_F_MORE)
+            .build()
+            .0;
+        assert_eq!(sqe.fd, 2);
+        assert_eq!(sqe.flags, crate::squeue::Flags::FIXED_FILE.bits());
+        assert_eq!(unsafe { sqe.__bindgen_anon_5.splice_fd_in }, 1);
+        assert_eq!(
+            unsafe { sqe.__bindgen_anon_3.splice_flags },
+            libc::SPLICE_F_MORE | sys::SPLICE_F_FD_IN_FIXED
+        );
+    }
+
+    #[test]
+    fn test_splice_build() {
+        let sqe = Splice::new(types::Fd(3), 4096, types::Fd(4), -1, 512)
+            .build()
+            .0;
+        assert_eq!(sqe.opcode, sys::IORING_OP_SPLICE as u8);
+        assert_eq!(sqe.fd, 4);
+        assert_eq!(sqe.len, 512);
+        assert_eq!(unsafe { sqe.__bindgen_anon_5.splice_fd_in }, 3);
+        assert_eq!(unsafe { sqe.__bindgen_anon_2.splice_off_in }, 4096);
+        assert_eq!(unsafe { sqe.__bindgen_anon_1.off }, u64::MAX);
+        assert_eq!(unsafe { sqe.__bindgen_anon_3.splice_flags }, 0);
+
+        for (flags, bits) in [
+            (types::SpliceFlags::MOVE, libc::SPLICE_F_MOVE),
+            (types::SpliceFlags::NONBLOCK, libc::SPLICE_F_NONBLOCK),
+            (types::SpliceFlags::MORE, libc::SPLICE_F_MORE),
+        ] {
+            let sqe = Splice::from_offsets(types::Fixed(3), None, types::Fd(4), Some(0), 512)
+                .splice_flags(flags)
+                .build()
+                .0;
+            assert_eq!(unsafe { sqe.__bindgen_anon_2.splice_off_in }, u64::MAX);
+            assert_eq!(unsafe { sqe.__bindgen_anon_1.off }, 0);
+            assert_eq!(
+                unsafe { sqe.__bindgen_anon_3.splice_flags },
+                bits | sys::SPLICE_F_FD_IN_FIXED
+            );
+        }
+
+        // Raw flags are passed through as they are.
+        let sqe = Splice::new(types::Fd(3), 0, types::Fd(4), 0, 512)
+            .flags(libc::SPLICE_F_MOVE)
+            .build()
+            .0;
+        assert_eq!(
+            unsafe { sqe.__bindgen_anon_3.splice_flags },
+            libc::SPLICE_F_MOVE
+        );
+    }
+
+    #[test]
+    fn test_sockopt_build() {
+        let mut optval = 0i32;
+        let sqe = GetSockOpt::new(
+            types::Fd(6),
+            libc::SOL_SOCKET as u32,
+            libc::SO_KEEPALIVE as u32,
+            &mut optval as *mut i32 as *mut libc::c_void,
+            4,
+        )
+        .build()
+        .0;
+        assert_eq!(sqe.opcode, sys::IORING_OP_URING_CMD as u8);
+        assert_eq!(sqe.fd, 6);
+        assert_eq!(
+            unsafe { sqe.__bindgen_anon_1.__bindgen_anon_1.cmd_op },
+            sys::SOCKET_URING_OP_GETSOCKOPT
+        );
+        assert_eq!(
+            unsafe { sqe.__bindgen_anon_2.__bindgen_anon_1.level },
+            libc::SOL_SOCKET as u32
+        );
+        assert_eq!(
+            unsafe { sqe.__bindgen_anon_2.__bindgen_anon_1.optname },
+            libc::SO_KEEPALIVE as u32
+        );
+        assert_eq!(unsafe { sqe.__bindgen_anon_5.optlen }, 4);
+        assert_eq!(
+            unsafe { *sqe.__bindgen_anon_6.optval.as_ref() },
+            &mut optval as *mut i32 as u64
+        );
+
+        let sqe = SetSockOpt::new(
+            types::Fixed(1),
+            libc::IPPROTO_TCP as u32,
+            libc::TCP_NODELAY as u32,
+            &optval as *const i32 as *const libc::c_void,
+            4,
+        )
+        .build()
+        .0;
+        assert_eq!(sqe.fd, 1);
+        assert_eq!(sqe.flags, crate::squeue::Flags::FIXED_FILE.bits());
+        assert_eq!(
+            unsafe { sqe.__bindgen_anon_1.__bindgen_anon_1.cmd_op },
+            sys::SOCKET_URING_OP_SETSOCKOPT
+        );
+        assert_eq!(
+            unsafe { sqe.__bindgen_anon_2.__bindgen_anon_1.level },
+            libc::IPPROTO_TCP as u32
+        );
+    }
+
+    #[test]
+    fn test_bind_listen_build() {
+        let addr = 0x2000 as *const libc::sockaddr;
+        let sqe = Bind::new(types::Fd(5), addr, 16).build().0;
+        assert_eq!(sqe.opcode, sys::IORING_OP_BIND as u8);
+        assert_eq!(sqe.fd, 5);
+        assert_eq!(sqe.flags, 0);
+        assert_eq!(unsafe { sqe.__bindgen_anon_2.addr }, 0x2000);
+        assert_eq!(unsafe { sqe.__bindgen_anon_1.addr2 }, 16);
+        assert_eq!(sqe.len, 0);
+
+        let sqe = Listen::new(types::Fixed(1), 128).build().0;
+        assert_eq!(sqe.opcode, sys::IORING_OP_LISTEN as u8);
+        assert_eq!(sqe.fd, 1);
+        assert_eq!(sqe.flags, crate::squeue::Flags::FIXED_FILE.bits());
+        assert_eq!(sqe.len, 128);
+        assert_eq!(unsafe { sqe.__bindgen_anon_2.addr }, 0);
+        assert_eq!(unsafe { sqe.__bindgen_anon_1.addr2 }, 0);
+    }
+
+    #[test]
+    fn test_epoll_ctl_build() {
+        let ev = 0x1000 as *const types::epoll_event;
+        let sqe = EpollCtl::new(types::Fixed(2), types::Fd(7), libc::EPOLL_CTL_MOD, ev)
+            .build()
+            .0;
+        assert_eq!(sqe.opcode, sys::IORING_OP_EPOLL_CTL as u8);
+        assert_eq!(sqe.fd, 2);
+        assert_eq!(sqe.flags, crate::squeue::Flags::FIXED_FILE.bits());
+        assert_eq!(sqe.len, libc::EPOLL_CTL_MOD as u32);
+        assert_eq!(unsafe { sqe.__bindgen_anon_1.off }, 7);
+        assert_eq!(unsafe { sqe.__bindgen_anon_2.addr }, 0x1000);
+    }
+
+    #[test]
+    fn test_ftruncate_build() {
+        let sqe = Ftruncate::new(types::Fd(7), 4096).build().0;
+        assert_eq!(sqe.opcode, sys::IORING_OP_FTRUNCATE as u8);
+        assert_eq!(sqe.fd, 7);
+        assert_eq!(unsafe { sqe.__bindgen_anon_1.off }, 4096);
+        assert_eq!(sqe.flags, 0);
+
+        let sqe = Ftruncate::new(types::Fixed(3), 0).build().0;
+        assert_eq!(sqe.fd, 3);
+        assert_eq!(unsafe { sqe.__bindgen_anon_1.off }, 0);
+        assert_eq!(sqe.flags, crate::squeue::Flags::FIXED_FILE.bits());
+    }
+
+    #[test]
+    fn test_futex_build() {
+        let futex = 0u32;
+
+        let sqe = FutexWait::new(
+            &futex,
+            1,
+            types::FUTEX_BITSET_MATCH_ANY,
+            types::FUTEX2_SIZE_U32 | types::FUTEX2_PRIVATE,
+        )
+        .build()
+        .0;
+        assert_eq!(sqe.opcode, sys::IORING_OP_FUTEX_WAIT as u8);
+        assert_eq!(
+            sqe.fd as u32,
+            types::FUTEX2_SIZE_U32 | types::FUTEX2_PRIVATE
+        );
+        assert_eq!(
+            unsafe { sqe.__bindgen_anon_2.addr },
+            &futex as *const u32 as u64
+        );
+        assert_eq!(unsafe { sqe.__bindgen_anon_1.off }, 1);
+        assert_eq!(
+            unsafe { sqe.__bindgen_anon_6.__bindgen_anon_1.as_ref().addr3 },
+            types::FUTEX_BITSET_MATCH_ANY
+        );
+        assert_eq!(unsafe { sqe.__bindgen_anon_3.futex_flags }, 0);
+
+        let sqe = FutexWake::new(&futex, 4, 0b1010, types::FUTEX2_SIZE_U32)
+            .build()
+            .0;
+        assert_eq!(sqe.opcode, sys::IORING_OP_FUTEX_WAKE as u8);
+        assert_eq!(sqe.fd as u32, types::FUTEX2_SIZE_U32);
+        assert_eq!(
+            unsafe { sqe.__bindgen_anon_2.addr },
+            &futex as *const u32 as u64
+        );
+        assert_eq!(unsafe { sqe.__bindgen_anon_1.off }, 4);
+        assert_eq!(
+            unsafe { sqe.__bindgen_anon_6.__bindgen_anon_1.as_ref().addr3 },
+            0b1010
+        );
+
+        let futexv = [types::FutexWaitV::new()
+            .uaddr(&futex as *const u32 as u64)
+            .flags(types::FUTEX2_SIZE_U32); 2];
+        let sqe = FutexWaitV::new(futexv.as_ptr(), futexv.len() as u32)
+            .build()
+            .0;
+        assert_eq!(sqe.opcode, sys::IORING_OP_FUTEX_WAITV as u8);
+        assert_eq!(unsafe { sqe.__bindgen_anon_2.addr }, futexv.as_ptr() as u64);
+        assert_eq!(sqe.len, 2);
+    }
+
+    #[test]
+    fn test_waitid_build() {
+        let mut info: libc::siginfo_t = unsafe { mem::zeroed() };
+
+        let sqe = WaitId::new(libc::P_PID, 1234, &mut info, libc::WEXITED)
+            .build()
+            .0;
+        assert_eq!(sqe.opcode, sys::IORING_OP_WAITID as u8);
+        assert_eq!(sqe.fd, 1234);
+        assert_eq!(sqe.len, libc::P_PID);
+        assert_eq!(
+            unsafe { sqe.__bindgen_anon_5.file_index },
+            libc::WEXITED as u32
+        );
+        assert_eq!(
+            unsafe { sqe.__bindgen_anon_1.addr2 },
+            &mut info as *mut libc::siginfo_t as u64
+        );
+        assert_eq!(unsafe { sqe.__bindgen_anon_2.addr }, 0);
+        assert_eq!(unsafe { sqe.__bindgen_anon_3.waitid_flags }, 0);
+    }
+
+    #[test]
+    fn test_fixed_fd_install_build() {
+        let sqe = FixedFdInstall::new(types::Fixed(5), types::FIXED_FD_NO_CLOEXEC)
+            .build()
+            .0;
+        assert_eq!(sqe.opcode, sys::IORING_OP_FIXED_FD_INSTALL as u8);
+        assert_eq!(sqe.fd, 5);
+        assert_eq!(sqe.flags, crate::squeue::Flags::FIXED_FILE.bits());
+        assert_eq!(
+            unsafe { sqe.__bindgen_anon_3.install_fd_flags },
+            sys::IORING_FIXED_FD_NO_CLOEXEC
+        );
+    }
+
+    #[test]
+    fn test_uring_cmd80_build() {
+        let mut cmd = [0u8; 80];
+        for (i, b) in cmd.iter_mut().enumerate() {
+            *b = i as u8;
+        }
+
+        let entry = UringCmd80::new(types::Fd(3), 0x1234)
+            .cmd(cmd)
+            .build()
+            .user_data(0x42);
+        assert_eq!(mem::size_of_val(&entry), 128);
+
+        let Entry128(Entry(sqe), tail) = entry;
+        assert_eq!(sqe.opcode, sys::IORING_OP_URING_CMD as u8);
+        assert_eq!(sqe.fd, 3);
+        assert_eq!(sqe.user_data, 0x42);
+        assert_eq!(
+            unsafe { sqe.__bindgen_anon_1.__bindgen_anon_1.cmd_op },
+            0x1234
+        );
+
+        // The first 16 bytes of the command live in the first half of the entry, and the rest in
+        // the extra 64 bytes of the 128-byte entry.
+        let head = unsafe {
+            *sqe.__bindgen_anon_6
+                .cmd
+                .as_ref()
+                .as_ptr()
+                .cast::<[u8; 16]>()
+        };
+        assert_eq!(head, cmd[..16]);
+        assert_eq!(tail, cmd[16..]);
+
+        let bytes: [u8; 128] = unsafe { mem::transmute(Entry128(Entry(sqe), tail)) };
+        assert_eq!(bytes[48..], cmd[..]);
+    }
+
+    #[test]
+    fn test_uring_cmd16_build() {
+        let mut cmd = [0u8; 16];
+        for (i, b) in cmd.iter_mut().enumerate() {
+            *b = 0xa0 + i as u8;
+        }
+
+        let sqe = UringCmd16::new(types::Fixed(4), 0x5678)
+            .cmd(cmd)
+            .buf_index(Some(2))
+            .build()
+            .0;
+        assert_eq!(sqe.opcode, sys::IORING_OP_URING_CMD as u8);
+        assert_eq!(sqe.fd, 4);
+        assert_eq!(sqe.flags, crate::squeue::Flags::FIXED_FILE.bits());
+        assert_eq!(
+            unsafe { sqe.__bindgen_anon_1.__bindgen_anon_1.cmd_op },
+            0x5678
+        );
+        assert_eq!(unsafe { sqe.__bindgen_anon_4.buf_index }, 2);
+        assert_eq!(
+            unsafe { sqe.__bindgen_anon_3.uring_cmd_flags },
+            sys::IORING_URING_CMD_FIXED
+        );
+
+        // The command occupies the last 16 bytes of the 64-byte entry.
+        let bytes: [u8; 64] = unsafe { mem::transmute(sqe) };
+        assert_eq!(bytes[48..], cmd[..]);
+    }
+
+    #[test]
+    fn test_async_cancel2_build() {
+        let sqe = AsyncCancel2::new(types::CancelBuilder::user_data(42).all())
+            .build()
+            .0;
+        assert_eq!(sqe.opcode, sys::IORING_OP_ASYNC_CANCEL as u8);
+        assert_eq!(sqe.fd, -1);
+        assert_eq!(unsafe { sqe.__bindgen_anon_2.addr }, 42);
+        assert_eq!(
+            unsafe { sqe.__bindgen_anon_3.cancel_flags },
+            sys::IORING_ASYNC_CANCEL_ALL
+        );
+
+        let sqe = AsyncCancel2::new(types::CancelBuilder::any()).build().0;
+        assert_eq!(sqe.fd, -1);
+        assert_eq!(unsafe { sqe.__bindgen_anon_2.addr }, 0);
+        assert_eq!(
+            unsafe { sqe.__bindgen_anon_3.cancel_flags },
+            sys::IORING_ASYNC_CANCEL_ANY
+        );
+
+        let sqe = AsyncCancel2::new(types::CancelBuilder::fd(types::Fd(7)).all())
+            .build()
+            .0;
+        assert_eq!(sqe.fd, 7);
+        assert_eq!(unsafe { sqe.__bindgen_anon_2.addr }, 0);
+        assert_eq!(
+            unsafe { sqe.__bindgen_anon_3.cancel_flags },
+            sys::IORING_ASYNC_CANCEL_FD | sys::IORING_ASYNC_CANCEL_ALL
+        );
+
+        let sqe = AsyncCancel2::new(types::CancelBuilder::fd(types::Fixed(3)))
+            .build()
+            .0;
+        assert_eq!(sqe.fd, 3);
+        assert_eq!(
+            unsafe { sqe.__bindgen_anon_3.cancel_flags },
+            sys::IORING_ASYNC_CANCEL_FD | sys::IORING_ASYNC_CANCEL_FD_FIXED
+        );
+    }
+
+    #[test]
+    fn test_timeout_build() {
+        let ts = types::Timespec::new().nsec(10_000_000);
+
+        let sqe = Timeout::new(&ts).build().0;
+        assert_eq!(sqe.opcode, sys::IORING_OP_TIMEOUT as u8);
+        assert_eq!(unsafe { sqe.__bindgen_anon_2.addr }, &ts as *const _ as u64);
+        assert_eq!(sqe.len, 1);
+        assert_eq!(unsafe { sqe.__bindgen_anon_1.off }, 0);
+        assert_eq!(unsafe { sqe.__bindgen_anon_3.timeout_flags }, 0);
+
+        let sqe = Timeout::new(&ts)
+            .count(3)
+            .flags(types::TimeoutFlags::MULTISHOT)
+            .build()
+            .0;
+        assert_eq!(unsafe { sqe.__bindgen_anon_1.off }, 3);
+        assert_eq!(
+            unsafe { sqe.__bindgen_anon_3.timeout_flags },
+            sys::IORING_TIMEOUT_MULTISHOT
+        );
+
+        for (flags, bits) in [
+            (types::TimeoutFlags::ABS, sys::IORING_TIMEOUT_ABS),
+            (
+                types::TimeoutFlags::ABS | types::TimeoutFlags::BOOTTIME,
+                sys::IORING_TIMEOUT_ABS | sys::IORING_TIMEOUT_BOOTTIME,
+            ),
+            (
+                types::TimeoutFlags::ABS | types::TimeoutFlags::REALTIME,
+                sys::IORING_TIMEOUT_ABS | sys::IORING_TIMEOUT_REALTIME,
+            ),
+        ] {
+            let sqe = Timeout::new(&ts).flags(flags).build().0;
+            assert_eq!(unsafe { sqe.__bindgen_anon_3.timeout_flags }, bits);
+        }
+    }
+
+    #[test]
+    fn test_nop_build() {
+        let sqe = Nop::new().build().0;
+        assert_eq!(sqe.opcode, sys::IORING_OP_NOP as u8);
+        assert_eq!(sqe.fd, -1);
+        assert_eq!(unsafe { sqe.__bindgen_anon_3.nop_flags }, 0);
+        assert_eq!(sqe.len, 0);
+
+        let sqe = Nop::new().result(-libc::EINVAL).build().0;
+        assert_eq!(
+            unsafe { sqe.__bindgen_anon_3.nop_flags },
+            sys::IORING_NOP_INJECT_RESULT
+        );
+        assert_eq!(sqe.len as i32, -libc::EINVAL);
+    }
+
+    #[test]
+    fn test_target_build() {
+        let fixed_file = crate::squeue::Flags::FIXED_FILE.bits();
+
+        let sqe = Fsync::new(types::Fd(7)).build().0;
+        assert_eq!(sqe.fd, 7);
+        assert_eq!(sqe.flags & fixed_file, 0);
+
+        let sqe = Fsync::new(types::Fixed(3)).build().0;
+        assert_eq!(sqe.fd, 3);
+        assert_eq!(sqe.flags & fixed_file, fixed_file);
+
+        for (target, fixed) in [
+            (types::Target::from(types::Fd(7)), false),
+            (types::Target::from(types::Fixed(3)), true),
+        ] {
+            let sqe = Read::new(target, std::ptr::null_mut(), 0).build().0;
+            assert_eq!(sqe.flags & fixed_file != 0, fixed);
+            assert_eq!(sqe.fd, if fixed { 3 } else { 7 });
+        }
+    }
+
+    #[test]
+    fn test_shutdown_build() {
+        let sqe = Shutdown::new(types::Fd(5), libc::SHUT_WR).build().0;
+        assert_eq!(sqe.opcode, sys::IORING_OP_SHUTDOWN as u8);
+        assert_eq!(sqe.fd, 5);
+        assert_eq!(sqe.len, libc::SHUT_WR as u32);
+        assert_eq!(sqe.flags, 0);
+
+        let sqe = Shutdown::new(types::Fixed(2), libc::SHUT_RDWR).build().0;
+        assert_eq!(sqe.fd, 2);
+        assert_eq!(sqe.len, libc::SHUT_RDWR as u32);
+        assert_eq!(sqe.flags, crate::squeue::Flags::FIXED_FILE.bits());
+    }
+
+    #[test]
+    fn test_xattr_build() {
+        let path = b"/tmp/file\0".as_ptr().cast();
+        let name = b"user.key\0".as_ptr().cast();
+        let mut value = [0u8; 8];
+
+        let sqe = SetXattr::new(path, name, value.as_ptr().cast(), 8)
+            .flags(libc::XATTR_CREATE)
+            .build()
+            .0;
+        assert_eq!(sqe.opcode, sys::IORING_OP_SETXATTR as u8);
+        assert_eq!(unsafe { sqe.__bindgen_anon_2.addr }, name as u64);
+        assert_eq!(unsafe { sqe.__bindgen_anon_1.addr2 }, value.as_ptr() as u64);
+        assert_eq!(sqe.len, 8);
+        assert_eq!(
+            unsafe { sqe.__bindgen_anon_3.xattr_flags },
+            libc::XATTR_CREATE as u32
+        );
+        assert_eq!(
+            unsafe { sqe.__bindgen_anon_6.__bindgen_anon_1.as_ref().addr3 },
+            path as u64
+        );
+
+        let sqe = GetXattr::new(path, name, value.as_mut_ptr().cast(), 8)
+            .build()
+            .0;
+        assert_eq!(sqe.opcode, sys::IORING_OP_GETXATTR as u8);
+        assert_eq!(unsafe { sqe.__bindgen_anon_1.addr2 }, value.as_ptr() as u64);
+        assert_eq!(unsafe { sqe.__bindgen_anon_3.xattr_flags }, 0);
+        assert_eq!(
+            unsafe { sqe.__bindgen_anon_6.__bindgen_anon_1.as_ref().addr3 },
+            path as u64
+        );
+
+        let sqe = FSetXattr::new(types::Fd(3), name, value.as_ptr().cast(), 8)
+            .flags(libc::XATTR_REPLACE)
+            .build()
+            .0;
+        assert_eq!(sqe.opcode, sys::IORING_OP_FSETXATTR as u8);
+        assert_eq!(sqe.fd, 3);
+        assert_eq!(unsafe { sqe.__bindgen_anon_2.addr }, name as u64);
+        assert_eq!(
+            unsafe { sqe.__bindgen_anon_3.xattr_flags },
+            libc::XATTR_REPLACE as u32
+        );
+
+        let sqe = FGetXattr::new(types::Fd(3), name, value.as_mut_ptr().cast(), 0)
+            .build()
+            .0;
+        assert_eq!(sqe.opcode, sys::IORING_OP_FGETXATTR as u8);
+        assert_eq!(sqe.fd, 3);
+        assert_eq!(sqe.len, 0);
+        assert_eq!(
+            unsafe { sqe.__bindgen_anon_6.__bindgen_anon_1.as_ref().addr3 },
+            0
+        );
+    }
+
+    #[test]
+    fn test_path_ops_build() {
+        let old = b"old\0".as_ptr().cast();
+        let new = b"new\0".as_ptr().cast();
+
+        let sqe = RenameAt::new(types::Fd(3), old, types::Fd(4), new)
+            .flags(libc::RENAME_NOREPLACE)
+            .build()
+            .0;
+        assert_eq!(sqe.opcode, sys::IORING_OP_RENAMEAT as u8);
+        assert_eq!(sqe.fd, 3);
+        assert_eq!(unsafe { sqe.__bindgen_anon_2.addr }, old as u64);
+        assert_eq!(sqe.len, 4);
+        assert_eq!(unsafe { sqe.__bindgen_anon_1.off }, new as u64);
+        assert_eq!(
+            unsafe { sqe.__bindgen_anon_3.rename_flags },
+            libc::RENAME_NOREPLACE
+        );
+
+        let sqe = UnlinkAt::new(types::Fd(3), old)
+            .flags(libc::AT_REMOVEDIR)
+            .build()
+            .0;
+        assert_eq!(sqe.opcode, sys::IORING_OP_UNLINKAT as u8);
+        assert_eq!(sqe.fd, 3);
+        assert_eq!(unsafe { sqe.__bindgen_anon_2.addr }, old as u64);
+        assert_eq!(
+            unsafe { sqe.__bindgen_anon_3.unlink_flags },
+            libc::AT_REMOVEDIR as u32
+        );
+
+        let sqe = MkDirAt::new(types::Fd(libc::AT_FDCWD), old)
+            .mode(0o755)
+            .build()
+            .0;
+        assert_eq!(sqe.opcode, sys::IORING_OP_MKDIRAT as u8);
+        assert_eq!(sqe.fd, libc::AT_FDCWD);
+        assert_eq!(unsafe { sqe.__bindgen_anon_2.addr }, old as u64);
+        assert_eq!(sqe.len, 0o755);
+
+        let sqe = SymlinkAt::new(types::Fd(3), old, new).build().0;
+        assert_eq!(sqe.opcode, sys::IORING_OP_SYMLINKAT as u8);
+        assert_eq!(sqe.fd, 3);
+        assert_eq!(unsafe { sqe.__bindgen_anon_2.addr }, old as u64);
+        assert_eq!(unsafe { sqe.__bindgen_anon_1.addr2 }, new as u64);
+
+        let sqe = LinkAt::new(types::Fd(3), old, types::Fd(4), new)
+            .flags(libc::AT_SYMLINK_FOLLOW)
+            .build()
+            .0;
+        assert_eq!(sqe.opcode, sys::IORING_OP_LINKAT as u8);
+        assert_eq!(sqe.fd, 3);
+        assert_eq!(unsafe { sqe.__bindgen_anon_2.addr }, old as u64);
+        assert_eq!(sqe.len, 4);
+        assert_eq!(unsafe { sqe.__bindgen_anon_1.addr2 }, new as u64);
+        assert_eq!(
+            unsafe { sqe.__bindgen_anon_3.hardlink_flags },
+            libc::AT_SYMLINK_FOLLOW as u32
+        );
+    }
+
+    #[test]
+    fn test_fixed_from_buf_build() {
+        let mut buf = [0u8; 64];
+
+        let sqe = ReadFixed::from_buf(types::Fd(3), 2, &mut buf[16..48])
+            .offset(8)
+            .build()
+            .0;
+        assert_eq!(sqe.opcode, sys::IORING_OP_READ_FIXED as u8);
+        assert_eq!(sqe.fd, 3);
+        assert_eq!(
+            unsafe { sqe.__bindgen_anon_2.addr },
+            buf[16..].as_ptr() as u64
+        );
+        assert_eq!(sqe.len, 32);
+        assert_eq!(unsafe { sqe.__bindgen_anon_4.buf_index }, 2);
+        assert_eq!(unsafe { sqe.__bindgen_anon_1.off }, 8);
+
+        let sqe = WriteFixed::from_buf(types::Fixed(1), 5, &buf).build().0;
+        assert_eq!(sqe.opcode, sys::IORING_OP_WRITE_FIXED as u8);
+        assert_eq!(sqe.fd, 1);
+        assert_eq!(unsafe { sqe.__bindgen_anon_2.addr }, buf.as_ptr() as u64);
+        assert_eq!(sqe.len, 64);
+        assert_eq!(unsafe { sqe.__bindgen_anon_4.buf_index }, 5);
+    }
+
+    #[test]
+    fn test_msghdr_build() {
+        let data = [0u8; 8];
+        let iov = [std::io::IoSlice::new(&data)];
+        let send_msg = types::MsgHdr::new().iov(&iov);
+        let sqe = SendMsg::from_msghdr(types::Fd(3), &send_msg).build().0;
+        assert_eq!(sqe.opcode, sys::IORING_OP_SENDMSG as u8);
+        assert_eq!(sqe.fd, 3);
+        assert_eq!(sqe.len, 1);
+        assert_eq!(
+            unsafe { sqe.__bindgen_anon_2.addr },
+            send_msg.as_ptr() as u64
+        );
+
+        let mut buf = [0u8; 8];
+        let mut iov = [std::io::IoSliceMut::new(&mut buf)];
+        let mut recv_msg = types::MsgHdr::new().iov_mut(&mut iov);
+        let sqe = RecvMsg::from_msghdr(types::Fixed(4), &mut recv_msg)
+            .build()
+            .0;
+        assert_eq!(sqe.opcode, sys::IORING_OP_RECVMSG as u8);
+        assert_eq!(sqe.fd, 4);
+        assert_eq!(sqe.flags, crate::squeue::Flags::FIXED_FILE.bits());
+        assert_eq!(
+            unsafe { sqe.__bindgen_anon_2.addr },
+            recv_msg.as_mut_ptr() as u64
+        );
+    }
+
+    #[test]
+    fn test_connect_from_addr_build() {
+        let addrs: [std::net::SocketAddr; 2] = [
+            "127.0.0.1:8080".parse().unwrap(),
+            "[::1]:8080".parse().unwrap(),
+        ];
+
+        for addr in addrs {
+            let sock_addr = types::SockAddr::from(addr);
+            assert_eq!(sock_addr.as_socket_addr(), addr);
+
+            let sqe = Connect::from_addr(types::Fd(3), &sock_addr).build().0;
+            assert_eq!(sqe.opcode, sys::IORING_OP_CONNECT as u8);
+            assert_eq!(sqe.fd, 3);
+            assert_eq!(
+                unsafe { sqe.__bindgen_anon_2.addr },
+                sock_addr.as_ptr() as u64
+            );
+            assert_eq!(
+                unsafe { sqe.__bindgen_anon_1.off },
+                sock_addr.addr_len() as u64
+            );
+        }
+
+        let v4 = types::SockAddr::from(addrs[0]);
+        let v6 = types::SockAddr::from(addrs[1]);
+        assert_eq!(v4.addr_len() as usize, mem::size_of::<libc::sockaddr_in>());
+        assert_eq!(v6.addr_len() as usize, mem::size_of::<libc::sockaddr_in6>());
+        assert_ne!(v4, v6);
+    }
+
+    #[test]
+    fn test_socket_file_index_build() {
+        let sqe = Socket::new(libc::AF_INET, libc::SOCK_STREAM, 0).build().0;
+        assert_eq!(sqe.opcode, sys::IORING_OP_SOCKET as u8);
+        assert_eq!(sqe.fd, libc::AF_INET);
+        assert_eq!(
+            unsafe { sqe.__bindgen_anon_1.off },
+            libc::SOCK_STREAM as u64
+        );
+        assert_eq!(unsafe { sqe.__bindgen_anon_5.file_index }, 0);
+
+        let sqe = Socket::new(libc::AF_INET, libc::SOCK_STREAM, 0)
+            .file_index(Some(types::DestinationSlot::auto_target()))
+            .build()
+            .0;
+        assert_eq!(
+            unsafe { sqe.__bindgen_anon_5.file_index },
+            sys::IORING_FILE_INDEX_ALLOC as u32
+        );
+
+        let slot = types::DestinationSlot::try_from_slot_target(3).unwrap();
+        let sqe = Socket::new(libc::AF_INET, libc::SOCK_STREAM, 0)
+            .file_index(Some(slot))
+            .build()
+            .0;
+        assert_eq!(unsafe { sqe.__bindgen_anon_5.file_index }, 4);
+    }
+
+    #[test]
+    fn test_fallocate_build() {
+        let sqe = Fallocate::new(types::Fd(3), 4096).build().0;
+        assert_eq!(sqe.opcode, sys::IORING_OP_FALLOCATE as u8);
+        assert_eq!(sqe.fd, 3);
+        assert_eq!(unsafe { sqe.__bindgen_anon_2.addr }, 4096);
+        assert_eq!(unsafe { sqe.__bindgen_anon_1.off }, 0);
+        assert_eq!(sqe.len, 0);
+
+        let sqe = Fallocate::new(types::Fixed(1), 512)
+            .offset(1024)
+            .fallocate_flags(types::FallocateFlags::PUNCH_HOLE | types::FallocateFlags::KEEP_SIZE)
+            .build()
+            .0;
+        assert_eq!(sqe.fd, 1);
+        assert_ne!(sqe.flags & crate::squeue::Flags::FIXED_FILE.bits(), 0);
+        assert_eq!(unsafe { sqe.__bindgen_anon_2.addr }, 512);
+        assert_eq!(unsafe { sqe.__bindgen_anon_1.off }, 1024);
+        assert_eq!(
+            sqe.len,
+            (libc::FALLOC_FL_PUNCH_HOLE | libc::FALLOC_FL_KEEP_SIZE) as u32
+        );
+
+        for (flag, raw) in [
+            (types::FallocateFlags::KEEP_SIZE, libc::FALLOC_FL_KEEP_SIZE),
+            (
+                types::FallocateFlags::PUNCH_HOLE,
+                libc::FALLOC_FL_PUNCH_HOLE,
+            ),
+            (
+                types::FallocateFlags::ZERO_RANGE,
+                libc::FALLOC_FL_ZERO_RANGE,
+            ),
+        ] {
+            let sqe = Fallocate::new(types::Fd(3), 1)
+                .fallocate_flags(flag)
+                .build()
+                .0;
+            assert_eq!(sqe.len, raw as u32);
+        }
+
+        // A raw mode is passed through as it is.
+        let sqe = Fallocate::new(types::Fd(3), 1)
+            .mode(libc::FALLOC_FL_KEEP_SIZE)
+            .build()
+            .0;
+        assert_eq!(sqe.len, libc::FALLOC_FL_KEEP_SIZE as u32);
+    }
+
+    #[test]
+    fn test_recvsend_flags_build() {
+        let poll_first = sys::IORING_RECVSEND_POLL_FIRST as u16;
+        let fixed_buf = sys::IORING_RECVSEND_FIXED_BUF as u16;
+        let mut buf = [0u8; 16];
+
+        let sqe = Send::new(types::Fd(3), buf.as_ptr(), 16).build().0;
+        assert_eq!(sqe.ioprio, 0);
+        let sqe = Send::new(types::Fd(3), buf.as_ptr(), 16)
+            .poll_first(true)
+            .build()
+            .0;
+        assert_eq!(sqe.ioprio, poll_first);
+
+        let sqe = Recv::new(types::Fd(3), buf.as_mut_ptr(), 16).build().0;
+        assert_eq!(sqe.ioprio, 0);
+        let sqe = Recv::new(types::Fd(3), buf.as_mut_ptr(), 16)
+            .poll_first(true)
+            .build()
+            .0;
+        assert_eq!(sqe.ioprio, poll_first);
+
+        let sqe = SendZc::new(types::Fd(3), buf.as_ptr(), 16).build().0;
+        assert_eq!(sqe.ioprio, 0);
+        let sqe = SendZc::new(types::Fd(3), buf.as_ptr(), 16)
+            .poll_first(true)
+            .build()
+            .0;
+        assert_eq!(sqe.ioprio, poll_first);
+        let sqe = SendZc::new(types::Fd(3), buf.as_ptr(), 16)
+            .buf_index(Some(7))
+            .build()
+            .0;
+        assert_eq!(sqe.ioprio, fixed_buf);
+        assert_eq!(unsafe { sqe.__bindgen_anon_4.buf_index }, 7);
+        let sqe = SendZc::new(types::Fd(3), buf.as_ptr(), 16)
+            .buf_index(Some(7))
+            .poll_first(true)
+            .build()
+            .0;
+        assert_eq!(sqe.ioprio, poll_first | fixed_buf);
+    }
+
+    #[test]
+    fn test_async_cancel_build() {
+        let sqe = AsyncCancel::new(42).build().0;
+        assert_eq!(sqe.opcode, sys::IORING_OP_ASYNC_CANCEL as u8);
+        assert_eq!(sqe.fd, -1);
+        assert_eq!(unsafe { sqe.__bindgen_anon_2.addr }, 42);
+        assert_eq!(unsafe { sqe.__bindgen_anon_3.cancel_flags }, 0);
+
+        let sqe = AsyncCancel::new(42).all(true).build().0;
+        assert_eq!(unsafe { sqe.__bindgen_anon_2.addr }, 42);
+        assert_eq!(
+            unsafe { sqe.__bindgen_anon_3.cancel_flags },
+            sys::IORING_ASYNC_CANCEL_ALL
+        );
+    }
+
+    #[test]
+    fn test_link_timeout_build() {
+        let ts = types::Timespec::new().sec(1);
+
+        let sqe = LinkTimeout::new(&ts).build().0;
+        assert_eq!(sqe.opcode, sys::IORING_OP_LINK_TIMEOUT as u8);
+        assert_eq!(sqe.fd, -1);
+        assert_eq!(unsafe { sqe.__bindgen_anon_2.addr }, &ts as *const _ as u64);
+        assert_eq!(sqe.len, 1);
+        assert_eq!(unsafe { sqe.__bindgen_anon_3.timeout_flags }, 0);
+
+        let sqe = LinkTimeout::new(&ts)
+            .flags(types::TimeoutFlags::ABS | types::TimeoutFlags::REALTIME)
+            .build()
+            .0;
+        assert_eq!(
+            unsafe { sqe.__bindgen_anon_3.timeout_flags },
+            sys::IORING_TIMEOUT_ABS | sys::IORING_TIMEOUT_REALTIME
+        );
+
+        let sqe = LinkTimeout::new(&ts)
+            .flags(types::TimeoutFlags::ABS | types::TimeoutFlags::BOOTTIME)
+            .build()
+            .0;
+        assert_eq!(
+            unsafe { sqe.__bindgen_anon_3.timeout_flags },
+            sys::IORING_TIMEOUT_ABS | sys::IORING_TIMEOUT_BOOTTIME
+        );
+    }
+
+    #[test]
+    fn test_poll_add_build() {
+        let sqe = PollAdd::new(types::Fd(5), libc::POLLIN as _).build().0;
+        assert_eq!(sqe.opcode, sys::IORING_OP_POLL_ADD as u8);
+        assert_eq!(sqe.fd, 5);
+        assert_eq!(sqe.len, 0);
+
+        let sqe = PollAdd::new(types::Fd(5), libc::POLLIN as _)
+            .multi(true)
+            .build()
+            .0;
+        assert_eq!(sqe.len, sys::IORING_POLL_ADD_MULTI);
+        #[cfg(target_endian = "little")]
+        assert_eq!(
+            unsafe { sqe.__bindgen_anon_3.poll32_events },
+            libc::POLLIN as u32
+        );
+    }
+
+    #[test]
+    fn test_recv_multi_build() {
+        let sqe = RecvMulti::new(types::Fd(4), 0x33).build().0;
+        assert_eq!(sqe.opcode, sys::IORING_OP_RECV as u8);
+        assert_eq!(sqe.fd, 4);
+        assert_eq!(sqe.ioprio, sys::IORING_RECV_MULTISHOT as u16);
+        assert_eq!(unsafe { sqe.__bindgen_anon_4.buf_group }, 0x33);
+        assert_ne!(sqe.flags & crate::squeue::Flags::BUFFER_SELECT.bits(), 0);
+
+        let msg: libc::msghdr = unsafe { mem::zeroed() };
+        let sqe = RecvMsgMulti::new(types::Fd(4), &msg, 0x33).build().0;
+        assert_eq!(sqe.opcode, sys::IORING_OP_RECVMSG as u8);
+        assert_eq!(sqe.ioprio, sys::IORING_RECV_MULTISHOT as u16);
+        assert_eq!(
+            unsafe { sqe.__bindgen_anon_2.addr },
+            &msg as *const _ as u64
+        );
+        assert_eq!(unsafe { sqe.__bindgen_anon_4.buf_group }, 0x33);
+        assert_ne!(sqe.flags & crate::squeue::Flags::BUFFER_SELECT.bits(), 0);
+    }
+
+    #[test]
+    fn test_accept_multi_build() {
+        let sqe = AcceptMulti::new(types::Fd(6)).build().0;
+        assert_eq!(sqe.opcode, sys::IORING_OP_ACCEPT as u8);
+        assert_eq!(sqe.fd, 6);
+        assert_eq!(sqe.ioprio, sys::IORING_ACCEPT_MULTISHOT as u16);
+        assert_eq!(unsafe { sqe.__bindgen_anon_5.file_index }, 0);
+
+        let sqe = AcceptMulti::new(types::Fd(6))
+            .allocate_file_index(true)
+            .build()
+            .0;
+        assert_eq!(sqe.ioprio, sys::IORING_ACCEPT_MULTISHOT as u16);
+        assert_eq!(
+            unsafe { sqe.__bindgen_anon_5.file_index },
+            sys::IORING_FILE_INDEX_ALLOC as u32
+        );
+    }
+
+    #[test]
+    fn test_send_zc_build() {
+        let buf = [0u8; 16];
+        let sqe = SendZc::new(types::Fd(8), buf.as_ptr(), 16)
+            .buf_index(Some(2))
+            .build()
+            .0;
+        assert_eq!(sqe.opcode, sys::IORING_OP_SEND_ZC as u8);
+        assert_eq!(sqe.fd, 8);
+        assert_eq!(unsafe { sqe.__bindgen_anon_2.addr }, buf.as_ptr() as u64);
+        assert_eq!(sqe.len, 16);
+        assert_eq!(unsafe { sqe.__bindgen_anon_4.buf_index }, 2);
+        assert_eq!(sqe.ioprio, sys::IORING_RECVSEND_FIXED_BUF as u16);
+
+        let msg: libc::msghdr = unsafe { mem::zeroed() };
+        let sqe = SendMsgZc::new(types::Fd(8), &msg)
+            .flags(libc::MSG_NOSIGNAL as u32)
+            .build()
+            .0;
+        assert_eq!(sqe.opcode, sys::IORING_OP_SENDMSG_ZC as u8);
+        assert_eq!(sqe.fd, 8);
+        assert_eq!(
+            unsafe { sqe.__bindgen_anon_2.addr },
+            &msg as *const _ as u64
+        );
+        assert_eq!(sqe.len, 1);
+        assert_eq!(
+            unsafe { sqe.__bindgen_anon_3.msg_flags },
+            libc::MSG_NOSIGNAL as u32
+        );
+
+        let sqe = SendBundle::new(types::Fd(8), 0x35).len(64).build().0;
+        assert_eq!(sqe.opcode, sys::IORING_OP_SEND as u8);
+        assert_eq!(sqe.len, 64);
+        assert_eq!(sqe.ioprio, sys::IORING_RECVSEND_BUNDLE as u16);
+        assert_eq!(unsafe { sqe.__bindgen_anon_4.buf_group }, 0x35);
+        assert_ne!(sqe.flags & crate::squeue::Flags::BUFFER_SELECT.bits(), 0);
+    }
+
+    #[test]
+    fn test_msg_ring_data_build() {
+        let sqe = MsgRingData::new(types::Fd(9), 82, 85, None).build().0;
+        assert_eq!(sqe.opcode, sys::IORING_OP_MSG_RING as u8);
+        assert_eq!(sqe.fd, 9);
+        assert_eq!(
+            unsafe { sqe.__bindgen_anon_2.addr },
+            sys::IORING_MSG_DATA as u64
+        );
+        assert_eq!(sqe.len, 82);
+        assert_eq!(unsafe { sqe.__bindgen_anon_1.off }, 85);
+        assert_eq!(unsafe { sqe.__bindgen_anon_3.msg_ring_flags }, 0);
+
+        let sqe = MsgRingData::new(types::Fd(9), 82, 85, Some(0x8000))
+            .build()
+            .0;
+        assert_eq!(unsafe { sqe.__bindgen_anon_5.file_index }, 0x8000);
+        assert_eq!(
+            unsafe { sqe.__bindgen_anon_3.msg_ring_flags },
+            sys::IORING_MSG_RING_FLAGS_PASS
+        );
+    }
+
+    #[test]
+    fn test_msg_ring_send_fd_build() {
+        let dest_slot = types::DestinationSlot::try_from_slot_target(2).unwrap();
+        let sqe = MsgRingSendFd::new(types::Fd(9), types::Fixed(1), dest_slot, 44)
+            .opcode_flags(types::MSG_RING_CQE_SKIP)
+            .build()
+            .0;
+        assert_eq!(sqe.opcode, sys::IORING_OP_MSG_RING as u8);
+        assert_eq!(sqe.fd, 9);
+        assert_eq!(
+            unsafe { sqe.__bindgen_anon_2.addr },
+            sys::IORING_MSG_SEND_FD as u64
+        );
+        assert_eq!(unsafe { sqe.__bindgen_anon_1.off }, 44);
+        assert_eq!(
+            unsafe { sqe.__bindgen_anon_6.__bindgen_anon_1.as_ref().addr3 },
+            1
+        );
+        assert_eq!(unsafe { sqe.__bindgen_anon_5.file_index }, 3);
+        assert_eq!(
+            unsafe { sqe.__bindgen_anon_3.msg_ring_flags },
+            sys::IORING_MSG_RING_CQE_SKIP
+        );
+    }
+}
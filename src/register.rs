@@ -1,9 +1,10 @@
 //! Some register syscall related types or parameters.
 
 use std::os::unix::io::RawFd;
+use std::sync::atomic;
 use std::{fmt, io};
 
-use crate::sys;
+use crate::{opcode, sys, Parameters};
 
 pub(crate) fn execute(
     fd: RawFd,
@@ -51,6 +52,22 @@ impl Probe {
             }
         }
     }
+
+    /// Iterate over the opcodes the kernel reported as supported, in ascending order.
+    pub fn supported_ops(&self) -> impl Iterator<Item = u8> + '_ {
+        self.ops()
+            .iter()
+            .filter(|op| op.flags & (sys::IO_URING_OP_SUPPORTED as u16) != 0)
+            .map(|op| op.op)
+    }
+
+    #[inline]
+    fn ops(&self) -> &[sys::io_uring_probe_op] {
+        unsafe {
+            let probe = &(self.0).0;
+            probe.ops.as_slice(probe.last_op as usize + 1)
+        }
+    }
 }
 
 impl Default for Probe {
@@ -62,23 +79,172 @@ impl Default for Probe {
 
 impl fmt::Debug for Probe {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        struct Op<'a>(&'a sys::io_uring_probe_op);
+        struct Op(u8);
 
-        impl fmt::Debug for Op<'_> {
+        impl fmt::Debug for Op {
             #[inline]
             fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-                f.debug_struct("Op").field("code", &self.0.op).finish()
+                f.debug_struct("Op").field("code", &self.0).finish()
             }
         }
 
-        let probe = &(self.0).0;
-        let list = unsafe { probe.ops.as_slice(probe.last_op as usize + 1) };
-        let list = list
-            .iter()
-            .filter(|op| op.flags & (sys::IO_URING_OP_SUPPORTED as u16) != 0)
-            .map(Op);
+        f.debug_set().entries(self.supported_ops().map(Op)).finish()
+    }
+}
+
+/// A summary of what the kernel supports, built from a [`Probe`] and the ring's [`Parameters`].
+///
+/// You can get one with [`IoUring::capabilities`](crate::IoUring::capabilities), which probes
+/// the kernel once per ring and returns the same summary afterwards. Features that can't be probed
+/// directly are inferred from an opcode introduced in the same kernel release.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Capabilities {
+    nop: bool,
+    recv_multishot: bool,
+    buf_ring: bool,
+    send_zc: bool,
+    msg_ring: bool,
+    socket: bool,
+    futex: bool,
+    ext_arg: bool,
+    skip_success: bool,
+    recvsend_bundle: bool,
+}
+
+impl Capabilities {
+    /// Summarize a probe filled in by [`register_probe`](crate::Submitter::register_probe).
+    pub fn new(probe: &Probe, params: &Parameters) -> Capabilities {
+        Capabilities {
+            nop: probe.is_supported(opcode::Nop::CODE),
+            // Multishot receive came with 6.0, as did SendZc.
+            recv_multishot: probe.is_supported(opcode::SendZc::CODE),
+            // Buffer rings came with 5.19, as did UringCmd.
+            buf_ring: probe.is_supported(opcode::UringCmd16::CODE),
+            send_zc: probe.is_supported(opcode::SendZc::CODE),
+            msg_ring: probe.is_supported(opcode::MsgRingData::CODE),
+            socket: probe.is_supported(opcode::Socket::CODE),
+            futex: probe.is_supported(opcode::FutexWait::CODE),
+            ext_arg: params.is_feature_ext_arg(),
+            skip_success: params.is_feature_skip_cqe_on_success(),
+            recvsend_bundle: params.is_feature_recvsend_bundle(),
+        }
+    }
+
+    /// Whether [`Nop`](opcode::Nop) is supported, which is true of any kernel that supports
+    /// probing.
+    pub fn has_nop(&self) -> bool {
+        self.nop
+    }
+
+    /// Whether [`RecvMulti`](opcode::RecvMulti) is supported. Available since 6.0.
+    pub fn has_recv_multishot(&self) -> bool {
+        self.recv_multishot
+    }
+
+    /// Whether [`register_buf_ring`](crate::Submitter::register_buf_ring) is supported.
+    /// Available since 5.19.
+    pub fn has_buf_ring(&self) -> bool {
+        self.buf_ring
+    }
+
+    /// Whether [`SendZc`](opcode::SendZc) is supported. Available since 6.0.
+    pub fn has_send_zc(&self) -> bool {
+        self.send_zc
+    }
+
+    /// Whether [`MsgRingData`](opcode::MsgRingData) is supported. Available since 5.18.
+    pub fn has_msg_ring(&self) -> bool {
+        self.msg_ring
+    }
+
+    /// Whether [`Socket`](opcode::Socket) is supported. Available since 5.19.
+    pub fn has_socket(&self) -> bool {
+        self.socket
+    }
+
+    /// Whether the futex opcodes, such as [`FutexWait`](opcode::FutexWait), are supported.
+    /// Available since 6.7.
+    pub fn has_futex(&self) -> bool {
+        self.futex
+    }
+
+    /// See [`Parameters::is_feature_ext_arg`].
+    pub fn has_ext_arg(&self) -> bool {
+        self.ext_arg
+    }
+
+    /// See [`Parameters::is_feature_skip_cqe_on_success`].
+    pub fn has_skip_success(&self) -> bool {
+        self.skip_success
+    }
+
+    /// See [`Parameters::is_feature_recvsend_bundle`].
+    pub fn has_recvsend_bundle(&self) -> bool {
+        self.recvsend_bundle
+    }
+
+    fn to_bits(self) -> u16 {
+        [
+            self.nop,
+            self.recv_multishot,
+            self.buf_ring,
+            self.send_zc,
+            self.msg_ring,
+            self.socket,
+            self.futex,
+            self.ext_arg,
+            self.skip_success,
+            self.recvsend_bundle,
+        ]
+        .iter()
+        .enumerate()
+        .fold(0, |bits, (i, &set)| bits | (u16::from(set) << i))
+    }
+
+    fn from_bits(bits: u16) -> Capabilities {
+        let bit = |i: u16| bits & (1 << i) != 0;
+        Capabilities {
+            nop: bit(0),
+            recv_multishot: bit(1),
+            buf_ring: bit(2),
+            send_zc: bit(3),
+            msg_ring: bit(4),
+            socket: bit(5),
+            futex: bit(6),
+            ext_arg: bit(7),
+            skip_success: bit(8),
+            recvsend_bundle: bit(9),
+        }
+    }
+}
+
+/// A [`Capabilities`] computed on first use and kept for the life of the ring.
+///
+/// It is packed into an atomic so the ring stays `Sync` without `OnceLock`, which is newer than
+/// our MSRV. Threads racing to fill it in store the same value.
+pub(crate) struct CapabilitiesCache(atomic::AtomicU16);
+
+impl CapabilitiesCache {
+    /// Set once the cache holds a value.
+    const INIT: u16 = 1 << 15;
+
+    pub const fn new() -> CapabilitiesCache {
+        CapabilitiesCache(atomic::AtomicU16::new(0))
+    }
+
+    pub fn get_or_try_init<F>(&self, f: F) -> io::Result<Capabilities>
+    where
+        F: FnOnce() -> io::Result<Capabilities>,
+    {
+        let bits = self.0.load(atomic::Ordering::Relaxed);
+        if bits & Self::INIT != 0 {
+            return Ok(Capabilities::from_bits(bits));
+        }
 
-        f.debug_set().entries(list).finish()
+        let caps = f()?;
+        self.0
+            .store(caps.to_bits() | Self::INIT, atomic::Ordering::Relaxed);
+        Ok(caps)
     }
 }
 
@@ -128,6 +294,21 @@ impl Restriction {
     }
 }
 
+impl fmt::Debug for Restriction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = unsafe { self.0.__bindgen_anon_1.sqe_op };
+
+        let name = match u32::from(self.0.opcode) {
+            sys::IORING_RESTRICTION_REGISTER_OP => "RegisterOp",
+            sys::IORING_RESTRICTION_SQE_OP => "SqeOp",
+            sys::IORING_RESTRICTION_SQE_FLAGS_ALLOWED => "SqeFlagsAllowed",
+            sys::IORING_RESTRICTION_SQE_FLAGS_REQUIRED => "SqeFlagsRequired",
+            _ => "Unknown",
+        };
+        f.debug_tuple(name).field(&value).finish()
+    }
+}
+
 /// A RawFd, which can be used for
 /// [register_files_update](crate::Submitter::register_files_update).
 ///
@@ -151,3 +332,48 @@ fn test_probe_layout() {
         Layout::for_value(&probe.0).align()
     );
 }
+
+#[test]
+fn test_restriction_layout() {
+    use crate::squeue::Flags;
+
+    assert_eq!(
+        std::mem::size_of::<Restriction>(),
+        std::mem::size_of::<sys::io_uring_restriction>()
+    );
+
+    let cases = [
+        (
+            Restriction::register_op(sys::IORING_REGISTER_PROBE as _),
+            sys::IORING_RESTRICTION_REGISTER_OP,
+            sys::IORING_REGISTER_PROBE as u8,
+        ),
+        (
+            Restriction::sqe_op(opcode::Nop::CODE),
+            sys::IORING_RESTRICTION_SQE_OP,
+            opcode::Nop::CODE,
+        ),
+        (
+            Restriction::sqe_flags_allowed(Flags::IO_LINK.bits()),
+            sys::IORING_RESTRICTION_SQE_FLAGS_ALLOWED,
+            Flags::IO_LINK.bits(),
+        ),
+        (
+            Restriction::sqe_flags_required(Flags::FIXED_FILE.bits()),
+            sys::IORING_RESTRICTION_SQE_FLAGS_REQUIRED,
+            Flags::FIXED_FILE.bits(),
+        ),
+    ];
+
+    for (res, kind, value) in cases {
+        assert_eq!(u32::from(res.0.opcode), kind);
+        assert_eq!(unsafe { res.0.__bindgen_anon_1.sqe_op }, value);
+        assert_eq!(res.0.resv, 0);
+        assert_eq!(res.0.resv2, [0; 3]);
+    }
+
+    assert_eq!(
+        format!("{:?}", Restriction::sqe_op(opcode::Nop::CODE)),
+        format!("SqeOp({})", opcode::Nop::CODE)
+    );
+}
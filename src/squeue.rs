@@ -1,8 +1,10 @@
 //! Submission Queue
 
+use std::cmp;
 use std::error::Error;
 use std::fmt::{self, Debug, Display, Formatter};
 use std::mem;
+use std::slice;
 use std::sync::atomic;
 
 use crate::sys;
@@ -36,6 +38,7 @@ pub trait EntryMarker: Send + Sync + Clone + Debug + From<Entry> + private::Seal
 
     fn set_user_data(self, user_data: u64) -> Self;
     fn get_user_data(&self) -> u64;
+    fn set_flags(self, flags: Flags) -> Self;
 }
 
 /// A 64-byte submission queue entry (SQE), representing a request for an I/O operation.
@@ -114,6 +117,15 @@ bitflags! {
         const BUFFER_SELECT = 1 << sys::IOSQE_BUFFER_SELECT_BIT;
 
         /// Don't post CQE if request succeeded.
+        ///
+        /// Only a failing request posts a completion, so a chain of linked requests with this
+        /// flag set on all but the last one produces a single CQE when everything succeeds. Take
+        /// that into account when choosing how many completions to wait for in
+        /// [`Submitter::submit_and_wait`](crate::Submitter::submit_and_wait).
+        ///
+        /// Requires
+        /// [`is_feature_skip_cqe_on_success`](crate::Parameters::is_feature_skip_cqe_on_success),
+        /// available since 5.17.
         const SKIP_SUCCESS = 1 << sys::IOSQE_CQE_SKIP_SUCCESS_BIT;
     }
 }
@@ -131,13 +143,16 @@ impl<E: EntryMarker> Inner<E> {
         let ring_entries = sq_mmap.offset(p.sq_off.ring_entries).cast::<u32>().read();
         let flags        = sq_mmap.offset(p.sq_off.flags       ) as *const atomic::AtomicU32;
         let dropped      = sq_mmap.offset(p.sq_off.dropped     ) as *const atomic::AtomicU32;
-        let array        = sq_mmap.offset(p.sq_off.array       ) as *mut u32;
 
         let sqes         = sqe_mmap.as_mut_ptr() as *mut E;
 
-        // To keep it simple, map it directly to `sqes`.
-        for i in 0..ring_entries {
-            array.add(i as usize).write_volatile(i);
+        // To keep it simple, map it directly to `sqes`. Without the array, the kernel consumes
+        // `sqes` in ring order, which is the same mapping.
+        if p.flags & sys::IORING_SETUP_NO_SQARRAY == 0 {
+            let array = sq_mmap.offset(p.sq_off.array) as *mut u32;
+            for i in 0..ring_entries {
+                array.add(i as usize).write_volatile(i);
+            }
         }
 
         Self {
@@ -181,7 +196,10 @@ impl<E: EntryMarker> SubmissionQueue<'_, E> {
     }
 
     /// When [`is_setup_sqpoll`](crate::Parameters::is_setup_sqpoll) is set, whether the kernel
-    /// threads has gone to sleep and requires a system call to wake it up.
+    /// thread has gone to sleep and requires a system call to wake it up.
+    ///
+    /// [`Submitter::submit`](crate::Submitter::submit) and its variants check this themselves,
+    /// and only enter the kernel with `IORING_ENTER_SQ_WAKEUP` when it is set.
     ///
     /// A result of `false` is only meaningful if the function was called after the latest update
     /// to the queue head. Other interpretations could lead to a race condition where the kernel
@@ -226,6 +244,10 @@ impl<E: EntryMarker> SubmissionQueue<'_, E> {
 
     /// The number of invalid submission queue entries that have been encountered in the ring
     /// buffer.
+    ///
+    /// The kernel skips entries it can't read, such as ones whose index in the submission queue
+    /// array is out of range, and counts them here instead of posting a completion. The counter
+    /// only increases, and wraps around on overflow.
     pub fn dropped(&self) -> u32 {
         unsafe { (*self.queue.dropped).load(atomic::Ordering::Acquire) }
     }
@@ -261,11 +283,18 @@ impl<E: EntryMarker> SubmissionQueue<'_, E> {
         self.len() == 0
     }
 
+    /// Get the number of entries that can be pushed before the submission queue ring buffer is
+    /// full.
+    #[inline]
+    pub fn remaining(&self) -> usize {
+        self.capacity() - self.len()
+    }
+
     /// Returns `true` if the submission queue ring buffer has reached capacity, and no more events
     /// can be added before the kernel consumes some.
     #[inline]
     pub fn is_full(&self) -> bool {
-        self.len() == self.capacity()
+        self.remaining() == 0
     }
 
     /// Attempts to push an entry into the queue.
@@ -285,6 +314,24 @@ impl<E: EntryMarker> SubmissionQueue<'_, E> {
         }
     }
 
+    /// Attempts to push an entry into the queue.
+    /// If the queue is full, the entry is handed back so it can be pushed again later without
+    /// cloning it.
+    ///
+    /// # Safety
+    ///
+    /// Developers must ensure that parameters of the entry (such as buffer) are valid and will
+    /// be valid for the entire duration of the operation, otherwise it may cause memory problems.
+    #[inline]
+    pub unsafe fn push_checked(&mut self, entry: E) -> Result<(), E> {
+        if !self.is_full() {
+            self.push_unchecked(entry);
+            Ok(())
+        } else {
+            Err(entry)
+        }
+    }
+
     /// Attempts to push several entries into the queue.
     /// If the queue does not have space for all of the entries, an error is returned.
     ///
@@ -301,7 +348,7 @@ impl<E: EntryMarker> SubmissionQueue<'_, E> {
     {
         let iter = entries.into_iter();
 
-        if self.capacity() - self.len() < iter.len() {
+        if self.remaining() < iter.len() {
             return Err(PushError);
         }
 
@@ -312,6 +359,112 @@ impl<E: EntryMarker> SubmissionQueue<'_, E> {
         Ok(())
     }
 
+    /// Attempts to push several entries into the queue as a chain of linked requests.
+    ///
+    /// [`Flags::IO_LINK`] is set on every entry except the last, so each one only starts after
+    /// the previous one completes. If the queue does not have space for all of the entries, an
+    /// error is returned and none of them are pushed, so a chain is never split across
+    /// submissions.
+    ///
+    /// # Safety
+    ///
+    /// Developers must ensure that parameters of all the entries (such as buffer) are valid and
+    /// will be valid for the entire duration of the operation, otherwise it may cause memory
+    /// problems.
+    #[inline]
+    pub unsafe fn push_linked(&mut self, entries: &[E]) -> Result<(), PushError> {
+        if self.remaining() < entries.len() {
+            return Err(PushError);
+        }
+
+        if let Some((last, init)) = entries.split_last() {
+            for entry in init {
+                self.push_unchecked(entry.clone().set_flags(Flags::IO_LINK));
+            }
+            self.push_unchecked(last.clone());
+        }
+
+        Ok(())
+    }
+
+    /// Get the free slots of the submission queue ring buffer, so entries can be written
+    /// directly into ring memory instead of being built elsewhere and copied in by
+    /// [`push`](Self::push).
+    ///
+    /// The free slots may wrap around the end of the ring, so they are returned as two slices;
+    /// the second is only non-empty when the first reaches the end of the ring. Slots are in
+    /// submission order, and none of them are visible to the kernel until they are published
+    /// with [`commit`](Self::commit).
+    #[inline]
+    pub fn as_mut_slices(&mut self) -> (&mut [mem::MaybeUninit<E>], &mut [mem::MaybeUninit<E>]) {
+        let free = self.remaining();
+        let start = (self.tail & self.queue.ring_mask) as usize;
+        let first = cmp::min(free, self.capacity() - start);
+
+        // Safety: the slots in `[tail, head + ring_entries)` are not owned by the kernel, and
+        // the two ranges don't overlap.
+        unsafe {
+            let sqes = self.queue.sqes as *mut mem::MaybeUninit<E>;
+            (
+                slice::from_raw_parts_mut(sqes.add(start), first),
+                slice::from_raw_parts_mut(sqes, free - first),
+            )
+        }
+    }
+
+    /// Publish the first `n` slots returned by [`as_mut_slices`](Self::as_mut_slices), counting
+    /// through the first slice and then the second.
+    ///
+    /// Like entries added with [`push`](Self::push), they are seen by the kernel once the queue
+    /// is synchronized or dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is greater than the number of free slots.
+    ///
+    /// # Safety
+    ///
+    /// The first `n` free slots must have been initialized with entries, and developers must
+    /// ensure that parameters of those entries (such as buffer) are valid and will be valid for
+    /// the entire duration of the operation, otherwise it may cause memory problems.
+    #[inline]
+    pub unsafe fn commit(&mut self, n: usize) {
+        assert!(
+            n <= self.remaining(),
+            "committed more entries than free slots"
+        );
+        self.tail = self.tail.wrapping_add(n as u32);
+    }
+
+    /// Pushes as many entries as fit into the queue, returning how many were pushed.
+    ///
+    /// Unlike [`push_multiple`](Self::push_multiple), a queue without space for all of the entries
+    /// still accepts a prefix of them, so the caller can submit and retry with
+    /// `&entries[pushed..]`.
+    ///
+    /// # Safety
+    ///
+    /// Developers must ensure that parameters of all the entries (such as buffer) are valid and
+    /// will be valid for the entire duration of the operation, otherwise it may cause memory
+    /// problems.
+    #[inline]
+    pub unsafe fn push_partial(&mut self, entries: &[E]) -> usize {
+        let n = entries.len().min(self.remaining());
+
+        for entry in &entries[..n] {
+            self.push_unchecked(entry.clone());
+        }
+
+        n
+    }
+
+    /// Push an entry into the queue without checking whether the queue is full.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure the queue is not full, otherwise an entry that has not yet been
+    /// consumed by the kernel will be overwritten. The requirements of [`push`](Self::push) also
+    /// apply.
     #[inline]
     pub unsafe fn push_unchecked(&mut self, entry: E) {
         *self
@@ -372,6 +525,10 @@ impl EntryMarker for Entry {
     fn get_user_data(&self) -> u64 {
         self.get_user_data()
     }
+
+    fn set_flags(self, flags: Flags) -> Self {
+        self.flags(flags)
+    }
 }
 
 impl Clone for Entry {
@@ -408,11 +565,10 @@ impl Entry128 {
         self
     }
 
-
     /// Get the previously application-supplied user data.
     #[inline]
     pub fn get_user_data(&self) -> u64 {
-        self.0.0.user_data
+        self.0 .0.user_data
     }
 
     /// Set the personality of this event. You can obtain a personality using
@@ -436,6 +592,10 @@ impl EntryMarker for Entry128 {
     fn get_user_data(&self) -> u64 {
         self.get_user_data()
     }
+
+    fn set_flags(self, flags: Flags) -> Self {
+        self.flags(flags)
+    }
 }
 
 impl From<Entry> for Entry128 {
@@ -479,3 +639,168 @@ impl<E: EntryMarker> Debug for SubmissionQueue<'_, E> {
         d.finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::cell::UnsafeCell;
+
+    use super::*;
+    use crate::opcode;
+
+    /// A submission queue ring living in ordinary memory, standing in for the one shared with the
+    /// kernel.
+    struct FakeRing {
+        head: atomic::AtomicU32,
+        tail: atomic::AtomicU32,
+        flags: atomic::AtomicU32,
+        dropped: atomic::AtomicU32,
+        sqes: Box<[UnsafeCell<Entry>]>,
+    }
+
+    impl FakeRing {
+        fn new(entries: u32) -> FakeRing {
+            assert!(entries.is_power_of_two());
+
+            FakeRing {
+                head: atomic::AtomicU32::new(0),
+                tail: atomic::AtomicU32::new(0),
+                flags: atomic::AtomicU32::new(0),
+                dropped: atomic::AtomicU32::new(0),
+                sqes: (0..entries)
+                    .map(|_| UnsafeCell::new(opcode::Nop::new().build()))
+                    .collect(),
+            }
+        }
+
+        fn inner(&self) -> Inner<Entry> {
+            Inner {
+                head: &self.head,
+                tail: &self.tail,
+                ring_mask: self.sqes.len() as u32 - 1,
+                ring_entries: self.sqes.len() as u32,
+                flags: &self.flags,
+                dropped: &self.dropped,
+                sqes: self.sqes.as_ptr() as *mut Entry,
+            }
+        }
+
+        /// The entry in slot `index`, as the kernel would read it.
+        fn sqe(&self, index: usize) -> Entry {
+            unsafe { (*self.sqes[index].get()).clone() }
+        }
+    }
+
+    #[test]
+    fn test_push_linked() {
+        let ring = FakeRing::new(4);
+        let inner = ring.inner();
+        let mut sq = unsafe { inner.borrow_shared() };
+
+        let chain = [
+            opcode::Nop::new().build().user_data(1),
+            opcode::Nop::new().build().user_data(2),
+            opcode::Nop::new().build().user_data(3).flags(Flags::ASYNC),
+        ];
+        unsafe { sq.push_linked(&chain) }.unwrap();
+        assert_eq!(sq.len(), 3);
+
+        // Too large for the one remaining slot, so nothing is pushed.
+        assert_eq!(unsafe { sq.push_linked(&chain[..2]) }, Err(PushError));
+        assert_eq!(sq.len(), 3);
+        sq.sync();
+        drop(sq);
+
+        assert_eq!(ring.tail.load(atomic::Ordering::Relaxed), 3);
+        let flags: Vec<_> = (0..3)
+            .map(|i| Flags::from_bits_retain(ring.sqe(i).0.flags))
+            .collect();
+        assert_eq!(flags, [Flags::IO_LINK, Flags::IO_LINK, Flags::ASYNC]);
+        assert_eq!(ring.sqe(3).0.flags, 0);
+    }
+
+    #[test]
+    fn test_push_checked() {
+        let ring = FakeRing::new(2);
+        let inner = ring.inner();
+        let mut sq = unsafe { inner.borrow_shared() };
+
+        for user_data in 0..2 {
+            let sqe = opcode::Nop::new().build().user_data(user_data);
+            unsafe { sq.push_checked(sqe) }.unwrap();
+        }
+        assert!(sq.is_full());
+
+        let sqe = opcode::Nop::new()
+            .build()
+            .user_data(0x42)
+            .flags(Flags::IO_LINK);
+        let rejected = unsafe { sq.push_checked(sqe) }.unwrap_err();
+        assert_eq!(rejected.get_user_data(), 0x42);
+        assert_eq!(rejected.0.flags, Flags::IO_LINK.bits());
+        assert_eq!(rejected.0.opcode, opcode::Nop::CODE);
+        assert_eq!(sq.len(), 2);
+
+        // Once the kernel consumes an entry, the rejected one can be pushed again.
+        ring.head.store(1, atomic::Ordering::Release);
+        sq.sync();
+        unsafe { sq.push_checked(rejected) }.unwrap();
+        sq.sync();
+        drop(sq);
+
+        assert_eq!(ring.tail.load(atomic::Ordering::Relaxed), 3);
+        assert_eq!(ring.sqe(0).get_user_data(), 0x42);
+    }
+
+    #[test]
+    fn test_as_mut_slices() {
+        let ring = FakeRing::new(4);
+        ring.head.store(3, atomic::Ordering::Relaxed);
+        ring.tail.store(3, atomic::Ordering::Relaxed);
+        let inner = ring.inner();
+        let mut sq = unsafe { inner.borrow_shared() };
+
+        // The free slots wrap around the end of the ring.
+        let (first, second) = sq.as_mut_slices();
+        assert_eq!((first.len(), second.len()), (1, 3));
+        let slots = first.iter_mut().chain(second.iter_mut());
+        for (user_data, slot) in (1..=3).zip(slots) {
+            slot.write(opcode::Nop::new().build().user_data(user_data));
+        }
+        unsafe { sq.commit(3) };
+        assert_eq!(sq.len(), 3);
+
+        let (first, second) = sq.as_mut_slices();
+        assert_eq!((first.len(), second.len()), (1, 0));
+        sq.sync();
+        drop(sq);
+
+        assert_eq!(ring.tail.load(atomic::Ordering::Relaxed), 6);
+        let user_data: Vec<_> = [3, 0, 1]
+            .iter()
+            .map(|&i| ring.sqe(i).get_user_data())
+            .collect();
+        assert_eq!(user_data, [1, 2, 3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "committed more entries than free slots")]
+    fn test_commit_overflow() {
+        let ring = FakeRing::new(2);
+        let inner = ring.inner();
+        let mut sq = unsafe { inner.borrow_shared() };
+        unsafe { sq.commit(3) };
+    }
+
+    #[test]
+    fn test_dropped() {
+        let ring = FakeRing::new(2);
+        let inner = ring.inner();
+        let sq = unsafe { inner.borrow_shared() };
+        assert_eq!(sq.dropped(), 0);
+
+        // Read straight from the ring, without needing a sync.
+        ring.dropped.fetch_add(3, atomic::Ordering::Release);
+        assert_eq!(sq.dropped(), 3);
+        assert_eq!(sq.len(), 0);
+    }
+}
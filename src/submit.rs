@@ -1,11 +1,13 @@
+use std::convert::TryFrom;
 use std::os::unix::io::{AsRawFd, RawFd};
 use std::sync::atomic;
+use std::time::Duration;
 use std::{io, mem, ptr};
 
 use crate::register::{execute, Probe};
 use crate::sys;
-use crate::types::{CancelBuilder, Timespec};
-use crate::util::{cast_ptr, OwnedFd};
+use crate::types::{CancelBuilder, NapiConfig, Timespec};
+use crate::util::{cast_ptr, OwnedFd, RegisteredRingFd};
 use crate::Parameters;
 
 use crate::register::Restriction;
@@ -20,27 +22,39 @@ use crate::types;
 pub struct Submitter<'a> {
     fd: &'a OwnedFd,
     params: &'a Parameters,
+    /// `None` for the halves of [`IoUring::into_split`](crate::IoUring::into_split), which
+    /// always use the fd.
+    registered_ring_fd: Option<&'a RegisteredRingFd>,
 
     sq_head: *const atomic::AtomicU32,
     sq_tail: *const atomic::AtomicU32,
     sq_flags: *const atomic::AtomicU32,
+    cq_head: *const atomic::AtomicU32,
+    cq_tail: *const atomic::AtomicU32,
 }
 
 impl<'a> Submitter<'a> {
     #[inline]
+    #[allow(clippy::too_many_arguments)]
     pub(crate) const fn new(
         fd: &'a OwnedFd,
         params: &'a Parameters,
+        registered_ring_fd: Option<&'a RegisteredRingFd>,
         sq_head: *const atomic::AtomicU32,
         sq_tail: *const atomic::AtomicU32,
         sq_flags: *const atomic::AtomicU32,
+        cq_head: *const atomic::AtomicU32,
+        cq_tail: *const atomic::AtomicU32,
     ) -> Submitter<'a> {
         Submitter {
             fd,
             params,
+            registered_ring_fd,
             sq_head,
             sq_tail,
             sq_flags,
+            cq_head,
+            cq_tail,
         }
     }
 
@@ -63,6 +77,17 @@ impl<'a> Submitter<'a> {
         }
     }
 
+    /// The number of completions in the CQ ring, as of the last time the completion queue synced.
+    #[inline]
+    fn cq_len(&self) -> usize {
+        unsafe {
+            let head = (*self.cq_head).load(atomic::Ordering::Acquire);
+            let tail = (*self.cq_tail).load(atomic::Ordering::Acquire);
+
+            tail.wrapping_sub(head) as usize
+        }
+    }
+
     /// CQ ring is overflown
     fn sq_cq_overflow(&self) -> bool {
         unsafe {
@@ -78,6 +103,10 @@ impl<'a> Submitter<'a> {
     /// You will probably want to use a more high-level API such as
     /// [`submit`](Self::submit) or [`submit_and_wait`](Self::submit_and_wait).
     ///
+    /// If the ring fd has been registered with [`register_ring_fd`](Self::register_ring_fd) by
+    /// the calling thread, the registered index is used in place of the fd and
+    /// `IORING_ENTER_REGISTERED_RING` is added to `flag`. Other threads use the fd.
+    ///
     /// # Safety
     ///
     /// This provides a raw interface so the developer must ensure that parameters are correct.
@@ -92,18 +121,41 @@ impl<'a> Submitter<'a> {
             .map(|arg| cast_ptr(arg).cast())
             .unwrap_or_else(ptr::null);
         let size = mem::size_of::<T>();
-        sys::io_uring_enter(
-            self.fd.as_raw_fd(),
-            to_submit,
-            min_complete,
-            flag,
-            arg,
-            size,
-        )
-        .map(|res| res as _)
+        let (fd, flag) = match self.registered_ring_fd.and_then(RegisteredRingFd::get) {
+            Some(index) => (index as _, flag | sys::IORING_ENTER_REGISTERED_RING),
+            None => (self.fd.as_raw_fd(), flag),
+        };
+        sys::io_uring_enter(fd, to_submit, min_complete, flag, arg, size).map(|res| res as _)
+    }
+
+    /// Like [`enter`](Self::enter), but with typed [`EnterFlags`](types::EnterFlags).
+    ///
+    /// This is useful to drive the ring by hand, for example to wake up the poll thread of a
+    /// [`setup_sqpoll`](crate::Builder::setup_sqpoll) ring with
+    /// [`SQ_WAKEUP`](types::EnterFlags::SQ_WAKEUP) without going through
+    /// [`submit`](Self::submit). As with `enter`, the registered ring index is used if the
+    /// calling thread registered it.
+    ///
+    /// # Safety
+    ///
+    /// This provides a raw interface so the developer must ensure that parameters are correct,
+    /// and in particular that `arg` matches what `flags` tell the kernel to expect.
+    #[inline]
+    pub unsafe fn enter_raw<T: Sized>(
+        &self,
+        to_submit: u32,
+        min_complete: u32,
+        flags: types::EnterFlags,
+        arg: Option<&T>,
+    ) -> io::Result<usize> {
+        self.enter(to_submit, min_complete, flags.bits(), arg)
     }
 
     /// Submit all queued submission queue events to the kernel.
+    ///
+    /// With [`setup_sqpoll`](crate::Builder::setup_sqpoll), the poll thread submits the entries
+    /// itself, so this only makes a system call when
+    /// [`need_wakeup`](crate::SubmissionQueue::need_wakeup) says the thread is asleep.
     #[inline]
     pub fn submit(&self) -> io::Result<usize> {
         self.submit_and_wait(0)
@@ -111,6 +163,9 @@ impl<'a> Submitter<'a> {
 
     /// Submit all queued submission queue events to the kernel and wait for at least `want`
     /// completion events to complete.
+    ///
+    /// Entries flagged with [`Flags::SKIP_SUCCESS`](crate::squeue::Flags::SKIP_SUCCESS) don't
+    /// post a completion when they succeed, so they should not be counted in `want`.
     pub fn submit_and_wait(&self, want: usize) -> io::Result<usize> {
         let len = self.sq_len();
         let mut flags = 0;
@@ -169,11 +224,112 @@ impl<'a> Submitter<'a> {
         unsafe { self.enter(len as _, want as _, flags, Some(&args.args)) }
     }
 
+    /// Submit all queued submission queue events to the kernel and wait for at least `want`
+    /// completion events to complete, or for `timeout` to elapse, whichever comes first.
+    ///
+    /// `sigmask`, if set, replaces the signal mask of the thread while it waits, like
+    /// `pselect(2)`.
+    ///
+    /// Unlike [`submit_with_args`](Self::submit_with_args), an expired timeout is not an error:
+    /// this returns `Ok` with the number of submitted entries, and the completions that did arrive
+    /// can be read from the completion queue.
+    ///
+    /// Requires [`is_feature_ext_arg`](crate::Parameters::is_feature_ext_arg), available since 5.11.
+    pub fn submit_and_wait_timeout(
+        &self,
+        want: usize,
+        timeout: &Timespec,
+        sigmask: Option<&libc::sigset_t>,
+    ) -> io::Result<usize> {
+        let args = types::SubmitArgs::new().timespec(timeout);
+        let res = match sigmask {
+            Some(sigmask) => self.submit_with_args(want, &args.sigmask(sigmask)),
+            None => self.submit_with_args(want, &args),
+        };
+
+        match res {
+            // The kernel only reports the timeout if nothing was submitted.
+            Err(err) if err.raw_os_error() == Some(libc::ETIME) => Ok(0),
+            res => res,
+        }
+    }
+
+    /// Submit all queued submission queue events to the kernel and wait for `want` completion
+    /// events, but only for up to `min_wait` if at least one completion has arrived by then.
+    ///
+    /// This lets the kernel batch completions under load without waiting for a full batch that
+    /// might never come. `min_wait` is capped at `u32::MAX` microseconds.
+    ///
+    /// Requires [`is_feature_min_timeout`](crate::Parameters::is_feature_min_timeout),
+    /// available since 6.12.
+    pub fn submit_and_wait_min(&self, want: usize, min_wait: Duration) -> io::Result<usize> {
+        let min_wait_usec = u32::try_from(min_wait.as_micros()).unwrap_or(u32::MAX);
+        let args = types::SubmitArgs::new().min_wait_usec(min_wait_usec);
+        self.submit_with_args(want, &args)
+    }
+
     /// Wait for the submission queue to have free entries.
     pub fn squeue_wait(&self) -> io::Result<usize> {
         unsafe { self.enter::<libc::sigset_t>(0, 0, sys::IORING_ENTER_SQ_WAIT, None) }
     }
 
+    /// Move completions that overflowed the completion queue into it, returning how many became
+    /// visible.
+    ///
+    /// When the CQ ring is full, the kernel holds further completions in an internal backlog
+    /// (see [`is_feature_nodrop`](crate::Parameters::is_feature_nodrop)) and sets
+    /// [`SubmissionQueue::cq_overflow`](crate::SubmissionQueue::cq_overflow). Only as many as there
+    /// is free space for are moved, so drain the completion queue first and call this again until
+    /// it returns 0. Completions that were dropped outright are counted by
+    /// [`CompletionQueue::overflow`](crate::CompletionQueue::overflow) instead.
+    ///
+    /// The returned count also includes any completion posted concurrently with the flush.
+    pub fn flush_overflow(&self) -> io::Result<u32> {
+        if !self.sq_cq_overflow() {
+            return Ok(0);
+        }
+
+        let tail = unsafe { (*self.cq_tail).load(atomic::Ordering::Acquire) };
+        unsafe {
+            self.enter::<libc::sigset_t>(0, 0, sys::IORING_ENTER_GETEVENTS, None)?;
+        }
+        let new_tail = unsafe { (*self.cq_tail).load(atomic::Ordering::Acquire) };
+
+        Ok(new_tail.wrapping_sub(tail))
+    }
+
+    /// Poll for completions on a ring set up with [`setup_iopoll`](crate::Builder::setup_iopoll),
+    /// entering the kernel until at least `want` are in the completion queue. Returns how many
+    /// there are, as counted from the last time the completion queue was synced.
+    ///
+    /// The kernel does not poll while there are completions left to reap, so if the queue holds
+    /// some but fewer than `want`, this returns early instead of spinning; consume them and call
+    /// it again. Submission queue entries are not submitted.
+    ///
+    /// Fails with [`InvalidInput`](io::ErrorKind::InvalidInput) if the ring was not set up with
+    /// `IORING_SETUP_IOPOLL`.
+    pub fn iopoll_wait(&self, want: usize) -> io::Result<usize> {
+        if !self.params.is_setup_iopoll() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "iopoll_wait requires setup_iopoll",
+            ));
+        }
+
+        loop {
+            let ready = self.cq_len();
+
+            // With SQPOLL, the kernel thread does the polling and entering waits as usual.
+            if ready >= want || (ready > 0 && !self.params.is_setup_sqpoll()) {
+                return Ok(ready);
+            }
+
+            unsafe {
+                self.enter::<libc::sigset_t>(0, want as _, sys::IORING_ENTER_GETEVENTS, None)?;
+            }
+        }
+    }
+
     /// Register in-memory fixed buffers for I/O with the kernel. You can use these buffers with the
     /// [`ReadFixed`](crate::opcode::ReadFixed) and [`WriteFixed`](crate::opcode::WriteFixed)
     /// operations.
@@ -271,10 +427,41 @@ impl<'a> Submitter<'a> {
         .map(drop)
     }
 
+    /// Variant of [`register_buffers2`](Self::register_buffers2) that splits one contiguous region
+    /// into `tags.len()` fixed buffers of `slot_len` bytes each, the buffer at index `i` starting
+    /// at `base + i * slot_len`.
+    ///
+    /// This suits a single large (e.g. huge-page backed) allocation that is carved into logical
+    /// slots, while still receiving a tagged CQE for each slot once it is released.
+    ///
+    /// Available since Linux 5.13.
+    ///
+    /// # Safety
+    ///
+    /// Developers must ensure that `base` points to at least `slot_len * tags.len()` bytes which
+    /// will be valid until buffers are unregistered or the ring destroyed, otherwise undefined
+    /// behaviour may occur.
+    pub unsafe fn register_buffer_slots(
+        &self,
+        base: *mut u8,
+        slot_len: usize,
+        tags: &[u64],
+    ) -> io::Result<()> {
+        let bufs = (0..tags.len())
+            .map(|i| libc::iovec {
+                iov_base: base.add(i * slot_len).cast(),
+                iov_len: slot_len,
+            })
+            .collect::<Vec<_>>();
+        self.register_buffers2(&bufs, tags)
+    }
+
     /// Registers an empty table of `nr` fixed buffers.
     ///
     /// These must be updated before use; e.g. using
-    /// [`register_buffers_update`](Self::register_buffers_update).
+    /// [`register_buffers_update`](Self::register_buffers_update), which can fill individual
+    /// slots and optionally tag them, so buffers can be rotated without re-registering the
+    /// whole table.
     ///
     /// See [`register_buffers`](Self::register_buffers)
     /// for more information about fixed buffers.
@@ -299,7 +486,10 @@ impl<'a> Submitter<'a> {
     /// available in kernels 5.19 and later.
     ///
     /// Registering a file table is a prerequisite for using any request that
-    /// uses direct descriptors.
+    /// uses direct descriptors. The empty slots can be filled later with
+    /// [`register_files_update`](Self::register_files_update), or by requests that install
+    /// a file directly into the table, such as [`Accept`](crate::opcode::Accept) with
+    /// a [`DestinationSlot`](crate::types::DestinationSlot).
     pub fn register_files_sparse(&self, nr: u32) -> io::Result<()> {
         let rr = sys::io_uring_rsrc_register {
             nr,
@@ -357,7 +547,54 @@ impl<'a> Submitter<'a> {
         Ok(ret as _)
     }
 
+    /// Variant of [`register_files_update`](Self::register_files_update)
+    /// with resource tagging.
+    ///
+    /// `tags` must be the same length as `fds` and contain the tag value corresponding to the
+    /// file at the same index, otherwise this fails with
+    /// [`InvalidInput`](io::ErrorKind::InvalidInput) without updating any file. Once a tagged file
+    /// has been replaced or unregistered and is no longer in use, a CQE is posted with `user_data`
+    /// set to its tag and all other fields zeroed. A tag of zero disables this for that file.
+    ///
+    /// Returns the number of files updated.
+    ///
+    /// Available since Linux 5.13.
+    pub fn register_files_update_tag(
+        &self,
+        offset: u32,
+        fds: &[RawFd],
+        tags: &[u64],
+    ) -> io::Result<usize> {
+        if fds.len() != tags.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "register_files_update_tag requires a tag for each file",
+            ));
+        }
+
+        let rr = sys::io_uring_rsrc_update2 {
+            nr: fds.len() as _,
+            data: fds.as_ptr() as _,
+            tags: tags.as_ptr() as _,
+            offset,
+            ..Default::default()
+        };
+
+        let ret = execute(
+            self.fd.as_raw_fd(),
+            sys::IORING_REGISTER_FILES_UPDATE2,
+            cast_ptr::<sys::io_uring_rsrc_update2>(&rr).cast(),
+            mem::size_of::<sys::io_uring_rsrc_update2>() as _,
+        )?;
+        Ok(ret as _)
+    }
+
     /// Register an eventfd created by [`eventfd`](libc::eventfd) with the io_uring instance.
+    ///
+    /// The eventfd is signalled as completions are posted, so an external event loop can wait on
+    /// it and then drain the completion queue. Only one eventfd can be registered at a time; a
+    /// second registration fails with `EBUSY` until
+    /// [`unregister_eventfd`](Self::unregister_eventfd) is called.
     pub fn register_eventfd(&self, eventfd: RawFd) -> io::Result<()> {
         execute(
             self.fd.as_raw_fd(),
@@ -485,6 +722,9 @@ impl<'a> Submitter<'a> {
     /// an operation not on the allowlist will fail with `-EACCES`.
     ///
     /// This can only be called once, to prevent untrusted code from removing restrictions.
+    ///
+    /// The kernel rejects the whole set with `-EINVAL` if a restriction names an opcode it doesn't
+    /// know of, leaving the ring unrestricted.
     pub fn register_restrictions(&self, res: &mut [Restriction]) -> io::Result<()> {
         execute(
             self.fd.as_raw_fd(),
@@ -510,6 +750,8 @@ impl<'a> Submitter<'a> {
     /// Tell io_uring on what CPUs the async workers can run. By default, async workers
     /// created by io_uring will inherit the CPU mask of its parent. This is usually
     /// all the CPUs in the system, unless the parent is being run with a limited set.
+    ///
+    /// Available since 5.14.
     pub fn register_iowq_aff(&self, cpu_set: &libc::cpu_set_t) -> io::Result<()> {
         execute(
             self.fd.as_raw_fd(),
@@ -521,6 +763,8 @@ impl<'a> Submitter<'a> {
     }
 
     /// Undoes a CPU mask previously set with [register_iowq_aff](Self::register_iowq_aff)
+    ///
+    /// Available since 5.14.
     pub fn unregister_iowq_aff(&self) -> io::Result<()> {
         execute(
             self.fd.as_raw_fd(),
@@ -538,6 +782,8 @@ impl<'a> Submitter<'a> {
     /// which carry out I/O operations that can never complete, for instance I/O
     /// on sockets. Passing `0` does not change the current limit. Returns
     /// previous limits on success.
+    ///
+    /// Available since 5.15.
     pub fn register_iowq_max_workers(&self, max: &mut [u32; 2]) -> io::Result<()> {
         execute(
             self.fd.as_raw_fd(),
@@ -606,6 +852,107 @@ impl<'a> Submitter<'a> {
         .map(drop)
     }
 
+    /// Get the head of the buffer ring registered for `bgid`, i.e. the index of the next buffer
+    /// the kernel will pick. It wraps around like the tail, so the number of buffers the kernel
+    /// has consumed is the difference to the previous head.
+    ///
+    /// Available since 6.8.
+    pub fn register_pbuf_status(&self, bgid: u16) -> io::Result<u32> {
+        let mut arg = sys::io_uring_buf_status {
+            buf_group: bgid as _,
+            ..Default::default()
+        };
+        execute(
+            self.fd.as_raw_fd(),
+            sys::IORING_REGISTER_PBUF_STATUS,
+            (&mut arg as *mut sys::io_uring_buf_status).cast(),
+            1,
+        )?;
+        Ok(arg.head)
+    }
+
+    /// Register the ring fd with the calling task, so that subsequent calls to
+    /// [`enter`](Self::enter) (and thus [`submit`](Self::submit) and friends) pass a registered
+    /// index instead of the fd, which saves looking up the file on every call.
+    ///
+    /// Returns the registered index. It is stored on the [`IoUring`](crate::IoUring) and used
+    /// automatically until [`unregister_ring_fd`](Self::unregister_ring_fd) is called, or the ring
+    /// is dropped.
+    ///
+    /// The registration belongs to the task (thread) that made it. The index means nothing on
+    /// other threads, or even names another ring that thread registered, so only the
+    /// registering thread uses it; the ring can still be entered from other threads, which go
+    /// through the fd as usual. Returns `EEXIST` if the ring fd is already registered, by any
+    /// thread.
+    ///
+    /// The halves of [`IoUring::into_split`](crate::IoUring::into_split) are meant to be used from
+    /// different threads, so they always use the fd, and registering through them fails with
+    /// [`Unsupported`](io::ErrorKind::Unsupported).
+    ///
+    /// Available since 5.18.
+    pub fn register_ring_fd(&self) -> io::Result<u32> {
+        let registered_ring_fd = match self.registered_ring_fd {
+            Some(registered_ring_fd) => registered_ring_fd,
+            None => {
+                return Err(io::Error::new(
+                    io::ErrorKind::Unsupported,
+                    "the ring fd can't be registered through a split half",
+                ))
+            }
+        };
+        if registered_ring_fd.is_registered() {
+            return Err(io::Error::from_raw_os_error(libc::EEXIST));
+        }
+
+        // An offset of -1 lets the kernel pick a free slot, which it then writes back.
+        let mut arg = sys::io_uring_rsrc_update {
+            offset: u32::MAX,
+            resv: 0,
+            data: self.fd.as_raw_fd() as _,
+        };
+        execute(
+            self.fd.as_raw_fd(),
+            sys::IORING_REGISTER_RING_FDS,
+            (&mut arg as *mut sys::io_uring_rsrc_update).cast(),
+            1,
+        )?;
+
+        registered_ring_fd.set(arg.offset);
+        Ok(arg.offset)
+    }
+
+    /// Unregister the ring fd registered with [`register_ring_fd`](Self::register_ring_fd),
+    /// after which the fd is used to enter the ring again.
+    ///
+    /// Returns `EINVAL` if the ring fd is not registered by the calling thread, as only that
+    /// thread can unregister it.
+    ///
+    /// Available since 5.18.
+    pub fn unregister_ring_fd(&self) -> io::Result<()> {
+        let registered = self
+            .registered_ring_fd
+            .and_then(|registered_ring_fd| Some((registered_ring_fd, registered_ring_fd.get()?)));
+        let (registered_ring_fd, index) = match registered {
+            Some(registered) => registered,
+            None => return Err(io::Error::from_raw_os_error(libc::EINVAL)),
+        };
+
+        let arg = sys::io_uring_rsrc_update {
+            offset: index as _,
+            resv: 0,
+            data: 0,
+        };
+        execute(
+            self.fd.as_raw_fd(),
+            sys::IORING_UNREGISTER_RING_FDS,
+            cast_ptr::<sys::io_uring_rsrc_update>(&arg).cast(),
+            1,
+        )?;
+
+        registered_ring_fd.clear();
+        Ok(())
+    }
+
     /// Performs a synchronous cancellation request, similar to [AsyncCancel](crate::opcode::AsyncCancel),
     /// except that it completes synchronously.
     ///
@@ -660,4 +1007,56 @@ impl<'a> Submitter<'a> {
         )
         .map(drop)
     }
+
+    /// Restrict the slots allocated by the kernel, e.g. for
+    /// [`DestinationSlot::auto_target`](crate::types::DestinationSlot::auto_target) or
+    /// [`AcceptMulti::allocate_file_index`](crate::opcode::AcceptMulti::allocate_file_index), to
+    /// the `len` slots starting at `offset` of the registered file table. The other slots can then
+    /// be managed by hand, without being taken by auto allocation.
+    ///
+    /// The range must fit into the registered file table.
+    ///
+    /// Available since 6.0.
+    pub fn register_file_alloc_range(&self, offset: u32, len: u32) -> io::Result<()> {
+        let arg = sys::io_uring_file_index_range {
+            off: offset,
+            len,
+            resv: 0,
+        };
+        execute(
+            self.fd.as_raw_fd(),
+            sys::IORING_REGISTER_FILE_ALLOC_RANGE,
+            cast_ptr::<sys::io_uring_file_index_range>(&arg).cast(),
+            0,
+        )
+        .map(drop)
+    }
+
+    /// Enable NAPI busy polling of the sockets used by the ring, with the settings in `napi`.
+    /// Returns the previous settings in `napi` on success.
+    ///
+    /// Available since 6.9.
+    pub fn register_napi(&self, napi: &mut NapiConfig) -> io::Result<()> {
+        execute(
+            self.fd.as_raw_fd(),
+            sys::IORING_REGISTER_NAPI,
+            (&mut napi.0 as *mut sys::io_uring_napi).cast(),
+            1,
+        )
+        .map(drop)
+    }
+
+    /// Disable NAPI busy polling, and return the settings it was using.
+    ///
+    /// Available since 6.9.
+    pub fn unregister_napi(&self) -> io::Result<NapiConfig> {
+        let mut napi = NapiConfig::new();
+        execute(
+            self.fd.as_raw_fd(),
+            sys::IORING_UNREGISTER_NAPI,
+            (&mut napi.0 as *mut sys::io_uring_napi).cast(),
+            1,
+        )?;
+        Ok(napi)
+    }
 }
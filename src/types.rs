@@ -1,15 +1,10 @@
 //! Common Linux types not provided by libc.
 
 pub(crate) mod sealed {
+    pub use super::Target;
     use super::{Fd, Fixed};
     use std::os::unix::io::RawFd;
 
-    #[derive(Debug)]
-    pub enum Target {
-        Fd(RawFd),
-        Fixed(u32),
-    }
-
     pub trait UseFd: Sized {
         fn into(self) -> RawFd;
     }
@@ -38,15 +33,25 @@ pub(crate) mod sealed {
             Target::Fixed(self.0)
         }
     }
+
+    impl UseFixed for Target {
+        #[inline]
+        fn into(self) -> Target {
+            self
+        }
+    }
 }
 
 use crate::sys;
-use crate::util::{cast_ptr, unwrap_nonzero, unwrap_u32};
+use crate::util::{cast_ptr, unwrap_nonzero, unwrap_u32, Mmap};
 use bitflags::bitflags;
 use std::convert::TryFrom;
 use std::marker::PhantomData;
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
 use std::num::NonZeroU32;
 use std::os::unix::io::RawFd;
+use std::sync::atomic;
+use std::{fmt, io, mem, ptr};
 
 pub use sys::__kernel_rwf_t as RwFlags;
 
@@ -57,6 +62,134 @@ pub struct statx {
     _priv: (),
 }
 
+/// A `statx` buffer for [`Statx`](crate::opcode::Statx), with typed accessors for the result.
+///
+/// Each accessor returns `None` if the kernel did not report the field in [`mask`](Self::mask),
+/// so reading a buffer before the request completes is safe, just uninformative.
+/// Use [`as_mut_ptr`](Self::as_mut_ptr) to pass the buffer to the opcode.
+///
+/// Only available on glibc targets, where `libc` defines `statx`.
+#[cfg(target_env = "gnu")]
+#[repr(transparent)]
+pub struct StatxBuf(libc::statx);
+
+#[cfg(target_env = "gnu")]
+impl StatxBuf {
+    pub fn new() -> Self {
+        StatxBuf(unsafe { mem::zeroed() })
+    }
+
+    /// The pointer to pass as the `statxbuf` of [`Statx`](crate::opcode::Statx).
+    ///
+    /// The buffer must stay valid and must not move until the request completes.
+    pub fn as_mut_ptr(&mut self) -> *mut statx {
+        &mut self.0 as *mut libc::statx as *mut statx
+    }
+
+    /// The raw `statx` struct.
+    pub fn as_raw(&self) -> &libc::statx {
+        &self.0
+    }
+
+    /// The `STATX_*` mask of fields the kernel filled in.
+    pub fn mask(&self) -> u32 {
+        self.0.stx_mask
+    }
+
+    fn has(&self, field: u32) -> bool {
+        self.0.stx_mask & field == field
+    }
+
+    /// The file type and mode bits, see `inode(7)`.
+    pub fn mode(&self) -> Option<u16> {
+        self.has(libc::STATX_TYPE | libc::STATX_MODE)
+            .then_some(self.0.stx_mode)
+    }
+
+    /// The size of the file in bytes.
+    pub fn size(&self) -> Option<u64> {
+        self.has(libc::STATX_SIZE).then_some(self.0.stx_size)
+    }
+
+    /// The number of 512 byte blocks allocated to the file.
+    pub fn blocks(&self) -> Option<u64> {
+        self.has(libc::STATX_BLOCKS).then_some(self.0.stx_blocks)
+    }
+
+    /// The inode number.
+    pub fn ino(&self) -> Option<u64> {
+        self.has(libc::STATX_INO).then_some(self.0.stx_ino)
+    }
+
+    /// The number of hard links.
+    pub fn nlink(&self) -> Option<u32> {
+        self.has(libc::STATX_NLINK).then_some(self.0.stx_nlink)
+    }
+
+    /// The owner user ID.
+    pub fn uid(&self) -> Option<u32> {
+        self.has(libc::STATX_UID).then_some(self.0.stx_uid)
+    }
+
+    /// The owner group ID.
+    pub fn gid(&self) -> Option<u32> {
+        self.has(libc::STATX_GID).then_some(self.0.stx_gid)
+    }
+
+    /// The last access time.
+    pub fn atime(&self) -> Option<std::time::SystemTime> {
+        self.has(libc::STATX_ATIME)
+            .then(|| statx_time(&self.0.stx_atime))
+    }
+
+    /// The last modification time.
+    pub fn mtime(&self) -> Option<std::time::SystemTime> {
+        self.has(libc::STATX_MTIME)
+            .then(|| statx_time(&self.0.stx_mtime))
+    }
+
+    /// The last status change time.
+    pub fn ctime(&self) -> Option<std::time::SystemTime> {
+        self.has(libc::STATX_CTIME)
+            .then(|| statx_time(&self.0.stx_ctime))
+    }
+
+    /// The creation time, if the filesystem records it.
+    pub fn btime(&self) -> Option<std::time::SystemTime> {
+        self.has(libc::STATX_BTIME)
+            .then(|| statx_time(&self.0.stx_btime))
+    }
+}
+
+#[cfg(target_env = "gnu")]
+fn statx_time(ts: &libc::statx_timestamp) -> std::time::SystemTime {
+    let nsec = std::time::Duration::from_nanos(ts.tv_nsec.into());
+    if ts.tv_sec >= 0 {
+        std::time::UNIX_EPOCH + std::time::Duration::from_secs(ts.tv_sec as u64) + nsec
+    } else {
+        std::time::UNIX_EPOCH - std::time::Duration::from_secs(ts.tv_sec.unsigned_abs()) + nsec
+    }
+}
+
+#[cfg(target_env = "gnu")]
+impl Default for StatxBuf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(target_env = "gnu")]
+impl fmt::Debug for StatxBuf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StatxBuf")
+            .field("mask", &self.mask())
+            .field("mode", &self.mode())
+            .field("size", &self.size())
+            .field("mtime", &self.mtime())
+            .finish_non_exhaustive()
+    }
+}
+
 /// Opaque types, you should use [`epoll_event`](libc::epoll_event) instead.
 #[repr(C)]
 #[allow(non_camel_case_types)]
@@ -64,6 +197,42 @@ pub struct epoll_event {
     _priv: (),
 }
 
+/// Advice for [`Fadvise::from_advice`](crate::opcode::Fadvise::from_advice), see
+/// `posix_fadvise(2)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(i32)]
+#[non_exhaustive]
+pub enum Advice {
+    Normal = libc::POSIX_FADV_NORMAL,
+    Random = libc::POSIX_FADV_RANDOM,
+    Sequential = libc::POSIX_FADV_SEQUENTIAL,
+    WillNeed = libc::POSIX_FADV_WILLNEED,
+    DontNeed = libc::POSIX_FADV_DONTNEED,
+    NoReuse = libc::POSIX_FADV_NOREUSE,
+}
+
+/// Advice for [`Madvise::from_advice`](crate::opcode::Madvise::from_advice), see `madvise(2)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(i32)]
+#[non_exhaustive]
+pub enum MemoryAdvice {
+    Normal = libc::MADV_NORMAL,
+    Random = libc::MADV_RANDOM,
+    Sequential = libc::MADV_SEQUENTIAL,
+    WillNeed = libc::MADV_WILLNEED,
+    DontNeed = libc::MADV_DONTNEED,
+    Free = libc::MADV_FREE,
+    Remove = libc::MADV_REMOVE,
+    DontFork = libc::MADV_DONTFORK,
+    DoFork = libc::MADV_DOFORK,
+    Mergeable = libc::MADV_MERGEABLE,
+    Unmergeable = libc::MADV_UNMERGEABLE,
+    HugePage = libc::MADV_HUGEPAGE,
+    NoHugePage = libc::MADV_NOHUGEPAGE,
+    DontDump = libc::MADV_DONTDUMP,
+    DoDump = libc::MADV_DODUMP,
+}
+
 /// A file descriptor that has not been registered with io_uring.
 #[derive(Debug, Clone, Copy)]
 #[repr(transparent)]
@@ -76,6 +245,30 @@ pub struct Fd(pub RawFd);
 #[repr(transparent)]
 pub struct Fixed(pub u32);
 
+/// Either a raw file descriptor or a fixed file index, chosen at runtime.
+///
+/// Opcodes that accept both [`Fd`] and [`Fixed`] also accept a `Target`, and set
+/// [`Flags::FIXED_FILE`](crate::squeue::Flags::FIXED_FILE) only for the `Fixed` variant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    Fd(RawFd),
+    Fixed(u32),
+}
+
+impl From<Fd> for Target {
+    #[inline]
+    fn from(fd: Fd) -> Self {
+        Target::Fd(fd.0)
+    }
+}
+
+impl From<Fixed> for Target {
+    #[inline]
+    fn from(fixed: Fixed) -> Self {
+        Target::Fixed(fixed.0)
+    }
+}
+
 bitflags! {
     /// Options for [`Timeout`](super::Timeout).
     ///
@@ -88,17 +281,31 @@ bitflags! {
     /// The default behavior when the timeout expires is to return a CQE with -libc::ETIME in
     /// the res field. To change this behavior to have zero returned, include
     /// [`types::TimeoutFlags::ETIME_SUCCESS`].
+    ///
+    /// A relative timeout can also be made periodic with [`types::TimeoutFlags::MULTISHOT`].
     #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
     pub struct TimeoutFlags: u32 {
+        /// The timespec is an absolute time rather than an interval.
         const ABS = sys::IORING_TIMEOUT_ABS;
 
+        /// Measure the timeout against `CLOCK_BOOTTIME`, which includes time spent suspended.
         const BOOTTIME = sys::IORING_TIMEOUT_BOOTTIME;
 
+        /// Measure the timeout against `CLOCK_REALTIME`, the wall clock. See the conversion of a
+        /// [`SystemTime`](std::time::SystemTime) into a [`Timespec`](super::Timespec).
         const REALTIME = sys::IORING_TIMEOUT_REALTIME;
 
         const LINK_TIMEOUT_UPDATE = sys::IORING_LINK_TIMEOUT_UPDATE;
 
         const ETIME_SUCCESS = sys::IORING_TIMEOUT_ETIME_SUCCESS;
+
+        /// Re-arm the timeout every time it expires, posting a CQE with
+        /// [`cqueue::more`](crate::cqueue::more) set for each expiry. The `count` of the
+        /// [`Timeout`](super::Timeout) is then the number of expiries after which the timeout
+        /// completes, or 0 to repeat until it is cancelled.
+        ///
+        /// Available since 6.4.
+        const MULTISHOT = sys::IORING_TIMEOUT_MULTISHOT;
     }
 }
 
@@ -110,6 +317,85 @@ bitflags! {
     }
 }
 
+bitflags! {
+    /// Options for [`Splice`](crate::opcode::Splice) and [`Tee`](crate::opcode::Tee), see
+    /// `splice(2)`.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+    pub struct SpliceFlags: u32 {
+        /// Move pages instead of copying, as a hint.
+        const MOVE = libc::SPLICE_F_MOVE;
+
+        /// Do not block on the pipes. The request may still block on the other file.
+        const NONBLOCK = libc::SPLICE_F_NONBLOCK;
+
+        /// More data will follow in a subsequent splice.
+        const MORE = libc::SPLICE_F_MORE;
+    }
+}
+
+bitflags! {
+    /// The mode of [`Fallocate`](crate::opcode::Fallocate), set with
+    /// [`Fallocate::fallocate_flags`](crate::opcode::Fallocate::fallocate_flags). See
+    /// `fallocate(2)`.
+    ///
+    /// With no flags, space is allocated for the range and the file is extended if needed.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+    pub struct FallocateFlags: u32 {
+        /// Do not change the size of the file, even if the range extends past its end.
+        const KEEP_SIZE = libc::FALLOC_FL_KEEP_SIZE as u32;
+
+        /// Deallocate the range, leaving a hole that reads as zeros. Must be combined with
+        /// `KEEP_SIZE`.
+        const PUNCH_HOLE = libc::FALLOC_FL_PUNCH_HOLE as u32;
+
+        /// Remove the range from the file, shifting the data after it down.
+        const COLLAPSE_RANGE = libc::FALLOC_FL_COLLAPSE_RANGE as u32;
+
+        /// Zero the range, preferably by converting it to unwritten extents.
+        const ZERO_RANGE = libc::FALLOC_FL_ZERO_RANGE as u32;
+
+        /// Insert a hole of the range's size at its offset, shifting the data after it up.
+        const INSERT_RANGE = libc::FALLOC_FL_INSERT_RANGE as u32;
+
+        /// Unshare any blocks in the range that are shared with other files.
+        const UNSHARE_RANGE = libc::FALLOC_FL_UNSHARE_RANGE as u32;
+    }
+}
+
+bitflags! {
+    /// Flags for [`Submitter::enter_raw`](super::Submitter::enter_raw), passed to
+    /// `io_uring_enter` as they are.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+    pub struct EnterFlags: u32 {
+        /// Wait for `min_complete` completions before returning.
+        const GETEVENTS = sys::IORING_ENTER_GETEVENTS;
+
+        /// Wake up the kernel's submission queue poll thread, if it has gone to sleep. Only
+        /// meaningful when [`Builder::setup_sqpoll`](crate::Builder::setup_sqpoll) is enabled.
+        const SQ_WAKEUP = sys::IORING_ENTER_SQ_WAKEUP;
+
+        /// Wait for the submission queue poll thread to free up entries in the submission queue.
+        /// Only meaningful when [`Builder::setup_sqpoll`](crate::Builder::setup_sqpoll) is
+        /// enabled.
+        const SQ_WAIT = sys::IORING_ENTER_SQ_WAIT;
+
+        /// The argument is a [`SubmitArgs`] rather than a signal mask.
+        ///
+        /// Available since 5.11.
+        const EXT_ARG = sys::IORING_ENTER_EXT_ARG;
+
+        /// The timeout of the [`SubmitArgs`] is an absolute time rather than a relative one.
+        ///
+        /// Available since 6.12.
+        const ABS_TIMER = sys::IORING_ENTER_ABS_TIMER;
+
+        /// The argument is an offset into a registered wait region rather than a pointer.
+        ///
+        /// Available since 6.13.
+        const EXT_ARG_REG = sys::IORING_ENTER_EXT_ARG_REG;
+    }
+}
+
 bitflags! {
     /// Options for [`AsyncCancel`](super::AsyncCancel) and
     /// [`Submitter::register_sync_cancel`](super::Submitter::register_sync_cancel).
@@ -167,12 +453,29 @@ impl OpenHow {
         self
     }
 
+    /// Restrict path resolution with the `RESOLVE_*` flags, e.g. [`RESOLVE_BENEATH`].
     pub const fn resolve(mut self, resolve: u64) -> Self {
         self.0.resolve = resolve;
         self
     }
 }
 
+/// Do not cross mount points while resolving, for [`OpenHow::resolve`].
+pub const RESOLVE_NO_XDEV: u64 = 0x01;
+/// Do not follow magic links such as `/proc/self/fd/*`. See [`RESOLVE_NO_XDEV`].
+pub const RESOLVE_NO_MAGICLINKS: u64 = 0x02;
+/// Do not follow any symbolic links. See [`RESOLVE_NO_XDEV`].
+pub const RESOLVE_NO_SYMLINKS: u64 = 0x04;
+/// Fail with `EXDEV` if the path escapes the directory it is resolved from, through `..`, an
+/// absolute path or a symbolic link. See [`RESOLVE_NO_XDEV`].
+pub const RESOLVE_BENEATH: u64 = 0x08;
+/// Resolve the path as if the directory were the root of the filesystem. See
+/// [`RESOLVE_NO_XDEV`].
+pub const RESOLVE_IN_ROOT: u64 = 0x10;
+/// Only resolve the path from the dentry cache, failing with `EAGAIN` otherwise. See
+/// [`RESOLVE_NO_XDEV`].
+pub const RESOLVE_CACHED: u64 = 0x20;
+
 #[derive(Default, Debug, Clone, Copy)]
 #[repr(transparent)]
 pub struct Timespec(pub(crate) sys::__kernel_timespec);
@@ -197,16 +500,137 @@ impl Timespec {
         self.0.tv_nsec = nsec as _;
         self
     }
+
+    /// Like [`nsec`](Self::nsec), but returns `None` if `nsec` is not less than one second, which
+    /// the kernel rejects with `EINVAL`.
+    #[inline]
+    pub const fn try_nsec(self, nsec: u32) -> Option<Self> {
+        if nsec < 1_000_000_000 {
+            Some(self.nsec(nsec))
+        } else {
+            None
+        }
+    }
+
+    /// Convert a [`Duration`](std::time::Duration), usable in `const` contexts unlike the
+    /// [`From`] impl.
+    ///
+    /// The nanoseconds are kept exactly, as a `Duration` has no finer resolution. The seconds are
+    /// cast to the kernel's signed 64-bit `tv_sec`, so a duration of more than `i64::MAX` seconds
+    /// wraps to a negative value, which the kernel rejects with `EINVAL`.
+    #[inline]
+    pub const fn from_duration(duration: std::time::Duration) -> Self {
+        Timespec::new()
+            .sec(duration.as_secs())
+            .nsec(duration.subsec_nanos())
+    }
 }
 
 impl From<std::time::Duration> for Timespec {
     fn from(value: std::time::Duration) -> Self {
-        Timespec::new()
-            .sec(value.as_secs())
-            .nsec(value.subsec_nanos())
+        Timespec::from_duration(value)
     }
 }
 
+/// Convert an [`Instant`](std::time::Instant) to a `CLOCK_MONOTONIC` time, for use as an absolute
+/// timeout with [`TimeoutFlags::ABS`].
+impl From<std::time::Instant> for Timespec {
+    fn from(value: std::time::Instant) -> Self {
+        let mut now_ts: libc::timespec = unsafe { mem::zeroed() };
+        let now = std::time::Instant::now();
+        unsafe {
+            libc::clock_gettime(libc::CLOCK_MONOTONIC, &mut now_ts);
+        }
+
+        let now_mono = std::time::Duration::new(now_ts.tv_sec as u64, now_ts.tv_nsec as u32);
+        let mono = if value >= now {
+            now_mono + (value - now)
+        } else {
+            now_mono.saturating_sub(now - value)
+        };
+        Timespec::from_duration(mono)
+    }
+}
+
+/// Convert a [`SystemTime`](std::time::SystemTime) to the time since the Unix epoch, for use as an
+/// absolute timeout with [`TimeoutFlags::ABS`] and [`TimeoutFlags::REALTIME`].
+///
+/// Fails if the time is before the Unix epoch.
+impl TryFrom<std::time::SystemTime> for Timespec {
+    type Error = std::time::SystemTimeError;
+
+    fn try_from(value: std::time::SystemTime) -> Result<Self, Self::Error> {
+        value.duration_since(std::time::UNIX_EPOCH).map(Into::into)
+    }
+}
+
+/// A `user_data` value packed from an operation tag, an index and a generation.
+///
+/// The tag takes the top 16 bits, the generation the next 16 and the index the low 32. Convert it
+/// to a `u64` for [`squeue::Entry::user_data`](crate::squeue::Entry::user_data), and back from
+/// [`cqueue::EntryMarker::user_data`](crate::cqueue::EntryMarker::user_data) on completion.
+///
+/// ```
+/// use io_uring::types::UserData;
+///
+/// let user_data = UserData::from_parts(3, 42, 7);
+/// let raw: u64 = user_data.into();
+///
+/// let decoded = UserData::from(raw);
+/// assert_eq!(decoded.tag(), 3);
+/// assert_eq!(decoded.index(), 42);
+/// assert_eq!(decoded.generation(), 7);
+/// ```
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct UserData(pub u64);
+
+impl UserData {
+    #[inline]
+    pub const fn from_parts(tag: u16, index: u32, gen: u16) -> Self {
+        UserData(((tag as u64) << 48) | ((gen as u64) << 32) | index as u64)
+    }
+
+    /// The operation tag.
+    #[inline]
+    pub const fn tag(self) -> u16 {
+        (self.0 >> 48) as u16
+    }
+
+    /// The index, such as a slot in a connection table.
+    #[inline]
+    pub const fn index(self) -> u32 {
+        self.0 as u32
+    }
+
+    /// The generation, to tell a reused index from a stale completion.
+    #[inline]
+    pub const fn generation(self) -> u16 {
+        (self.0 >> 32) as u16
+    }
+}
+
+impl From<UserData> for u64 {
+    #[inline]
+    fn from(value: UserData) -> u64 {
+        value.0
+    }
+}
+
+impl From<u64> for UserData {
+    #[inline]
+    fn from(value: u64) -> UserData {
+        UserData(value)
+    }
+}
+
+/// The size of the kernel's `sigset_t`, `_NSIG / 8`, which the kernel checks `sigmask_sz`
+/// against. It is smaller than libc's `sigset_t`, which has room for future signals.
+#[cfg(any(target_arch = "mips", target_arch = "mips64"))]
+const KERNEL_SIGSET_SIZE: u32 = 128 / 8;
+#[cfg(not(any(target_arch = "mips", target_arch = "mips64")))]
+const KERNEL_SIGSET_SIZE: u32 = 64 / 8;
+
 /// Submit arguments
 ///
 /// Note that arguments that exceed their lifetime will fail to compile.
@@ -253,7 +677,7 @@ impl<'prev, 'now> SubmitArgs<'prev, 'now> {
     #[inline]
     pub fn sigmask<'new>(mut self, sigmask: &'new libc::sigset_t) -> SubmitArgs<'now, 'new> {
         self.args.sigmask = cast_ptr(sigmask) as _;
-        self.args.sigmask_sz = std::mem::size_of::<libc::sigset_t>() as _;
+        self.args.sigmask_sz = KERNEL_SIGSET_SIZE;
 
         SubmitArgs {
             args: self.args,
@@ -272,6 +696,476 @@ impl<'prev, 'now> SubmitArgs<'prev, 'now> {
             now: PhantomData,
         }
     }
+
+    /// Wait at most `min_wait_usec` microseconds for the requested number of completions. If any
+    /// completion has arrived by then, return early with what is there, otherwise keep waiting
+    /// as usual.
+    ///
+    /// Requires [`is_feature_min_timeout`](crate::Parameters::is_feature_min_timeout),
+    /// available since 6.12.
+    #[inline]
+    pub const fn min_wait_usec(mut self, min_wait_usec: u32) -> Self {
+        self.args.min_wait_usec = min_wait_usec;
+        self
+    }
+}
+
+/// A [`msghdr`](libc::msghdr) for [`SendMsg`](crate::opcode::SendMsg) and
+/// [`RecvMsg`](crate::opcode::RecvMsg) that borrows its buffers, so they cannot be dropped while
+/// the header is alive.
+///
+/// Pass it to [`SendMsg::from_msghdr`](crate::opcode::SendMsg::from_msghdr) or
+/// [`RecvMsg::from_msghdr`](crate::opcode::RecvMsg::from_msghdr), or pass
+/// [`as_ptr`](Self::as_ptr) or [`as_mut_ptr`](Self::as_mut_ptr) to other opcodes. The header
+/// itself must still outlive the request.
+///
+/// ```
+/// use io_uring::{opcode, types};
+/// use io_uring::types::MsgHdr;
+/// use std::io::IoSliceMut;
+///
+/// let (mut head, mut body) = ([0u8; 4], [0u8; 64]);
+/// let mut iov = [IoSliceMut::new(&mut head), IoSliceMut::new(&mut body)];
+/// let mut name = [0u8; 16];
+///
+/// let mut msg = MsgHdr::new().iov_mut(&mut iov).name(&mut name);
+/// let recvmsg_e = opcode::RecvMsg::from_msghdr(types::Fd(0), &mut msg).build();
+/// # let _ = recvmsg_e;
+/// ```
+pub struct MsgHdr<'a> {
+    hdr: libc::msghdr,
+    _marker: PhantomData<&'a mut [u8]>,
+}
+
+impl<'a> MsgHdr<'a> {
+    pub fn new() -> Self {
+        MsgHdr {
+            hdr: unsafe { mem::zeroed() },
+            _marker: PhantomData,
+        }
+    }
+
+    /// Gather the data to send from `iov`.
+    pub fn iov(mut self, iov: &'a [std::io::IoSlice<'a>]) -> Self {
+        self.hdr.msg_iov = iov.as_ptr() as *mut libc::iovec;
+        self.hdr.msg_iovlen = iov.len() as _;
+        self
+    }
+
+    /// Scatter the received data into `iov`.
+    pub fn iov_mut(mut self, iov: &'a mut [std::io::IoSliceMut<'a>]) -> Self {
+        self.hdr.msg_iov = iov.as_mut_ptr().cast();
+        self.hdr.msg_iovlen = iov.len() as _;
+        self
+    }
+
+    /// The socket address to send to, or to receive the source address into.
+    pub fn name(mut self, name: &'a mut [u8]) -> Self {
+        self.hdr.msg_name = name.as_mut_ptr().cast();
+        self.hdr.msg_namelen = name.len() as _;
+        self
+    }
+
+    /// The ancillary data to send, or to receive into.
+    pub fn control(mut self, control: &'a mut [u8]) -> Self {
+        self.hdr.msg_control = control.as_mut_ptr().cast();
+        self.hdr.msg_controllen = control.len() as _;
+        self
+    }
+
+    /// The length of the name, which the kernel updates on receive.
+    pub fn name_len(&self) -> u32 {
+        self.hdr.msg_namelen
+    }
+
+    /// The length of the ancillary data, which the kernel updates on receive.
+    pub fn control_len(&self) -> usize {
+        self.hdr.msg_controllen as _
+    }
+
+    pub fn as_ptr(&self) -> *const libc::msghdr {
+        &self.hdr
+    }
+
+    pub fn as_mut_ptr(&mut self) -> *mut libc::msghdr {
+        &mut self.hdr
+    }
+}
+
+impl Default for MsgHdr<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for MsgHdr<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MsgHdr")
+            .field("msg_namelen", &self.hdr.msg_namelen)
+            .field("msg_iovlen", &self.hdr.msg_iovlen)
+            .field("msg_controllen", &self.hdr.msg_controllen)
+            .finish()
+    }
+}
+
+/// A message header, along with the iovec and socket address it points to. It is boxed so that
+/// the pointers stay valid when the owner moves.
+struct NamedMsg {
+    hdr: libc::msghdr,
+    iov: libc::iovec,
+    addr: libc::sockaddr_storage,
+}
+
+impl NamedMsg {
+    fn new(buf: *mut u8, len: usize, addr: libc::sockaddr_storage, addr_len: u32) -> Box<Self> {
+        let mut msg = Box::new(NamedMsg {
+            hdr: unsafe { mem::zeroed() },
+            iov: libc::iovec {
+                iov_base: buf.cast(),
+                iov_len: len,
+            },
+            addr,
+        });
+
+        msg.hdr.msg_iov = &mut msg.iov;
+        msg.hdr.msg_iovlen = 1;
+        msg.hdr.msg_name = ptr::addr_of_mut!(msg.addr).cast();
+        msg.hdr.msg_namelen = addr_len;
+        msg
+    }
+}
+
+/// Convert `addr` to its C representation, returning it along with its length.
+fn socket_addr_to_raw(addr: &SocketAddr) -> (libc::sockaddr_storage, u32) {
+    let mut storage: libc::sockaddr_storage = unsafe { mem::zeroed() };
+
+    let len = match addr {
+        SocketAddr::V4(addr) => {
+            let sin = libc::sockaddr_in {
+                sin_family: libc::AF_INET as _,
+                sin_port: addr.port().to_be(),
+                sin_addr: libc::in_addr {
+                    s_addr: u32::from_ne_bytes(addr.ip().octets()),
+                },
+                sin_zero: [0; 8],
+            };
+            unsafe { ptr::write(ptr::addr_of_mut!(storage).cast(), sin) };
+            mem::size_of::<libc::sockaddr_in>()
+        }
+        SocketAddr::V6(addr) => {
+            let sin6 = libc::sockaddr_in6 {
+                sin6_family: libc::AF_INET6 as _,
+                sin6_port: addr.port().to_be(),
+                sin6_flowinfo: addr.flowinfo(),
+                sin6_addr: libc::in6_addr {
+                    s6_addr: addr.ip().octets(),
+                },
+                sin6_scope_id: addr.scope_id(),
+            };
+            unsafe { ptr::write(ptr::addr_of_mut!(storage).cast(), sin6) };
+            mem::size_of::<libc::sockaddr_in6>()
+        }
+    };
+
+    (storage, len as _)
+}
+
+/// Convert a C socket address of `len` bytes, returning `None` if it is not an IPv4 or IPv6
+/// address.
+fn socket_addr_from_raw(storage: &libc::sockaddr_storage, len: u32) -> Option<SocketAddr> {
+    let len = len as usize;
+
+    match storage.ss_family as libc::c_int {
+        libc::AF_INET if len >= mem::size_of::<libc::sockaddr_in>() => {
+            let sin =
+                unsafe { &*(storage as *const libc::sockaddr_storage).cast::<libc::sockaddr_in>() };
+            let ip = Ipv4Addr::from(sin.sin_addr.s_addr.to_ne_bytes());
+            Some(SocketAddrV4::new(ip, u16::from_be(sin.sin_port)).into())
+        }
+        libc::AF_INET6 if len >= mem::size_of::<libc::sockaddr_in6>() => {
+            let sin6 = unsafe {
+                &*(storage as *const libc::sockaddr_storage).cast::<libc::sockaddr_in6>()
+            };
+            let ip = Ipv6Addr::from(sin6.sin6_addr.s6_addr);
+            Some(
+                SocketAddrV6::new(
+                    ip,
+                    u16::from_be(sin6.sin6_port),
+                    sin6.sin6_flowinfo,
+                    sin6.sin6_scope_id,
+                )
+                .into(),
+            )
+        }
+        _ => None,
+    }
+}
+
+/// A socket address in its C representation, for operations that take a
+/// [`sockaddr`](libc::sockaddr) pointer and length such as [`Connect`](crate::opcode::Connect).
+///
+/// The kernel reads the address through a pointer, so the `SockAddr` must outlive the
+/// submission of the request.
+///
+/// ```
+/// use io_uring::{opcode, types};
+///
+/// let addr = types::SockAddr::from("127.0.0.1:4000".parse::<std::net::SocketAddr>().unwrap());
+/// let connect_e = opcode::Connect::from_addr(types::Fd(3), &addr).build();
+/// # let _ = connect_e;
+/// ```
+#[derive(Clone, Copy)]
+pub struct SockAddr {
+    storage: libc::sockaddr_storage,
+    len: libc::socklen_t,
+}
+
+impl SockAddr {
+    pub fn as_ptr(&self) -> *const libc::sockaddr {
+        ptr::addr_of!(self.storage).cast()
+    }
+
+    /// The length of the address, in bytes.
+    pub fn addr_len(&self) -> libc::socklen_t {
+        self.len
+    }
+
+    /// Convert back to a [`SocketAddr`].
+    pub fn as_socket_addr(&self) -> SocketAddr {
+        socket_addr_from_raw(&self.storage, self.len).expect("constructed from a SocketAddr")
+    }
+}
+
+impl From<SocketAddr> for SockAddr {
+    fn from(addr: SocketAddr) -> Self {
+        let (storage, len) = socket_addr_to_raw(&addr);
+        SockAddr { storage, len }
+    }
+}
+
+impl fmt::Debug for SockAddr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SockAddr")
+            .field(&self.as_socket_addr())
+            .finish()
+    }
+}
+
+impl PartialEq for SockAddr {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_socket_addr() == other.as_socket_addr()
+    }
+}
+
+impl Eq for SockAddr {}
+
+/// A message for [`SendMsg`](crate::opcode::SendMsg) that sends `buf` to a given address, like
+/// `sendto(2)`.
+///
+/// Pass [`as_ptr`](Self::as_ptr) to the opcode. The header may be moved, but must still outlive
+/// the request.
+///
+/// ```
+/// use io_uring::{opcode, types};
+/// use std::net::UdpSocket;
+/// use std::os::unix::io::AsRawFd;
+///
+/// let socket = UdpSocket::bind("127.0.0.1:0")?;
+/// let msg = types::SendTo::new(b"ping", "127.0.0.1:4000".parse().unwrap());
+/// let send_e = opcode::SendMsg::new(types::Fd(socket.as_raw_fd()), msg.as_ptr()).build();
+/// # let _ = send_e;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub struct SendTo<'a> {
+    msg: Box<NamedMsg>,
+    _marker: PhantomData<&'a [u8]>,
+}
+
+impl<'a> SendTo<'a> {
+    pub fn new(buf: &'a [u8], addr: SocketAddr) -> Self {
+        let (storage, len) = socket_addr_to_raw(&addr);
+
+        SendTo {
+            msg: NamedMsg::new(buf.as_ptr() as *mut u8, buf.len(), storage, len),
+            _marker: PhantomData,
+        }
+    }
+
+    /// The address the message is sent to.
+    pub fn addr(&self) -> SocketAddr {
+        socket_addr_from_raw(&self.msg.addr, self.msg.hdr.msg_namelen)
+            .expect("constructed from a SocketAddr")
+    }
+
+    pub fn as_ptr(&self) -> *const libc::msghdr {
+        &self.msg.hdr
+    }
+}
+
+impl fmt::Debug for SendTo<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SendTo")
+            .field("len", &self.msg.iov.iov_len)
+            .field("addr", &self.addr())
+            .finish()
+    }
+}
+
+/// A message for [`RecvMsg`](crate::opcode::RecvMsg) that receives into `buf` and records the
+/// address of the sender, like `recvfrom(2)`.
+///
+/// Pass [`as_mut_ptr`](Self::as_mut_ptr) to the opcode, and read the sender with
+/// [`peer_addr`](Self::peer_addr) once it completes. The header may be moved, but must still
+/// outlive the request.
+pub struct RecvFrom<'a> {
+    msg: Box<NamedMsg>,
+    _marker: PhantomData<&'a mut [u8]>,
+}
+
+impl<'a> RecvFrom<'a> {
+    pub fn new(buf: &'a mut [u8]) -> Self {
+        let storage = unsafe { mem::zeroed() };
+        let len = mem::size_of::<libc::sockaddr_storage>() as u32;
+
+        RecvFrom {
+            msg: NamedMsg::new(buf.as_mut_ptr(), buf.len(), storage, len),
+            _marker: PhantomData,
+        }
+    }
+
+    /// The address the message was received from, which is `None` until the request completes,
+    /// or if the socket is not an IPv4 or IPv6 one.
+    pub fn peer_addr(&self) -> Option<SocketAddr> {
+        socket_addr_from_raw(&self.msg.addr, self.msg.hdr.msg_namelen)
+    }
+
+    /// The [flags](libc::MSG_TRUNC) the kernel set on the received message.
+    pub fn msg_flags(&self) -> i32 {
+        self.msg.hdr.msg_flags
+    }
+
+    pub fn as_mut_ptr(&mut self) -> *mut libc::msghdr {
+        &mut self.msg.hdr
+    }
+}
+
+impl fmt::Debug for RecvFrom<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RecvFrom")
+            .field("len", &self.msg.iov.iov_len)
+            .field("peer_addr", &self.peer_addr())
+            .finish()
+    }
+}
+
+/// An [`iovec`](libc::iovec) that borrows its buffer, so the buffer cannot be dropped while the
+/// `IoVec` is alive.
+#[repr(transparent)]
+pub struct IoVec<'a> {
+    iov: libc::iovec,
+    _marker: PhantomData<&'a mut [u8]>,
+}
+
+impl<'a> IoVec<'a> {
+    /// Describe `buf` as a source of data, e.g. for [`Writev`](crate::opcode::Writev).
+    ///
+    /// The kernel must not write through an `IoVec` made this way.
+    pub fn new(buf: &'a [u8]) -> Self {
+        IoVec {
+            iov: libc::iovec {
+                iov_base: buf.as_ptr() as *mut _,
+                iov_len: buf.len(),
+            },
+            _marker: PhantomData,
+        }
+    }
+
+    /// Describe `buf` as a destination for data, e.g. for [`Readv`](crate::opcode::Readv).
+    pub fn new_mut(buf: &'a mut [u8]) -> Self {
+        IoVec {
+            iov: libc::iovec {
+                iov_base: buf.as_mut_ptr().cast(),
+                iov_len: buf.len(),
+            },
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn as_ptr(&self) -> *const u8 {
+        self.iov.iov_base as *const u8
+    }
+
+    pub fn len(&self) -> usize {
+        self.iov.iov_len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.iov.iov_len == 0
+    }
+}
+
+impl fmt::Debug for IoVec<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IoVec")
+            .field("iov_base", &self.iov.iov_base)
+            .field("iov_len", &self.iov.iov_len)
+            .finish()
+    }
+}
+
+/// A list of [`IoVec`]s, laid out as an array of [`iovec`](libc::iovec) for
+/// [`Readv`](crate::opcode::Readv), [`Writev`](crate::opcode::Writev) and
+/// [`Submitter::register_buffers`](crate::Submitter::register_buffers).
+///
+/// Pass [`as_ptr`](Self::as_ptr) and [`len`](Self::len) to the opcode, or
+/// [`as_raw`](Self::as_raw) to the register call. The list itself must still outlive the request.
+///
+/// The buffers must outlive the list:
+///
+/// ```compile_fail,E0597
+/// use io_uring::types::{IoVec, IoVecs};
+///
+/// let iovecs;
+/// {
+///     let buf = vec![0u8; 16];
+///     iovecs = IoVecs::from_iter([IoVec::new(&buf)]);
+/// }
+/// let _ = iovecs.as_ptr();
+/// ```
+#[derive(Debug, Default)]
+pub struct IoVecs<'a>(Vec<IoVec<'a>>);
+
+impl<'a> IoVecs<'a> {
+    pub fn new() -> Self {
+        IoVecs(Vec::new())
+    }
+
+    pub fn push(&mut self, iov: IoVec<'a>) {
+        self.0.push(iov);
+    }
+
+    pub fn as_ptr(&self) -> *const libc::iovec {
+        self.0.as_ptr().cast()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn as_raw(&self) -> &[libc::iovec] {
+        // Safety: `IoVec` is a transparent wrapper around `libc::iovec`.
+        unsafe { std::slice::from_raw_parts(self.as_ptr(), self.0.len()) }
+    }
+}
+
+impl<'a> FromIterator<IoVec<'a>> for IoVecs<'a> {
+    fn from_iter<I: IntoIterator<Item = IoVec<'a>>>(iter: I) -> Self {
+        IoVecs(iter.into_iter().collect())
+    }
 }
 
 #[repr(transparent)]
@@ -327,9 +1221,162 @@ impl BufRingEntry {
     }
 }
 
-/// A destination slot for sending fixed resources
-/// (e.g. [`opcode::MsgRingSendFd`](crate::opcode::MsgRingSendFd)).
-#[derive(Debug, Clone, Copy)]
+/// A ring of provided buffers shared with the kernel, to be registered with
+/// [`Submitter::register_buf_ring`](crate::Submitter::register_buf_ring).
+///
+/// Buffers are handed to the kernel by [adding](Self::add) them to the ring and then
+/// [advancing](Self::advance) its tail. Requests that set
+/// [`BUFFER_SELECT`](crate::squeue::Flags::BUFFER_SELECT) with the buffer group the ring was
+/// registered under will then take buffers from it, and report the one they used in the flags of
+/// their completion (see [`buffer_id`](Self::buffer_id)). Used buffers are not returned to the
+/// ring automatically; they must be added again once the application is done with them, or the
+/// completions walked with [`CompletionQueue::with_buf_ring`](crate::cqueue::CompletionQueue::with_buf_ring),
+/// which does so as each one is dropped.
+///
+/// Available since 5.19.
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use io_uring::{types::BufRing, IoUring};
+///
+/// let ring = IoUring::new(8)?;
+/// let mut bufs = vec![[0u8; 4096]; 8];
+/// let mut buf_ring = BufRing::new(8)?;
+///
+/// for (bid, buf) in bufs.iter_mut().enumerate() {
+///     unsafe { buf_ring.add(buf.as_mut_ptr(), buf.len() as _, bid as _) };
+/// }
+/// buf_ring.advance();
+///
+/// unsafe {
+///     ring.submitter()
+///         .register_buf_ring(buf_ring.ring_addr(), buf_ring.ring_entries(), 0xbead)?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct BufRing {
+    ring: Mmap,
+    ring_entries: u16,
+    /// The tail as last published to the kernel.
+    tail: u16,
+    /// The number of buffers added since the tail was last published.
+    pending: u16,
+    /// The address and length of the buffer last added under each ID.
+    bufs: Vec<(*mut u8, u32)>,
+}
+
+unsafe impl Send for BufRing {}
+
+impl BufRing {
+    /// Allocate a ring with room for `ring_entries` buffers.
+    ///
+    /// `ring_entries` must be a power of two no larger than 32768, otherwise an
+    /// [`InvalidInput`](io::ErrorKind::InvalidInput) error is returned.
+    pub fn new(ring_entries: u16) -> io::Result<BufRing> {
+        if !ring_entries.is_power_of_two() || ring_entries > 1 << 15 {
+            return Err(io::ErrorKind::InvalidInput.into());
+        }
+
+        // The ring must be page-aligned and zero-filled, which an anonymous mapping guarantees.
+        let ring = Mmap::new_anonymous(ring_entries as usize * mem::size_of::<BufRingEntry>())?;
+
+        Ok(BufRing {
+            ring,
+            ring_entries,
+            tail: 0,
+            pending: 0,
+            bufs: Vec::new(),
+        })
+    }
+
+    /// The address of the ring, to be passed to
+    /// [`Submitter::register_buf_ring`](crate::Submitter::register_buf_ring).
+    #[inline]
+    pub fn ring_addr(&self) -> u64 {
+        self.ring.as_mut_ptr() as u64
+    }
+
+    /// The number of entries in the ring.
+    #[inline]
+    pub fn ring_entries(&self) -> u16 {
+        self.ring_entries
+    }
+
+    /// Add a buffer with the ID `bid` to the ring. It will not be visible to the kernel until the
+    /// next call to [`advance`](Self::advance).
+    ///
+    /// At most [`ring_entries`](Self::ring_entries) buffers may be in the ring at once, counting
+    /// both those added and those published but not yet taken by the kernel.
+    ///
+    /// # Safety
+    ///
+    /// `addr` must be valid for writes of `len` bytes until the kernel has returned the buffer in
+    /// a completion, or the ring has been unregistered.
+    pub unsafe fn add(&mut self, addr: *mut u8, len: u32, bid: u16) {
+        debug_assert!(self.pending < self.ring_entries);
+
+        let index = self.tail.wrapping_add(self.pending) & (self.ring_entries - 1);
+        let entry = self.entries().add(index as usize);
+
+        // Only write the fields of the entry; in the first entry, `resv` is the tail of the ring.
+        ptr::addr_of_mut!((*entry).0.addr).write(addr as u64);
+        ptr::addr_of_mut!((*entry).0.len).write(len);
+        ptr::addr_of_mut!((*entry).0.bid).write(bid);
+
+        let slot = bid as usize;
+        if self.bufs.len() <= slot {
+            self.bufs.resize(slot + 1, (ptr::null_mut(), 0));
+        }
+        self.bufs[slot] = (addr, len);
+
+        self.pending += 1;
+    }
+
+    /// Make the buffers added since the last call available to the kernel.
+    pub fn advance(&mut self) {
+        self.tail = self.tail.wrapping_add(self.pending);
+        self.pending = 0;
+
+        unsafe {
+            let tail = BufRingEntry::tail(self.entries()) as *const atomic::AtomicU16;
+            (*tail).store(self.tail, atomic::Ordering::Release);
+        }
+    }
+
+    /// The ID of the buffer used by an operation, given the [flags](crate::cqueue::EntryMarker::flags)
+    /// of its completion. See [`buffer_select`](crate::cqueue::buffer_select).
+    #[inline]
+    pub fn buffer_id(&self, flags: u32) -> Option<u16> {
+        crate::cqueue::buffer_select(flags)
+    }
+
+    /// The address and length of the buffer last added with the ID `bid`, if any.
+    #[inline]
+    pub(crate) fn buffer(&self, bid: u16) -> Option<(*mut u8, u32)> {
+        self.bufs
+            .get(bid as usize)
+            .copied()
+            .filter(|(addr, _)| !addr.is_null())
+    }
+
+    #[inline]
+    fn entries(&self) -> *mut BufRingEntry {
+        self.ring.as_mut_ptr().cast()
+    }
+}
+
+/// A destination slot in the fixed file table, for operations that place a file there instead of
+/// returning a file descriptor, such as [`opcode::Accept`](crate::opcode::Accept),
+/// [`opcode::Socket`](crate::opcode::Socket), [`opcode::OpenAt`](crate::opcode::OpenAt) and
+/// [`opcode::MsgRingSendFd`](crate::opcode::MsgRingSendFd).
+///
+/// The slot is either an explicit index, or [allocated](Self::auto_target) by the kernel. The
+/// kernel takes slots offset by one, with `IORING_FILE_INDEX_ALLOC` standing for allocation, and
+/// this type takes care of that encoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct DestinationSlot {
     /// Fixed slot as indexed by the kernel (target+1).
     dest: NonZeroU32,
@@ -366,6 +1413,22 @@ impl DestinationSlot {
         Ok(Self { dest })
     }
 
+    /// Try to use the slot at `index`, the same as
+    /// [`try_from_slot_target`](Self::try_from_slot_target).
+    #[inline]
+    pub fn try_from_index(index: u32) -> Result<Self, u32> {
+        Self::try_from_slot_target(index)
+    }
+
+    /// The index of the slot, or `None` if it is allocated by the kernel.
+    pub fn index(&self) -> Option<u32> {
+        if self.dest == DestinationSlot::AUTO_ALLOC {
+            None
+        } else {
+            Some(self.dest.get() - 1)
+        }
+    }
+
     pub(crate) fn kernel_index_arg(&self) -> u32 {
         self.dest.get()
     }
@@ -630,6 +1693,36 @@ impl CancelBuilder {
     }
 }
 
+/// Do not post a completion on the target ring of a
+/// [`MsgRingSendFd`](crate::opcode::MsgRingSendFd), for its `opcode_flags`. The source ring
+/// still gets one. Not valid for [`MsgRingData`](crate::opcode::MsgRingData).
+///
+/// Available since 6.1.
+pub const MSG_RING_CQE_SKIP: u32 = sys::IORING_MSG_RING_CQE_SKIP;
+
+/// Do not mark the file descriptor installed by
+/// [`FixedFdInstall`](crate::opcode::FixedFdInstall) as close-on-exec.
+pub const FIXED_FD_NO_CLOEXEC: u32 = sys::IORING_FIXED_FD_NO_CLOEXEC;
+
+/// The futex is 8 bits wide, for the `futex_flags` of [`FutexWait`](crate::opcode::FutexWait)
+/// and [`FutexWake`](crate::opcode::FutexWake) or the flags of [`FutexWaitV`].
+pub const FUTEX2_SIZE_U8: u32 = 0x00;
+/// The futex is 16 bits wide. See [`FUTEX2_SIZE_U8`].
+pub const FUTEX2_SIZE_U16: u32 = 0x01;
+/// The futex is 32 bits wide. See [`FUTEX2_SIZE_U8`].
+pub const FUTEX2_SIZE_U32: u32 = 0x02;
+/// The futex is 64 bits wide. See [`FUTEX2_SIZE_U8`].
+pub const FUTEX2_SIZE_U64: u32 = 0x03;
+/// The futex is followed by a NUMA node hint. See [`FUTEX2_SIZE_U8`].
+pub const FUTEX2_NUMA: u32 = 0x04;
+/// The futex is private to the process, equivalent to `FUTEX_PRIVATE_FLAG`. See
+/// [`FUTEX2_SIZE_U8`].
+pub const FUTEX2_PRIVATE: u32 = 128;
+
+/// A `mask` for [`FutexWait`](crate::opcode::FutexWait) and
+/// [`FutexWake`](crate::opcode::FutexWake) that matches any waiter.
+pub const FUTEX_BITSET_MATCH_ANY: u64 = 0xffff_ffff;
+
 /// Wrapper around `futex_waitv` as used in [`futex_waitv` system
 /// call](https://www.kernel.org/doc/html/latest/userspace-api/futex2.html).
 #[derive(Default, Debug, Clone, Copy)]
@@ -662,6 +1755,47 @@ impl FutexWaitV {
     }
 }
 
+/// NAPI busy poll settings, for
+/// [`Submitter::register_napi`](crate::Submitter::register_napi).
+#[derive(Default, Debug, Clone, Copy)]
+#[repr(transparent)]
+pub struct NapiConfig(pub(crate) sys::io_uring_napi);
+
+impl NapiConfig {
+    pub const fn new() -> Self {
+        Self(sys::io_uring_napi {
+            busy_poll_to: 0,
+            prefer_busy_poll: 0,
+            opcode: 0,
+            pad: [0; 2],
+            op_param: 0,
+            resv: 0,
+        })
+    }
+
+    /// Busy poll for up to `busy_poll_to` microseconds while waiting for completions.
+    pub const fn busy_poll_to(mut self, busy_poll_to: u32) -> Self {
+        self.0.busy_poll_to = busy_poll_to;
+        self
+    }
+
+    /// Set `SO_PREFER_BUSY_POLL` semantics for the busy polling.
+    pub const fn prefer_busy_poll(mut self, prefer_busy_poll: bool) -> Self {
+        self.0.prefer_busy_poll = prefer_busy_poll as _;
+        self
+    }
+
+    /// The busy poll timeout in microseconds.
+    pub const fn get_busy_poll_to(&self) -> u32 {
+        self.0.busy_poll_to
+    }
+
+    /// Whether busy polling is preferred.
+    pub const fn is_prefer_busy_poll(&self) -> bool {
+        self.0.prefer_busy_poll != 0
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::time::Duration;
@@ -677,6 +1811,288 @@ mod tests {
 
         assert_eq!(timespec.0.tv_sec as u64, duration.as_secs());
         assert_eq!(timespec.0.tv_nsec as u32, duration.subsec_nanos());
+
+        for (duration, sec, nsec) in [
+            (Duration::from_millis(250), 0, 250_000_000),
+            (Duration::new(0, 999_999_999), 0, 999_999_999),
+            (Duration::from_nanos(1_000_000_000), 1, 0),
+            (Duration::new(3, 1), 3, 1),
+            (Duration::from_secs(86_400), 86_400, 0),
+        ] {
+            let ts = Timespec::from_duration(duration);
+            assert_eq!(ts.0.tv_sec, sec);
+            assert_eq!(ts.0.tv_nsec, nsec);
+
+            let ts = Timespec::from(duration);
+            assert_eq!(ts.0.tv_sec, sec);
+            assert_eq!(ts.0.tv_nsec, nsec);
+        }
+
+        let ts = Timespec::new().sec(1).try_nsec(999_999_999).unwrap();
+        assert_eq!(ts.0.tv_nsec, 999_999_999);
+        assert!(Timespec::new().try_nsec(1_000_000_000).is_none());
+        assert!(Timespec::new().try_nsec(u32::MAX).is_none());
+    }
+
+    #[test]
+    fn test_timespec_from_instant() {
+        use std::time::Instant;
+
+        let mono = |ts: Timespec| Duration::new(ts.0.tv_sec as u64, ts.0.tv_nsec as u32);
+        let mut now: libc::timespec = unsafe { mem::zeroed() };
+        unsafe { libc::clock_gettime(libc::CLOCK_MONOTONIC, &mut now) };
+        let now = Duration::new(now.tv_sec as u64, now.tv_nsec as u32);
+
+        let later = mono(Timespec::from(Instant::now() + Duration::from_secs(5)));
+        assert!(later >= now + Duration::from_secs(5));
+        assert!(later < now + Duration::from_secs(6));
+
+        let earlier = mono(Timespec::from(Instant::now()));
+        assert!(earlier >= now);
+        assert!(earlier < later);
+    }
+
+    #[test]
+    fn test_iovecs() {
+        let head = [1u8; 4];
+        let mut body = [0u8; 64];
+        let (head_ptr, body_ptr) = (head.as_ptr(), body.as_ptr());
+
+        let mut iovecs = IoVecs::new();
+        assert!(iovecs.is_empty());
+        iovecs.push(IoVec::new(&head));
+        iovecs.push(IoVec::new_mut(&mut body));
+        assert_eq!(iovecs.len(), 2);
+
+        let raw = iovecs.as_raw();
+        assert_eq!(raw.as_ptr(), iovecs.as_ptr());
+        assert_eq!(raw[0].iov_base as *const u8, head_ptr);
+        assert_eq!(raw[0].iov_len, 4);
+        assert_eq!(raw[1].iov_base as *const u8, body_ptr);
+        assert_eq!(raw[1].iov_len, 64);
+
+        let iov = IoVec::new(&[]);
+        assert!(iov.is_empty());
+        let iovecs: IoVecs = [IoVec::new(&head)].into_iter().collect();
+        assert_eq!(iovecs.len(), 1);
+        assert_eq!(iovecs.0[0].as_ptr(), head_ptr);
+        assert_eq!(iovecs.0[0].len(), 4);
+    }
+
+    #[test]
+    fn test_destination_slot() {
+        let auto = DestinationSlot::auto_target();
+        assert_eq!(auto.kernel_index_arg(), sys::IORING_FILE_INDEX_ALLOC as u32);
+        assert_eq!(auto.index(), None);
+
+        let slot = DestinationSlot::try_from_index(0).unwrap();
+        assert_eq!(slot.kernel_index_arg(), 1);
+        assert_eq!(slot.index(), Some(0));
+        assert_eq!(slot, DestinationSlot::try_from_slot_target(0).unwrap());
+
+        // The largest index stays clear of the allocation sentinel.
+        let max = sys::IORING_FILE_INDEX_ALLOC as u32 - 2;
+        let slot = DestinationSlot::try_from_index(max).unwrap();
+        assert_eq!(slot.kernel_index_arg(), max + 1);
+        assert_eq!(slot.index(), Some(max));
+        assert_ne!(slot, auto);
+
+        assert_eq!(DestinationSlot::try_from_index(max + 1), Err(max + 1));
+        assert_eq!(DestinationSlot::try_from_index(u32::MAX), Err(u32::MAX));
+    }
+
+    #[test]
+    fn test_send_to_recv_from() {
+        let buf = [7u8; 16];
+        let addrs: [SocketAddr; 2] = [
+            "192.0.2.1:4000".parse().unwrap(),
+            "[2001:db8::1%3]:5000".parse().unwrap(),
+        ];
+
+        for addr in addrs {
+            let msg = SendTo::new(&buf, addr);
+            assert_eq!(msg.addr(), addr);
+
+            // The header points into the box, so it stays valid once moved.
+            let moved = msg;
+            let hdr = unsafe { &*moved.as_ptr() };
+            let iov = unsafe { &*hdr.msg_iov };
+            assert_eq!(hdr.msg_iovlen, 1);
+            assert_eq!(iov.iov_base as *const u8, buf.as_ptr());
+            assert_eq!(iov.iov_len, 16);
+
+            let storage = unsafe { &*hdr.msg_name.cast::<libc::sockaddr_storage>() };
+            assert_eq!(socket_addr_from_raw(storage, hdr.msg_namelen), Some(addr));
+        }
+
+        let mut buf = [0u8; 16];
+        let buf_ptr = buf.as_ptr();
+        let mut msg = RecvFrom::new(&mut buf);
+        assert_eq!(msg.peer_addr(), None);
+
+        let hdr = unsafe { &mut *msg.as_mut_ptr() };
+        assert_eq!(unsafe { (*hdr.msg_iov).iov_base } as *const u8, buf_ptr);
+        assert_eq!(
+            hdr.msg_namelen as usize,
+            mem::size_of::<libc::sockaddr_storage>()
+        );
+
+        // Fill in the name as the kernel would.
+        let (storage, len) = socket_addr_to_raw(&addrs[0]);
+        unsafe { ptr::write(hdr.msg_name.cast(), storage) };
+        hdr.msg_namelen = len;
+        assert_eq!(msg.peer_addr(), Some(addrs[0]));
+    }
+
+    #[test]
+    fn test_timespec_from_system_time() {
+        use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+        let time = UNIX_EPOCH + Duration::new(1_700_000_000, 123_456_789);
+        let ts = Timespec::try_from(time).unwrap();
+        assert_eq!(ts.0.tv_sec, 1_700_000_000);
+        assert_eq!(ts.0.tv_nsec, 123_456_789);
+
+        assert!(Timespec::try_from(UNIX_EPOCH - Duration::from_secs(1)).is_err());
+        assert!(Timespec::try_from(SystemTime::now()).is_ok());
+    }
+
+    #[test]
+    #[cfg(target_env = "gnu")]
+    fn test_statx_buf() {
+        use std::time::{Duration, UNIX_EPOCH};
+
+        let mut buf = StatxBuf::new();
+        assert_eq!(buf.mask(), 0);
+        assert_eq!(buf.size(), None);
+        assert_eq!(buf.mtime(), None);
+
+        buf.0.stx_mask = libc::STATX_SIZE | libc::STATX_MTIME;
+        buf.0.stx_size = 42;
+        buf.0.stx_mode = 0o100644;
+        buf.0.stx_mtime.tv_sec = -2;
+        buf.0.stx_mtime.tv_nsec = 500;
+        assert_eq!(buf.size(), Some(42));
+        assert_eq!(buf.mode(), None);
+        assert_eq!(
+            buf.mtime(),
+            Some(UNIX_EPOCH - Duration::from_secs(2) + Duration::from_nanos(500))
+        );
+        assert_eq!(
+            buf.as_mut_ptr() as *const libc::statx,
+            buf.as_raw() as *const _
+        );
+    }
+
+    #[test]
+    fn test_user_data() {
+        for (tag, index, gen) in [
+            (0, 0, 0),
+            (1, 2, 3),
+            (u16::MAX, 0, 0),
+            (0, u32::MAX, 0),
+            (0, 0, u16::MAX),
+            (u16::MAX, u32::MAX, u16::MAX),
+            (0x1234, 0xdead_beef, 0x5678),
+        ] {
+            let user_data = UserData::from_parts(tag, index, gen);
+            let raw = u64::from(user_data);
+            let decoded = UserData::from(raw);
+
+            assert_eq!(decoded, user_data);
+            assert_eq!(decoded.tag(), tag);
+            assert_eq!(decoded.index(), index);
+            assert_eq!(decoded.generation(), gen);
+        }
+
+        assert_eq!(
+            u64::from(UserData::from_parts(0x1234, 0xdead_beef, 0x5678)),
+            0x1234_5678_dead_beef
+        );
+    }
+
+    #[test]
+    fn test_msg_hdr() {
+        use std::io::IoSliceMut;
+
+        let (mut head, mut body) = ([0u8; 4], [0u8; 64]);
+        let mut iov = [IoSliceMut::new(&mut head), IoSliceMut::new(&mut body)];
+        let iov_ptr = iov.as_ptr() as *mut libc::iovec;
+        let mut name = [0u8; 16];
+        let name_ptr = name.as_mut_ptr();
+        let mut control = [0u8; 32];
+        let control_ptr = control.as_mut_ptr();
+
+        let mut msg = MsgHdr::new()
+            .iov_mut(&mut iov)
+            .name(&mut name)
+            .control(&mut control);
+        assert_eq!(msg.as_ptr(), msg.as_mut_ptr() as *const _);
+
+        let hdr = unsafe { &*msg.as_ptr() };
+        assert_eq!(hdr.msg_iov, iov_ptr);
+        assert_eq!(hdr.msg_iovlen, 2);
+        assert_eq!(hdr.msg_name, name_ptr.cast());
+        assert_eq!(hdr.msg_namelen, 16);
+        assert_eq!(hdr.msg_control, control_ptr.cast());
+        assert_eq!(hdr.msg_controllen, 32);
+        assert_eq!(hdr.msg_flags, 0);
+
+        let iovecs = unsafe { std::slice::from_raw_parts(hdr.msg_iov, 2) };
+        assert_eq!(iovecs[0].iov_len, 4);
+        assert_eq!(iovecs[1].iov_len, 64);
+
+        assert_eq!(msg.name_len(), 16);
+        assert_eq!(msg.control_len(), 32);
+
+        let empty = MsgHdr::new();
+        let hdr = unsafe { &*empty.as_ptr() };
+        assert!(hdr.msg_iov.is_null());
+        assert!(hdr.msg_name.is_null());
+        assert!(hdr.msg_control.is_null());
+    }
+
+    #[test]
+    fn test_buf_ring() {
+        assert!(BufRing::new(0).is_err());
+        assert!(BufRing::new(6).is_err());
+        assert!(BufRing::new(1 << 15 | 1).is_err());
+
+        let mut bufs = vec![[0u8; 64]; 8];
+        let mut buf_ring = BufRing::new(8).unwrap();
+        assert_eq!(buf_ring.ring_entries(), 8);
+        assert_eq!(buf_ring.ring_addr() % 4096, 0);
+
+        let entries = buf_ring.ring_addr() as *const BufRingEntry;
+        let tail = || unsafe { *BufRingEntry::tail(entries) };
+
+        for (bid, buf) in bufs.iter_mut().enumerate() {
+            unsafe { buf_ring.add(buf.as_mut_ptr(), buf.len() as _, bid as _) };
+        }
+        assert_eq!(tail(), 0);
+
+        buf_ring.advance();
+        assert_eq!(tail(), 8);
+
+        for (bid, buf) in bufs.iter().enumerate() {
+            let entry = unsafe { &*entries.add(bid) };
+            assert_eq!(entry.addr(), buf.as_ptr() as u64);
+            assert_eq!(entry.len(), 64);
+            assert_eq!(entry.bid(), bid as u16);
+        }
+
+        // Buffers that were consumed can be added back, wrapping around the ring.
+        unsafe { buf_ring.add(bufs[3].as_mut_ptr(), 32, 3) };
+        buf_ring.advance();
+        assert_eq!(tail(), 9);
+        let entry = unsafe { &*entries };
+        assert_eq!(entry.addr(), bufs[3].as_ptr() as u64);
+        assert_eq!(entry.len(), 32);
+        assert_eq!(entry.bid(), 3);
+
+        let flags = sys::IORING_CQE_F_BUFFER | (5 << sys::IORING_CQE_BUFFER_SHIFT);
+        assert_eq!(buf_ring.buffer_id(flags), Some(5));
+        assert_eq!(buf_ring.buffer_id(0), None);
     }
 
     #[test]
@@ -708,4 +2124,21 @@ mod tests {
             AsyncCancelFlags::FD | AsyncCancelFlags::FD_FIXED | AsyncCancelFlags::ALL
         );
     }
+
+    #[test]
+    fn test_enter_flags() {
+        use super::EnterFlags;
+
+        let bits = [
+            (EnterFlags::GETEVENTS, 1 << 0),
+            (EnterFlags::SQ_WAKEUP, 1 << 1),
+            (EnterFlags::SQ_WAIT, 1 << 2),
+            (EnterFlags::EXT_ARG, 1 << 3),
+            (EnterFlags::ABS_TIMER, 1 << 5),
+            (EnterFlags::EXT_ARG_REG, 1 << 6),
+        ];
+        for (flag, bit) in bits {
+            assert_eq!(flag.bits(), bit);
+        }
+    }
 }
@@ -11,10 +11,12 @@ pub(crate) mod private {
     pub trait Sealed {}
 }
 
-/// A region of memory mapped using `mmap(2)`.
+/// A region of memory mapped using `mmap(2)`, or provided by the user.
 pub(crate) struct Mmap {
     addr: ptr::NonNull<libc::c_void>,
     len: usize,
+    /// Whether the memory is unmapped on drop.
+    owned: bool,
 }
 
 impl Mmap {
@@ -33,7 +35,52 @@ impl Mmap {
                 addr => {
                     // here, `mmap` will never return null
                     let addr = ptr::NonNull::new_unchecked(addr);
-                    Ok(Mmap { addr, len })
+                    Ok(Mmap {
+                        addr,
+                        len,
+                        owned: true,
+                    })
+                }
+            }
+        }
+    }
+
+    /// Wrap `len` bytes of memory at `addr` that the caller owns, which is not unmapped on drop.
+    ///
+    /// # Safety
+    ///
+    /// The memory must be valid for reads and writes, and outlive the returned value.
+    pub unsafe fn from_user(addr: *mut libc::c_void, len: usize) -> io::Result<Mmap> {
+        match ptr::NonNull::new(addr) {
+            Some(addr) => Ok(Mmap {
+                addr,
+                len,
+                owned: false,
+            }),
+            None => Err(io::Error::from_raw_os_error(libc::EFAULT)),
+        }
+    }
+
+    /// Map `len` bytes of anonymous memory, which is page-aligned and zero-filled.
+    pub fn new_anonymous(len: usize) -> io::Result<Mmap> {
+        unsafe {
+            match libc::mmap(
+                ptr::null_mut(),
+                len,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_PRIVATE | libc::MAP_ANONYMOUS | libc::MAP_POPULATE,
+                -1,
+                0,
+            ) {
+                libc::MAP_FAILED => Err(io::Error::last_os_error()),
+                addr => {
+                    // here, `mmap` will never return null
+                    let addr = ptr::NonNull::new_unchecked(addr);
+                    Ok(Mmap {
+                        addr,
+                        len,
+                        owned: true,
+                    })
                 }
             }
         }
@@ -62,8 +109,10 @@ impl Mmap {
 
 impl Drop for Mmap {
     fn drop(&mut self) {
-        unsafe {
-            libc::munmap(self.addr.as_ptr(), self.len);
+        if self.owned {
+            unsafe {
+                libc::munmap(self.addr.as_ptr(), self.len);
+            }
         }
     }
 }
@@ -115,6 +164,70 @@ mod fd {
     }
 }
 
+/// The ring fd registered with `IORING_REGISTER_RING_FDS`, and the thread that registered it.
+///
+/// The kernel keeps registered ring fds per task, so the index only refers to this ring on the
+/// registering thread. On any other thread, it is either unused or refers to another ring.
+pub(crate) struct RegisteredRingFd {
+    /// The registered index, or -1.
+    index: atomic::AtomicI32,
+    /// The id of the registering thread, as returned by `thread_id`, or 0.
+    owner: atomic::AtomicU64,
+}
+
+/// A unique, non-zero id for the calling thread. Unlike a tid, it is never reused for another
+/// thread once this one exits.
+fn thread_id() -> u64 {
+    static NEXT_ID: atomic::AtomicU64 = atomic::AtomicU64::new(1);
+
+    thread_local! {
+        static ID: u64 = NEXT_ID.fetch_add(1, atomic::Ordering::Relaxed);
+    }
+
+    ID.with(|id| *id)
+}
+
+impl RegisteredRingFd {
+    pub const fn new() -> RegisteredRingFd {
+        RegisteredRingFd {
+            index: atomic::AtomicI32::new(-1),
+            owner: atomic::AtomicU64::new(0),
+        }
+    }
+
+    /// Whether the ring fd is registered, by any thread.
+    #[inline]
+    pub fn is_registered(&self) -> bool {
+        self.index.load(atomic::Ordering::Relaxed) != -1
+    }
+
+    /// The registered index, if it was registered by the calling thread.
+    #[inline]
+    pub fn get(&self) -> Option<u32> {
+        // Only the owner can observe its own id here, and it reads its own writes in order.
+        if self.owner.load(atomic::Ordering::Relaxed) != thread_id() {
+            return None;
+        }
+        match self.index.load(atomic::Ordering::Relaxed) {
+            -1 => None,
+            index => Some(index as u32),
+        }
+    }
+
+    /// Record `index` as registered by the calling thread.
+    #[inline]
+    pub fn set(&self, index: u32) {
+        self.owner.store(thread_id(), atomic::Ordering::Relaxed);
+        self.index.store(index as i32, atomic::Ordering::Relaxed);
+    }
+
+    #[inline]
+    pub fn clear(&self) {
+        self.index.store(-1, atomic::Ordering::Relaxed);
+        self.owner.store(0, atomic::Ordering::Relaxed);
+    }
+}
+
 #[inline(always)]
 pub(crate) unsafe fn unsync_load(u: *const atomic::AtomicU32) -> u32 {
     *u.cast::<u32>()